@@ -2,8 +2,8 @@
 //!
 //! You can run this with the following command:
 //!
-//! `TOKEN=<TOKEN> RUST_LOG=tes=debug cargo run --release --features=client,serde
-//! --example service-info <URL>`
+//! `USERNAME=<USERNAME> PASSWORD=<PASSWORD> RUST_LOG=tes=debug cargo run
+//! --release --features=client,serde --example service-info <URL>`
 
 use anyhow::Context;
 use anyhow::Result;
@@ -20,10 +20,14 @@ async fn main() -> Result<()> {
 
     let mut builder = client::Builder::default()
         .url_from_string(url)
-        .expect("url could not be parsed");
+        .expect("url could not be parsed")
+        .retry_strategy(client::strategy::default_backoff());
 
-    if let Ok(token) = std::env::var("TOKEN") {
-        builder = builder.insert_header("Authorization", format!("Basic {}", token));
+    if let Ok(username) = std::env::var("USERNAME") {
+        let password = std::env::var("PASSWORD").ok();
+        builder = builder
+            .basic_auth(username, password)
+            .context("setting basic authentication credentials")?;
     }
 
     let client = builder.try_build().expect("could not build client");
@@ -2,12 +2,13 @@
 //!
 //! You can run this with the following command:
 //!
-//! `TOKEN=<TOKEN> RUST_LOG=tes=debug cargo run --release --features=client,serde
-//! --example task-list-all <URL>`
+//! `USERNAME=<USERNAME> PASSWORD=<PASSWORD> RUST_LOG=tes=debug cargo run
+//! --release --features=client,serde --example task-list-all <URL>`
 
 use anyhow::Context;
 use anyhow::Result;
 use tes::v1::client;
+use tes::v1::client::tasks::ListTasksParams;
 use tes::v1::client::tasks::View;
 use tracing_subscriber::EnvFilter;
 
@@ -21,10 +22,14 @@ async fn main() -> Result<()> {
 
     let mut builder = client::Builder::default()
         .url_from_string(url)
-        .expect("url could not be parsed");
-
-    if let Ok(token) = std::env::var("TOKEN") {
-        builder = builder.insert_header("Authorization", format!("Basic {}", token));
+        .expect("url could not be parsed")
+        .retry_strategy(client::strategy::default_backoff());
+
+    if let Ok(username) = std::env::var("USERNAME") {
+        let password = std::env::var("PASSWORD").ok();
+        builder = builder
+            .basic_auth(username, password)
+            .context("setting basic authentication credentials")?;
     }
 
     let client = builder.try_build().expect("could not build client");
@@ -32,7 +37,7 @@ async fn main() -> Result<()> {
     println!(
         "{:#?}",
         client
-            .list_all_tasks(View::Full)
+            .list_all_tasks(ListTasksParams::builder().view(View::Full).build())
             .await
             .context("listing all tasks")?
     );
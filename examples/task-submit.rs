@@ -2,8 +2,8 @@
 //!
 //! You can run this with the following command:
 //!
-//! `TOKEN=<TOKEN> RUST_LOG=tes=debug cargo run --release --features=client,serde
-//! --example task-submit <URL>`
+//! `USERNAME=<USERNAME> PASSWORD=<PASSWORD> RUST_LOG=tes=debug cargo run
+//! --release --features=client,serde --example task-submit <URL>`
 
 use anyhow::Context;
 use anyhow::Result;
@@ -23,33 +23,33 @@ async fn main() -> Result<()> {
 
     let mut builder = client::Builder::default()
         .url_from_string(url)
-        .expect("url could not be parsed");
-
-    if let Ok(token) = std::env::var("TOKEN") {
-        builder = builder.insert_header("Authorization", format!("Basic {}", token));
+        .expect("url could not be parsed")
+        .retry_strategy(client::strategy::default_backoff());
+
+    if let Ok(username) = std::env::var("USERNAME") {
+        let password = std::env::var("PASSWORD").ok();
+        builder = builder
+            .basic_auth(username, password)
+            .context("setting basic authentication credentials")?;
     }
 
     let client = builder.try_build().expect("could not build client");
 
-    let task = Task {
-        name: Some(String::from("my-task")),
-        description: Some(String::from("A description.")),
-        resources: Some(Resources {
+    let task = Task::builder()
+        .name("my-task")
+        .description("A description.")
+        .resources(Resources {
             cpu_cores: Some(4),
             preemptible: Some(true),
             ..Default::default()
-        }),
-        executors: vec![Executor {
-            image: String::from("ubuntu:latest"),
-            command: vec![
-                String::from("/bin/bash"),
-                String::from("-c"),
-                String::from("echo 'hello, world!'"),
-            ],
-            ..Default::default()
-        }],
-        ..Default::default()
-    };
+        })
+        .executor(
+            Executor::from_shell("ubuntu:latest", "echo 'hello, world!'")
+                .try_build()
+                .context("building an executor")?,
+        )
+        .try_build()
+        .context("building a task")?;
 
     println!(
         "{:#?}",
@@ -17,4 +17,11 @@
 //!     https://editor.swagger.io/?url=https://ga4gh.github.io/task-execution-schemas/openapi.yaml
 #![doc = include_str!("../docs/FEATURES.md")]
 
+#[cfg(all(
+    target_arch = "wasm32",
+    feature = "client",
+    not(feature = "client-wasm")
+))]
+compile_error!("the `client-wasm` feature must be enabled to use the client on `wasm32` targets");
+
 pub mod v1;
@@ -1,31 +1,111 @@
 //! A client for interacting with a Task Execution Service (TES) service.
 
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::Stream;
+use futures_util::StreamExt as _;
+use futures_util::TryStreamExt as _;
+use futures_util::future;
+use futures_util::stream;
+use reqwest::Method;
+use reqwest::StatusCode;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::WWW_AUTHENTICATE;
 use reqwest_middleware::ClientWithMiddleware as ReqwestClient;
 use serde::Deserialize;
 use serde::Serialize;
 use tracing::debug;
 use tracing::trace;
+use tracing::warn;
 use url::Url;
 
+use crate::v1::client::breaker::CircuitBreaker;
+use crate::v1::client::cancel::CancelOptions;
+use crate::v1::client::cancel::CancelReport;
+use crate::v1::client::handle::TaskHandle;
+use crate::v1::client::hedge::Hedge;
+use crate::v1::client::metrics::CallRecord;
+use crate::v1::client::metrics::RequestMetrics;
+use crate::v1::client::metrics::RequestRecord;
+use crate::v1::client::redact::Redactor;
+use crate::v1::client::resubmit::Resubmission;
+use crate::v1::client::resubmit::ResubmitPolicy;
+use crate::v1::client::retry::Attempt;
+use crate::v1::client::retry::History;
+use crate::v1::client::retry::Operation;
+use crate::v1::client::retry::RequestOutcome;
+use crate::v1::client::retry::RetryEvent;
+use crate::v1::client::retry::Retryability;
+use crate::v1::client::strategy::RetryConfig;
+use crate::v1::client::tasks::ListTasksParams;
 use crate::v1::client::tasks::View;
+use crate::v1::client::wait::LogChunk;
+use crate::v1::client::wait::LogTail;
+use crate::v1::client::wait::PollPolicy;
+use crate::v1::client::wait::StateChange;
+use crate::v1::client::wait::WaitOptions;
+use crate::v1::client::wait::WaitProgress;
 use crate::v1::types::Task;
+use crate::v1::types::requests::PageToken;
 use crate::v1::types::responses::CreateTask;
 use crate::v1::types::responses::ListTasks;
 use crate::v1::types::responses::ServiceInfo;
 use crate::v1::types::responses::task;
 use crate::v1::types::responses::task::MinimalTask;
+use crate::v1::types::task::State;
+use crate::v1::types::task::validation;
 
-mod builder;
+pub mod auth;
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-blocking"))]
+pub mod blocking;
+mod breaker;
+pub mod builder;
+pub mod cancel;
+#[cfg(all(not(target_arch = "wasm32"), feature = "client-download"))]
+pub mod download;
+pub mod handle;
+mod hedge;
+mod message;
+pub mod metrics;
+#[cfg(feature = "client-oauth")]
+pub mod oauth;
 mod options;
+mod redact;
+pub mod resubmit;
+pub mod retry;
+pub mod strategy;
 pub mod tasks;
+#[cfg(test)]
+mod test_server;
+pub mod wait;
 
 pub use builder::Builder;
+pub use builder::DEFAULT_IDEMPOTENCY_TAG;
+pub use builder::DEFAULT_USER_AGENT;
 pub use options::Options;
 
 /// An error within the client.
+///
+/// New variants may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    /// An error when serializing or deserializing JSON.
+    /// An error when obtaining credentials for a request.
+    Auth(auth::BoxError),
+
+    /// An error when serializing the body of a request to JSON.
+    ///
+    /// Errors when deserializing the body of a response are reported as
+    /// [`Error::Decode`].
     SerdeJSON(serde_json::Error),
 
     /// A middleware error from `reqwest_middleware`.
@@ -34,230 +114,4634 @@ pub enum Error {
 
     /// An error from `reqwest`.
     Reqwest(reqwest::Error),
+
+    /// The service kept responding with a transient error status (e.g., `429
+    /// Too Many Requests` or `503 Service Unavailable`) until all retries were
+    /// exhausted.
+    RetriesExhausted {
+        /// The status of the final response.
+        status: StatusCode,
+
+        /// The number of retries that were made.
+        retries: u32,
+    },
+
+    /// The request still failed after it was retried.
+    ///
+    /// This wraps the error from the final attempt (use [`Error::inner()`] to
+    /// get it) and records every failed attempt (see [`Error::attempts()`]).
+    Retried {
+        /// The failed attempts (including the final attempt, if one was made).
+        attempts: Vec<Attempt>,
+
+        /// How long the request took (including every attempt and the delays
+        /// between them).
+        elapsed: Duration,
+
+        /// The error from the final attempt.
+        last: Box<Error>,
+    },
+
+    /// The retry deadline elapsed before the request succeeded.
+    DeadlineExceeded {
+        /// The retry deadline.
+        deadline: Duration,
+
+        /// The error from the final attempt.
+        last: Box<Error>,
+    },
+
+    /// The service responded with an unsuccessful status.
+    Http {
+        /// The status of the response.
+        status: StatusCode,
+
+        /// The URL of the request.
+        url: Box<Url>,
+
+        /// The beginning of the body of the response (up to
+        /// [`MAX_BODY_SNIPPET_LEN`] bytes).
+        body: String,
+
+        /// The message extracted from the body of the response (if it is one
+        /// of the JSON shapes used by known TES implementations).
+        ///
+        /// See [`Error::server_message()`].
+        message: Option<String>,
+    },
+
+    /// The service rejected the credentials of the request (i.e., it
+    /// responded with `401 Unauthorized` or `403 Forbidden`).
+    ///
+    /// These responses are never retried by the
+    /// [`DefaultRetryPolicy`](retry::DefaultRetryPolicy).
+    Unauthorized {
+        /// The status of the response.
+        status: StatusCode,
+
+        /// The value of the `WWW-Authenticate` header of the response (which
+        /// often describes the realm or the required scope).
+        www_authenticate: Option<String>,
+
+        /// The beginning of the body of the response (up to
+        /// [`MAX_BODY_SNIPPET_LEN`] bytes).
+        body: String,
+
+        /// The message extracted from the body of the response.
+        ///
+        /// See [`Error::server_message()`].
+        message: Option<String>,
+    },
+
+    /// The service does not know of the requested task (i.e., it responded
+    /// with `404 Not Found`).
+    TaskNotFound {
+        /// The ID of the task.
+        id: String,
+    },
+
+    /// The service returned a page token that it had already returned while
+    /// listing tasks, so listing would never end.
+    ///
+    /// The page token itself is not included, as page tokens may be
+    /// sensitive.
+    PaginationLoop {
+        /// The number of the page (starting from one) that would have been
+        /// requested again.
+        page: usize,
+    },
+
+    /// Listing tasks returned more tasks than the requested maximum.
+    ///
+    /// See [`Client::list_all_tasks_with_limit()`].
+    TooManyTasks {
+        /// The maximum number of tasks.
+        max: usize,
+    },
+
+    /// A task did not finish before the timeout of waiting for it elapsed.
+    ///
+    /// See [`Client::wait_for_task()`].
+    WaitTimeout {
+        /// The ID of the task.
+        id: String,
+
+        /// How long the client waited for the task.
+        timeout: Duration,
+
+        /// The last state of the task that was observed (if any).
+        last_state: Option<State>,
+    },
+
+    /// A task did not finish before its deadline, so it was canceled.
+    ///
+    /// See [`Client::run_with_deadline()`].
+    TaskDeadlineExceeded {
+        /// The ID of the task.
+        id: String,
+
+        /// The deadline of the task.
+        deadline: Duration,
+
+        /// The last state of the task that was observed (if any).
+        last_state: Option<State>,
+    },
+
+    /// The body of a response could not be deserialized.
+    ///
+    /// The request that failed is reported by the enclosing [`Error::Request`].
+    Decode {
+        /// The status of the response.
+        status: StatusCode,
+
+        /// The beginning of the body of the response (up to
+        /// [`MAX_BODY_SNIPPET_LEN`] bytes).
+        body: String,
+
+        /// The path within the JSON body at which deserialization failed
+        /// (e.g., `tasks[3].logs[0].logs[1].exit_code`).
+        path: String,
+
+        /// Whether the service returned an explicit `null` at `path`.
+        ///
+        /// Some services return `null` for fields that the TES specification
+        /// requires to be present (e.g., `"logs": null`), which is a common
+        /// cause of these errors.
+        null: bool,
+
+        /// The error from deserializing the body.
+        source: serde_json::Error,
+    },
+
+    /// The circuit breaker is open because the service failed repeatedly, so
+    /// the request was not sent.
+    CircuitOpen,
+
+    /// The arguments of a request are invalid, so the request was not sent.
+    Validation(ValidationError),
+
+    /// An error when converting a retrieved task into a submittable task.
+    Task(task::Error),
+
+    /// An error from a request to an endpoint within the service.
+    ///
+    /// Every error from a request is wrapped within this variant to record
+    /// which request failed. Use [`Error::inner()`] to get the underlying
+    /// error.
+    Request {
+        /// The method of the request.
+        method: Method,
+
+        /// The path of the request (without the query string, which may
+        /// contain sensitive values such as page tokens).
+        path: String,
+
+        /// The underlying error.
+        source: Box<Error>,
+    },
+
+    /// An error while running a task with resubmission.
+    ///
+    /// See [`Client::run_with_resubmission()`]. Use [`Error::inner()`] to get
+    /// the underlying error.
+    Resubmission {
+        /// The IDs of the tasks that were created before the error (from the
+        /// first attempt to the last), which may still be executing.
+        attempts: Vec<String>,
+
+        /// The underlying error.
+        source: Box<Error>,
+    },
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::SerdeJSON(err) => write!(f, "json serde error: {err}"),
+            Error::Auth(err) => write!(f, "authentication error: {err}"),
+            Error::SerdeJSON(err) => write!(f, "failed to serialize the request body: {err}"),
             Error::Middlware(err) => write!(f, "middleware error: {err}"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Error::Reqwest(err) if err.is_connect() => {
+                // NOTE: the underlying cause (e.g., a failure to verify the
+                // server's certificate) is left to the source chain, so that
+                // reporters walking the chain do not print it twice.
+                let host = err
+                    .url()
+                    .and_then(|url| url.host_str())
+                    .unwrap_or("<unknown>");
+                write!(f, "failed to connect to `{host}`: {err}")
+            }
             Error::Reqwest(err) => write!(f, "reqwest error: {err}"),
+            Error::RetriesExhausted { status, retries } => {
+                write!(f, "service responded with {status} after {retries} retries")
+            }
+            Error::Retried {
+                attempts,
+                elapsed,
+                last,
+            } => {
+                write!(
+                    f,
+                    "failed after {} attempts in {:.1}s (",
+                    attempts.len(),
+                    elapsed.as_secs_f64()
+                )?;
+
+                for (i, attempt) in attempts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", attempt.reason())?;
+                }
+
+                write!(f, "): {last}")
+            }
+            Error::DeadlineExceeded { deadline, last } => {
+                write!(f, "retry deadline of {deadline:?} exceeded: {last}")
+            }
+            // NOTE: the URL is omitted as its query string may contain
+            // sensitive values (the path is reported by `Error::Request`).
+            Error::Http { status, body, .. } if body.is_empty() => {
+                write!(f, "service responded with {status}")
+            }
+            Error::Http { status, body, .. } => {
+                write!(f, "service responded with {status}: {body}")
+            }
+            Error::Unauthorized {
+                status,
+                www_authenticate,
+                body,
+                ..
+            } => {
+                write!(f, "service rejected the credentials with {status}")?;
+
+                if let Some(challenge) = www_authenticate {
+                    write!(f, " (WWW-Authenticate: {challenge})")?;
+                }
+
+                if !body.is_empty() {
+                    write!(f, ": {body}")?;
+                }
+
+                Ok(())
+            }
+            Error::TaskNotFound { id } => write!(f, "task `{id}` was not found"),
+            Error::PaginationLoop { page } => write!(
+                f,
+                "the service returned the token for page {page} more than once while listing tasks"
+            ),
+            Error::TooManyTasks { max } => {
+                write!(f, "the service listed more than the maximum of {max} tasks")
+            }
+            Error::WaitTimeout {
+                id,
+                timeout,
+                last_state,
+            } => {
+                write!(
+                    f,
+                    "task `{id}` did not finish within {:.1}s",
+                    timeout.as_secs_f64()
+                )?;
+
+                if let Some(state) = last_state {
+                    write!(f, " (last state: {state})")?;
+                }
+
+                Ok(())
+            }
+            Error::TaskDeadlineExceeded {
+                id,
+                deadline,
+                last_state,
+            } => {
+                write!(
+                    f,
+                    "task `{id}` did not finish within its deadline of {:.1}s and was canceled",
+                    deadline.as_secs_f64()
+                )?;
+
+                if let Some(state) = last_state {
+                    write!(f, " (last state: {state})")?;
+                }
+
+                Ok(())
+            }
+            Error::Decode {
+                status,
+                body,
+                path,
+                null: true,
+                ..
+            } => write!(
+                f,
+                "failed to decode the response ({status}): the service returned null for \
+                 `{path}`, which the TES specification does not allow; body: {body:?}"
+            ),
+            Error::Decode {
+                status,
+                body,
+                path,
+                source,
+                ..
+            } => write!(
+                f,
+                "failed to decode the response ({status}) at `{path}`: {source}; body: {body:?}"
+            ),
+            Error::CircuitOpen => write!(
+                f,
+                "circuit breaker is open after repeated failures of the service"
+            ),
+            Error::Task(err) => write!(f, "task error: {err}"),
+            Error::Validation(err) => write!(f, "invalid request: {err}"),
+            Error::Request {
+                method,
+                path,
+                source,
+            } => write!(f, "{method} {path}: {source}"),
+            Error::Resubmission { attempts, source } if attempts.is_empty() => {
+                write!(
+                    f,
+                    "failed to create the first attempt of the task: {source}"
+                )
+            }
+            Error::Resubmission { attempts, source } => {
+                write!(f, "failed after attempting task(s) ")?;
+
+                for (i, id) in attempts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "`{id}`")?;
+                }
+
+                write!(f, ": {source}")
+            }
         }
     }
 }
 
-impl std::error::Error for Error {}
-
-/// A [`Result`](std::result::Result) with an [`Error`].
-type Result<T> = std::result::Result<T, Error>;
-
-impl From<reqwest_middleware::Error> for Error {
-    fn from(value: reqwest_middleware::Error) -> Self {
-        match value {
-            reqwest_middleware::Error::Middleware(err) => Error::Middlware(err),
-            reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Auth(err) => Some(err.as_ref()),
+            Error::SerdeJSON(err) => Some(err),
+            Error::Middlware(err) => Some(err.as_ref()),
+            Error::Reqwest(err) => Some(err),
+            Error::Retried { last, .. } | Error::DeadlineExceeded { last, .. } => {
+                Some(last.as_ref())
+            }
+            Error::Decode { source, .. } => Some(source),
+            Error::Task(err) => Some(err),
+            Error::Validation(err) => Some(err),
+            Error::Request { source, .. } | Error::Resubmission { source, .. } => {
+                Some(source.as_ref())
+            }
+            Error::RetriesExhausted { .. }
+            | Error::Http { .. }
+            | Error::Unauthorized { .. }
+            | Error::TaskNotFound { .. }
+            | Error::PaginationLoop { .. }
+            | Error::TooManyTasks { .. }
+            | Error::WaitTimeout { .. }
+            | Error::TaskDeadlineExceeded { .. }
+            | Error::CircuitOpen => None,
         }
     }
 }
 
-/// A client for interacting with a service.
-#[derive(Debug)]
-pub struct Client {
-    /// The base URL.
-    url: Url,
+#[cfg(feature = "diagnostics")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self.inner() {
+            Error::Auth(_) => "tes::client::auth",
+            Error::SerdeJSON(_) => "tes::client::serialize",
+            Error::Middlware(_) => "tes::client::middleware",
+            Error::Reqwest(err) if retry::is_connect(err) => "tes::client::connect",
+            Error::Reqwest(err) if err.is_timeout() => "tes::client::timeout",
+            Error::Reqwest(_) => "tes::client::reqwest",
+            Error::RetriesExhausted { .. } => "tes::client::retries_exhausted",
+            Error::DeadlineExceeded { .. } => "tes::client::deadline_exceeded",
+            Error::Http { .. } => "tes::client::http",
+            Error::Unauthorized { .. } => "tes::client::unauthorized",
+            Error::TaskNotFound { .. } => "tes::client::task_not_found",
+            Error::PaginationLoop { .. } => "tes::client::pagination_loop",
+            Error::TooManyTasks { .. } => "tes::client::too_many_tasks",
+            Error::WaitTimeout { .. } => "tes::client::wait_timeout",
+            Error::TaskDeadlineExceeded { .. } => "tes::client::task_deadline_exceeded",
+            Error::Decode { .. } => "tes::client::decode",
+            Error::CircuitOpen => "tes::client::circuit_open",
+            Error::Task(_) => "tes::client::task",
+            Error::Validation(err) => return err.code(),
+            Error::Retried { .. } | Error::Request { .. } | Error::Resubmission { .. } => {
+                unreachable!("these errors are unwrapped by `inner()`")
+            }
+        };
 
-    /// The underlying client.
-    client: ReqwestClient,
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let help = match self.inner() {
+            Error::Reqwest(err) if retry::is_connect(err) => {
+                "check that the URL is correct and that the service is running"
+            }
+            Error::RetriesExhausted { .. } | Error::DeadlineExceeded { .. } => {
+                "the service may be overloaded or unavailable: try again later"
+            }
+            Error::Unauthorized { .. } => {
+                "check that the credentials (e.g., `$USERNAME`/`$PASSWORD` or the bearer token) \
+                 are set and have not expired"
+            }
+            Error::TaskNotFound { .. } => "check the ID of the task",
+            Error::PaginationLoop { .. } => {
+                "the service does not conform to the TES specification: report the repeated page \
+                 token to the maintainers of the service"
+            }
+            Error::TooManyTasks { .. } => "narrow down the listing or raise the maximum",
+            Error::WaitTimeout { .. } => {
+                "the task may still be running: wait for it again or raise the timeout"
+            }
+            Error::TaskDeadlineExceeded { .. } => {
+                "the task was canceled: inspect its logs or raise the deadline"
+            }
+            Error::Decode { null: true, .. } => {
+                "the service does not conform to the TES specification: report the null field to \
+                 the maintainers of the service"
+            }
+            // NOTE: a JSON object with an unexpected value is most likely a
+            // TES service that does not conform to the specification, while
+            // anything else is most likely not a TES service at all.
+            Error::Decode {
+                body, path, source, ..
+            } if source.is_data() && body.trim_start().starts_with('{') => {
+                return Some(Box::new(format!(
+                    "the service does not conform to the TES specification: report the value at \
+                     `{path}` to the maintainers of the service"
+                )));
+            }
+            Error::Decode { .. } => {
+                "the URL may be missing the `/ga4gh/tes/v1` prefix, or the service may not be a \
+                 TES service"
+            }
+            Error::CircuitOpen => {
+                "the service failed repeatedly: wait for it to recover before trying again"
+            }
+            Error::Validation(err) => return err.help(),
+            _ => return None,
+        };
+
+        Some(Box::new(help))
+    }
 }
 
-impl Client {
-    /// Gets an empty builder for a [`Client`].
-    pub fn builder() -> Builder {
-        Builder::default()
+impl From<ValidationError> for Error {
+    fn from(err: ValidationError) -> Self {
+        Error::Validation(err)
     }
+}
 
-    /// Performs a `GET` request on an endpoint within the service.
-    ///
-    /// # Safety
+/// A problem with the arguments of a request that is detected before the
+/// request is sent.
+///
+/// New variants may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The requested page size exceeds the maximum (inclusive) page size.
+    PageSizeTooLarge {
+        /// The requested page size.
+        given: u32,
+
+        /// The maximum page size.
+        max: u32,
+    },
+
+    /// More tag values than tag keys were provided when filtering tasks.
     ///
-    /// Because calls to `get()` are all local to this crate, the provided
-    /// `endpoint` is assumed to always be joinable to the base URL without
-    /// issue.
-    async fn get<Response>(&self, endpoint: impl AsRef<str>) -> Result<Response>
-    where
-        Response: for<'de> Deserialize<'de>,
-    {
-        let endpoint = endpoint.as_ref();
+    /// Each tag value is matched against the tag key at the same position, so
+    /// there cannot be more values than keys.
+    TagFilterArityMismatch {
+        /// The number of tag keys.
+        keys: usize,
 
-        // SAFETY: as described in the documentation for this method, the URL is
-        // already validated upon creationg of the [`Client`], and the
-        // `endpoint` is assumed to always be joinable to that URL, so this
-        // should always unwrap.
-        let url = self.url.join(endpoint).unwrap();
-        debug!("GET {url}");
+        /// The number of tag values.
+        values: usize,
+    },
 
-        let bytes = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(Error::from)?
-            .bytes()
-            .await
-            .map_err(Error::Reqwest)?;
+    /// A task violates the structure required by the specification (see
+    /// [`Task::validate()`]).
+    InvalidTask(Vec<validation::Error>),
+}
 
-        trace!("{bytes:?}");
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::PageSizeTooLarge { given, max } => {
+                write!(f, "page size of {given} exceeds the maximum of {max}")
+            }
+            ValidationError::TagFilterArityMismatch { keys, values } => write!(
+                f,
+                "{values} tag value(s) were provided for only {keys} tag key(s)"
+            ),
+            ValidationError::InvalidTask(errors) => {
+                write!(f, "invalid task: ")?;
+
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+
+                    write!(f, "{error}")?;
+                }
 
-        serde_json::from_slice(&bytes).map_err(Error::SerdeJSON)
+                Ok(())
+            }
+        }
     }
+}
 
-    /// Performs a `POST1` request on an endpoint within the service.
-    ///
-    /// # Safety
-    ///
-    /// Because calls to `post()` are all local to this crate, the provided
-    /// `endpoint` is assumed to always be joinable to the base URL without
-    /// issue.
-    async fn post<Body, Response>(&self, endpoint: impl AsRef<str>, body: Body) -> Result<Response>
-    where
-        Body: Serialize,
-        Response: for<'de> Deserialize<'de>,
-    {
-        let endpoint = endpoint.as_ref();
-        let body = serde_json::to_string(&body).map_err(Error::SerdeJSON)?;
+impl std::error::Error for ValidationError {}
 
-        // SAFETY: as described in the documentation for this method, the URL is
-        // already validated upon creationg of the [`Client`], and the
-        // `endpoint` is assumed to always be joinable to that URL, so this
-        // should always unwrap.
-        let url = self.url.join(endpoint).unwrap();
-        debug!("POST {url} {body}");
+#[cfg(feature = "diagnostics")]
+impl miette::Diagnostic for ValidationError {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self {
+            ValidationError::PageSizeTooLarge { .. } => "tes::client::validation::page_size",
+            ValidationError::TagFilterArityMismatch { .. } => {
+                "tes::client::validation::tag_filter_arity"
+            }
+            ValidationError::InvalidTask(_) => "tes::client::validation::invalid_task",
+        };
 
-        self.client
-            .post(url)
-            .body(body)
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(Error::from)?
-            .json::<Response>()
-            .await
-            .map_err(Error::Reqwest)
+        Some(Box::new(code))
     }
 
-    /// Gets the service information.
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        match self {
+            ValidationError::PageSizeTooLarge { max, .. } => {
+                Some(Box::new(format!("use a page size of at most {max}")))
+            }
+            ValidationError::TagFilterArityMismatch { .. } => {
+                Some(Box::new("provide a tag key for every tag value"))
+            }
+            ValidationError::InvalidTask(_) => Some(Box::new(
+                "fix the listed fields of the task before submitting it",
+            )),
+        }
+    }
+}
+
+/// A potential problem with a task that is detected before it is submitted.
+///
+/// Unlike a [`ValidationError`], a warning does not mean that the service will
+/// reject the task, as services do not always advertise everything they
+/// support.
+///
+/// New variants may be added in future releases, so matches on this warning
+/// should include a wildcard (`_`) arm.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValidationWarning {
+    /// The scheme of the URL of an input or output is not among the storage
+    /// advertised by the service.
+    UnsupportedStorage {
+        /// The URL.
+        url: String,
+
+        /// The storage advertised by the service.
+        supported: Vec<String>,
+    },
+
+    /// The structure of the task has a violation that is only a warning by
+    /// default (see [`Task::validate_with()`]).
+    Task(validation::Error),
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::UnsupportedStorage { url, supported } => write!(
+                f,
+                "the storage of `{url}` is not advertised by the service (which advertises {})",
+                supported.join(", ")
+            ),
+            ValidationWarning::Task(warning) => write!(f, "{warning}"),
+        }
+    }
+}
+
+/// Gets the scheme of a URL (or of an advertised storage location).
+///
+/// A storage location without a `://` separator is taken to be a scheme.
+fn storage_scheme(url: &str) -> &str {
+    url.split_once("://")
+        .map(|(scheme, _)| scheme)
+        .unwrap_or(url)
+}
+
+impl Error {
+    /// Gets the method and path of the request that failed (if the error came
+    /// from a request).
     ///
-    /// This method makes a request to the `GET /service-info` endpoint.
-    pub async fn service_info(&self) -> Result<ServiceInfo> {
-        self.get("./service-info").await
+    /// The path never includes the query string.
+    pub fn endpoint(&self) -> Option<(&Method, &str)> {
+        match self {
+            Error::Request { method, path, .. } => Some((method, path)),
+            _ => None,
+        }
     }
 
-    /// Lists a single page of tasks within the service.
+    /// Gets the underlying error (without the context of the request that
+    /// failed).
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::Request { source, .. } | Error::Resubmission { source, .. } => source.inner(),
+            Error::Retried { last, .. } => last.inner(),
+            err => err,
+        }
+    }
+
+    /// Gets the failed attempts of a request that was retried.
     ///
-    /// This method makes a request to the `GET /tasks` endpoint.
-    pub async fn list_tasks(
-        &self,
-        view: &View,
-        next_token: Option<&str>,
-    ) -> Result<ListTasks<task::Response>> {
-        let mut url = format!("./tasks?view={view}");
+    /// Each attempt reports why it failed and how long the client waited
+    /// before the next attempt. This is empty if the request was not retried.
+    pub fn attempts(&self) -> &[Attempt] {
+        match self {
+            Error::Request { source, .. } => source.attempts(),
+            Error::Retried { attempts, .. } => attempts,
+            _ => &[],
+        }
+    }
 
-        if let Some(token) = next_token {
-            url.push_str("&page_token=");
-            url.push_str(token);
+    /// Gets how long a request that was retried took (including every
+    /// attempt and the delays between them).
+    ///
+    /// This is `None` if the request was not retried.
+    pub fn elapsed(&self) -> Option<Duration> {
+        match self {
+            Error::Request { source, .. } => source.elapsed(),
+            Error::Retried { elapsed, .. } => Some(*elapsed),
+            _ => None,
         }
+    }
 
-        match view {
-            View::Minimal => {
-                let results = self.get::<ListTasks<MinimalTask>>(url).await?;
-
-                Ok(ListTasks {
-                    next_page_token: results.next_page_token,
-                    tasks: results
-                        .tasks
-                        .into_iter()
-                        .map(task::Response::Minimal)
-                        .collect::<Vec<_>>(),
-                })
-            }
-            View::Basic => {
-                let results = self.get::<ListTasks<Task>>(url).await?;
+    /// Records the failed attempts of a request (if it was retried).
+    fn retried(self, history: &History) -> Self {
+        if !history.is_retried() {
+            return self;
+        }
 
-                Ok(ListTasks {
-                    next_page_token: results.next_page_token,
-                    tasks: results
-                        .tasks
-                        .into_iter()
-                        .map(task::Response::Basic)
-                        .collect::<Vec<_>>(),
-                })
-            }
-            View::Full => {
-                let results = self.get::<ListTasks<Task>>(url).await?;
+        Error::Retried {
+            attempts: history.failures.clone(),
+            elapsed: history.start.elapsed(),
+            last: Box::new(self),
+        }
+    }
 
-                Ok(ListTasks {
-                    next_page_token: results.next_page_token,
-                    tasks: results
-                        .tasks
-                        .into_iter()
-                        .map(task::Response::Full)
-                        .collect::<Vec<_>>(),
-                })
-            }
+    /// Gets the status of the response that caused the error (if the service
+    /// responded).
+    pub fn status(&self) -> Option<StatusCode> {
+        match self.inner() {
+            Error::Reqwest(err) => err.status(),
+            Error::RetriesExhausted { status, .. }
+            | Error::Http { status, .. }
+            | Error::Unauthorized { status, .. }
+            | Error::Decode { status, .. } => Some(*status),
+            Error::TaskNotFound { .. } => Some(StatusCode::NOT_FOUND),
+            Error::DeadlineExceeded { last, .. } => last.status(),
+            _ => None,
         }
     }
 
-    /// Lists all tasks within the service.
+    /// Gets the message reported by the service within the body of an
+    /// unsuccessful response.
     ///
-    /// This method is a convenience wrapper around [`Self::list_tasks()`] that
-    /// submits follow on requests and the server says there are more results.
-    pub async fn list_all_tasks(&self, view: View) -> Result<Vec<task::Response>> {
-        let mut results = Vec::new();
-        let mut next_token = None;
-        let mut page = 1usize;
+    /// The message is extracted from the JSON error bodies of known TES
+    /// implementations (e.g., Funnel, TESK, and Microsoft TES). Otherwise,
+    /// this falls back to the beginning of the body itself (if it is not
+    /// empty).
+    pub fn server_message(&self) -> Option<&str> {
+        match self.inner() {
+            Error::Http { body, message, .. } | Error::Unauthorized { body, message, .. } => {
+                message
+                    .as_deref()
+                    .or_else(|| Some(body.trim()).filter(|body| !body.is_empty()))
+            }
+            Error::DeadlineExceeded { last, .. } => last.server_message(),
+            _ => None,
+        }
+    }
 
-        loop {
-            debug!("reading task page {page} with token {next_token:?}",);
+    /// Returns whether the error is a timeout.
+    ///
+    /// This includes a request timing out, the
+    /// [retry deadline](Builder::retry_deadline) being exceeded, the timeout of
+    /// [waiting for a task](Client::wait_for_task) elapsing, and the
+    /// [deadline of a task](Client::run_with_deadline) passing.
+    pub fn is_timeout(&self) -> bool {
+        match self.inner() {
+            Error::Reqwest(err) => err.is_timeout(),
+            Error::DeadlineExceeded { .. }
+            | Error::WaitTimeout { .. }
+            | Error::TaskDeadlineExceeded { .. } => true,
+            _ => false,
+        }
+    }
 
-            let response = self.list_tasks(&view, next_token.as_deref()).await?;
-            results.extend(response.tasks);
+    /// Returns whether the error is a failure to connect to the service.
+    pub fn is_connect(&self) -> bool {
+        match self.inner() {
+            Error::Reqwest(err) => retry::is_connect(err),
+            Error::DeadlineExceeded { last, .. } => last.is_connect(),
+            _ => false,
+        }
+    }
 
-            next_token = response.next_page_token;
-            if next_token.is_none() {
-                break;
+    /// Returns whether the error is transient, so the request may succeed if
+    /// it is made again later.
+    ///
+    /// This is consistent with the
+    /// [`DefaultRetryPolicy`](retry::DefaultRetryPolicy): connection
+    /// failures, timeouts, and `408 Request Timeout`, `429 Too Many
+    /// Requests`, and `5xx` responses are transient (as is an open circuit
+    /// breaker). Note that this does not consider whether the request is
+    /// idempotent: the service may have created a task even if creating it
+    /// failed.
+    pub fn is_retryable(&self) -> bool {
+        match self.inner() {
+            Error::Reqwest(err) => match err.status() {
+                Some(status) => retry::is_transient_status(status),
+                None => retry::is_transient_error(err),
+            },
+            Error::RetriesExhausted { status, .. } | Error::Http { status, .. } => {
+                retry::is_transient_status(*status)
             }
+            Error::DeadlineExceeded { last, .. } => last.is_retryable(),
+            Error::CircuitOpen => true,
+            _ => false,
+        }
+    }
 
-            page += 1;
+    /// Wraps an error with the request that failed.
+    fn with_endpoint(self, method: Method, url: &Url) -> Self {
+        Error::Request {
+            method,
+            path: url.path().to_string(),
+            source: Box::new(self),
         }
+    }
 
-        Ok(results)
+    /// Converts a `404 Not Found` response to a request for a particular task
+    /// into an [`Error::TaskNotFound`].
+    fn for_task(self, id: &str) -> Self {
+        match self {
+            Error::Request {
+                method,
+                path,
+                source,
+            } => Error::Request {
+                method,
+                path,
+                source: Box::new(source.for_task(id)),
+            },
+            Error::Retried {
+                attempts,
+                elapsed,
+                last,
+            } => Error::Retried {
+                attempts,
+                elapsed,
+                last: Box::new(last.for_task(id)),
+            },
+            Error::Http {
+                status: StatusCode::NOT_FOUND,
+                ..
+            } => Error::TaskNotFound { id: id.to_string() },
+            err => err,
+        }
     }
+}
 
-    /// Creates a task within the service.
-    ///
-    /// This method makes a request to the `POST /tasks` endpoint.
-    pub async fn create_task(&self, task: Task) -> Result<CreateTask> {
-        self.post("./tasks", task).await
+/// A [`Result`](std::result::Result) with an [`Error`].
+type Result<T> = std::result::Result<T, Error>;
+
+impl From<reqwest_middleware::Error> for Error {
+    fn from(value: reqwest_middleware::Error) -> Self {
+        match value {
+            reqwest_middleware::Error::Middleware(err) => match err.downcast::<auth::Failure>() {
+                Ok(err) => Error::Auth(err.0),
+                Err(err) => Error::Middlware(err),
+            },
+            reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+        }
     }
+}
 
-    /// Gets a specific task within the service.
-    ///
-    /// This method makes a request to the `GET /tasks/{id}` endpoint.
-    pub async fn get_task(&self, id: impl AsRef<str>, view: View) -> Result<task::Response> {
-        let url = format!("./tasks/{}?view={view}", id.as_ref());
+/// Converts the result of a failed final attempt into an error.
+///
+/// `retries` is the number of retries that were made.
+fn failure(result: reqwest_middleware::Result<reqwest::Response>, retries: u32) -> Error {
+    match result {
+        Ok(response) => Error::RetriesExhausted {
+            status: response.status(),
+            retries,
+        },
+        Err(err) => Error::from(err),
+    }
+}
 
-        Ok(match view {
-            View::Minimal => task::Response::Minimal(self.get(url).await?),
-            View::Basic => task::Response::Basic(self.get(url).await?),
-            View::Full => task::Response::Full(self.get(url).await?),
-        })
+/// The maximum number of bytes of a response body (or a message extracted
+/// from it) kept within an [`Error::Http`], an [`Error::Unauthorized`], or an
+/// [`Error::Decode`].
+pub const MAX_BODY_SNIPPET_LEN: usize = 1024;
+
+/// Gets the beginning of a response body for reporting within an error.
+fn snippet(body: &[u8]) -> String {
+    let end = body.len().min(MAX_BODY_SNIPPET_LEN);
+    let mut snippet = String::from_utf8_lossy(&body[..end]).into_owned();
+
+    if end < body.len() {
+        snippet.push_str("...");
     }
 
-    /// Cancels a task within the service.
-    ///
+    snippet
+}
+
+/// Returns whether the value at a path within a JSON body is `null`.
+fn is_null_at(body: &[u8], path: &serde_path_to_error::Path) -> bool {
+    use serde_path_to_error::Segment;
+
+    let Ok(root) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return false;
+    };
+
+    let mut value = &root;
+    for segment in path.iter() {
+        value = match segment {
+            Segment::Seq { index } => match value.get(index) {
+                Some(value) => value,
+                None => return false,
+            },
+            Segment::Map { key } => match value.get(key) {
+                Some(value) => value,
+                None => return false,
+            },
+            // NOTE: enums do not introduce a level of nesting for untagged
+            // representations, so the value is left as is.
+            Segment::Enum { .. } => value,
+            Segment::Unknown => return false,
+        };
+    }
+
+    value.is_null()
+}
+
+/// Generates a random key for identifying a submitted task.
+fn idempotency_key() -> String {
+    format!("{:016x}{:016x}", strategy::random(), strategy::random())
+}
+
+/// The result of sending a request that may be recovered.
+enum Sent<T> {
+    /// The response to the final attempt.
+    Response(reqwest::Response),
+
+    /// The value recovered after an earlier attempt may have succeeded.
+    Recovered(T),
+}
+
+/// The maximum number of task IDs remembered by
+/// [`Client::list_tasks_stream_deduplicated()`].
+pub const MAX_DEDUPLICATED_TASK_IDS: usize = 100_000;
+
+/// The state of a stream of tasks.
+enum Listing {
+    /// The next page of tasks to read.
+    Page {
+        /// The parameters (with the page token of the page).
+        params: ListTasksParams,
+
+        /// The number of the page (starting from one).
+        page: usize,
+
+        /// The page tokens returned by the service so far.
+        tokens: HashSet<PageToken>,
+    },
+
+    /// The service returned a page token it had already returned.
+    Loop {
+        /// The number of the page.
+        page: usize,
+    },
+
+    /// The last page has been read.
+    Done,
+}
+
+/// The most recent task IDs (up to [`MAX_DEDUPLICATED_TASK_IDS`]).
+#[derive(Default)]
+struct RecentIds {
+    /// The remembered IDs.
+    ids: HashSet<String>,
+
+    /// The remembered IDs (from the oldest to the newest).
+    order: VecDeque<String>,
+}
+
+impl RecentIds {
+    /// Remembers an ID, returning whether it was not already remembered.
+    fn insert(&mut self, id: &str) -> bool {
+        if self.ids.contains(id) {
+            return false;
+        }
+
+        if self.order.len() == MAX_DEDUPLICATED_TASK_IDS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+
+        self.ids.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        true
+    }
+}
+
+/// A task that is being waited on.
+struct Waiting {
+    /// The index of the task within the waited tasks.
+    index: usize,
+
+    /// The last observed state of the task.
+    state: Option<State>,
+
+    /// The number of polls that observed the same state before the last poll.
+    unchanged: u32,
+
+    /// The number of successful polls of the task.
+    polls: u32,
+
+    /// When the state of the task was last observed to change (since the
+    /// wait started).
+    last_change: Duration,
+
+    /// When the task is next polled (since the wait started).
+    due: Duration,
+}
+
+/// A client for interacting with a service.
+///
+/// Clones of a client share the same connection pool and circuit breaker.
+#[derive(Clone)]
+pub struct Client {
+    /// The base URL.
+    url: Url,
+
+    /// The underlying client.
+    client: ReqwestClient,
+
+    /// The redactor for logged headers and bodies.
+    redactor: Redactor,
+
+    /// The configuration for retrying requests.
+    retry: retry::Config,
+
+    /// The name of the tag holding the idempotency key of submitted tasks (if
+    /// idempotent submission is enabled).
+    idempotency_tag: Option<String>,
+
+    /// The circuit breaker (if enabled).
+    breaker: Option<CircuitBreaker>,
+
+    /// The configuration for hedging reads (if enabled).
+    hedge: Option<Hedge>,
+
+    /// The sink for request metrics (if any).
+    metrics: Option<Arc<dyn RequestMetrics>>,
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // NOTE: the underlying client prints the values of its default headers,
+        // which may contain credentials, so it is intentionally omitted.
+        f.debug_struct("Client")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Client {
+    /// Gets an empty builder for a [`Client`].
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Gets a copy of the client that retries requests using the provided
+    /// strategies.
+    ///
+    /// This is useful for overriding the [`RetryConfig`] configured with
+    /// [`Builder::retry_config()`] for particular calls. The copy shares its
+    /// connection pool (and circuit breaker) with this client.
+    ///
+    /// ```rust
+    /// use tes::v1::client::Client;
+    /// use tes::v1::client::strategy;
+    /// use tes::v1::client::strategy::RetryConfig;
+    ///
+    /// # async fn run(client: &Client) -> Result<(), tes::v1::client::Error> {
+    /// let patient = client.with_retry_config(RetryConfig::uniform(strategy::default_backoff_with(
+    ///     10,
+    ///     strategy::DEFAULT_BASE,
+    ///     strategy::DEFAULT_CAP,
+    /// )));
+    ///
+    /// patient.service_info().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry_config(&self, config: RetryConfig) -> Self {
+        let mut client = self.clone();
+        client.retry.strategies = config;
+        client
+    }
+
+    /// Sends a request, retrying it if it fails with a transient error.
+    ///
+    /// The final response is returned regardless of its status. Every attempt
+    /// is recorded within `history`.
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Bytes>,
+        operation: Operation,
+        history: &mut History,
+    ) -> Result<reqwest::Response> {
+        let sent = self
+            .send_recoverable(method, url, body, operation, history, || async {
+                Ok(None::<Infallible>)
+            })
+            .await?;
+
+        match sent {
+            Sent::Response(response) => Ok(response),
+            Sent::Recovered(never) => match never {},
+        }
+    }
+
+    /// Sends a request, retrying it if it fails with a transient error.
+    ///
+    /// Before each retry, if the service may have received a previous attempt,
+    /// `recover` is called to check whether that attempt succeeded. If it
+    /// returns a value, no further attempts are made and the value is
+    /// returned.
+    ///
+    /// Every attempt is recorded within `history`.
+    async fn send_recoverable<T, F, Fut>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<Bytes>,
+        operation: Operation,
+        history: &mut History,
+        mut recover: F,
+    ) -> Result<Sent<T>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Option<T>>>,
+    {
+        // NOTE: creating a task is only idempotent when the task is tagged
+        // with an idempotency key.
+        let idempotent = operation != Operation::Create || self.idempotency_tag.is_some();
+        let mut strategy = self.retry.strategy(operation).clone();
+        let retries = strategy.len();
+        let mut retry = 0;
+        let mut received = false;
+
+        loop {
+            if received {
+                let recovered = recover().await?;
+                if let Some(value) = recovered {
+                    return Ok(Sent::Recovered(value));
+                }
+            }
+
+            if let Some(breaker) = &self.breaker {
+                if !breaker.allow() {
+                    return Err(Error::CircuitOpen);
+                }
+            }
+
+            let build = || {
+                let request = self.client.request(method.clone(), url.clone());
+
+                // NOTE: cloning the body only increments a reference count, so
+                // a large body is never copied between attempts.
+                match &body {
+                    Some(body) => request
+                        .body(body.clone())
+                        .header(CONTENT_TYPE, "application/json"),
+                    None => request,
+                }
+            };
+
+            let started = retry::Instant::now();
+            let result = match &self.hedge {
+                Some(hedge) if operation == Operation::Read => hedge.send(build).await,
+                _ => build().send().await,
+            };
+
+            history.attempts += 1;
+            let outcome = RequestOutcome::new(&method, retry + 1, idempotent, &result);
+            received |= !outcome.is_connect();
+
+            if let Some(metrics) = &self.metrics {
+                metrics.record(&RequestRecord::new(&url, &outcome, started.elapsed()));
+            }
+
+            if let (Some(breaker), Some(failed)) = (&self.breaker, outcome.service_failed()) {
+                breaker.record(failed);
+            }
+
+            let backoff = match self.retry.policy.classify(&outcome) {
+                Retryability::Retry => strategy.next(),
+                Retryability::Permanent => {
+                    if !result.as_ref().is_ok_and(|r| r.status().is_success()) {
+                        history.failures.push(Attempt::new(&outcome, None));
+                    }
+
+                    return Ok(Sent::Response(result?));
+                }
+            };
+
+            let backoff = match backoff {
+                Some(backoff) => backoff,
+                None => {
+                    history.failures.push(Attempt::new(&outcome, None));
+                    return Err(failure(result, retry));
+                }
+            };
+
+            let requested = result.as_ref().ok().and_then(retry::requested_delay);
+            let (delay, from_service) = self.retry.delay(backoff, requested);
+
+            // NOTE: the deadline is checked before sleeping so that a long
+            // delay (e.g., one requested by the service) is never waited out
+            // only to give up afterwards.
+            if let Some(deadline) = self.retry.deadline {
+                if history.start.elapsed() + delay >= deadline {
+                    history.failures.push(Attempt::new(&outcome, None));
+                    return Err(Error::DeadlineExceeded {
+                        deadline,
+                        last: Box::new(failure(result, retry)),
+                    });
+                }
+            }
+
+            history.failures.push(Attempt::new(&outcome, Some(delay)));
+
+            if let Some(observer) = &self.retry.observer {
+                observer.on_retry(&RetryEvent::new(&url, &outcome, delay, from_service));
+            }
+
+            retry += 1;
+
+            let reason = match &result {
+                Ok(response) => response.status().to_string(),
+                Err(err) => err.to_string(),
+            };
+
+            if from_service {
+                warn!(
+                    "{method} {url} failed ({reason}): retrying in {delay:?} as requested by the \
+                     service (retry {retry} of {retries})"
+                );
+            } else {
+                warn!(
+                    "{method} {url} failed ({reason}): retrying in {delay:?} (retry {retry} of \
+                     {retries})"
+                );
+            }
+
+            retry::sleep(delay).await;
+        }
+    }
+
+    /// Performs a `GET` request on an endpoint within the service.
+    ///
+    /// # Safety
+    ///
+    /// Because calls to `get()` are all local to this crate, the provided
+    /// `endpoint` is assumed to always be joinable to the base URL without
+    /// issue.
+    async fn get<Response>(&self, endpoint: impl AsRef<str>) -> Result<Response>
+    where
+        Response: for<'de> Deserialize<'de>,
+    {
+        let endpoint = endpoint.as_ref();
+
+        // SAFETY: as described in the documentation for this method, the URL is
+        // already validated upon creationg of the [`Client`], and the
+        // `endpoint` is assumed to always be joinable to that URL, so this
+        // should always unwrap.
+        let url = self.url.join(endpoint).unwrap();
+        debug!("GET {url}");
+
+        let mut history = History::new();
+        let result = async {
+            let response = self
+                .send(
+                    Method::GET,
+                    url.clone(),
+                    None,
+                    Operation::Read,
+                    &mut history,
+                )
+                .await?;
+            self.read(response).await
+        }
+        .await;
+
+        self.finish(Method::GET, &url, &history, result)
+    }
+
+    /// Performs a `POST` request on an endpoint within the service.
+    ///
+    /// `operation` determines how the request is retried.
+    ///
+    /// # Safety
+    ///
+    /// Because calls to `post()` are all local to this crate, the provided
+    /// `endpoint` is assumed to always be joinable to the base URL without
+    /// issue.
+    async fn post<Body, Response>(
+        &self,
+        endpoint: impl AsRef<str>,
+        body: Body,
+        operation: Operation,
+    ) -> Result<Response>
+    where
+        Body: Serialize,
+        Response: for<'de> Deserialize<'de>,
+    {
+        let endpoint = endpoint.as_ref();
+        let body = Bytes::from(serde_json::to_vec(&body).map_err(Error::SerdeJSON)?);
+
+        // SAFETY: as described in the documentation for this method, the URL is
+        // already validated upon creationg of the [`Client`], and the
+        // `endpoint` is assumed to always be joinable to that URL, so this
+        // should always unwrap.
+        let url = self.url.join(endpoint).unwrap();
+        debug!("POST {url} {}", self.redactor.body(&body));
+
+        let mut history = History::new();
+        let result = async {
+            let response = self
+                .send(
+                    Method::POST,
+                    url.clone(),
+                    Some(body),
+                    operation,
+                    &mut history,
+                )
+                .await?;
+            self.read(response).await
+        }
+        .await;
+
+        self.finish(Method::POST, &url, &history, result)
+    }
+
+    /// Finishes a call to an endpoint.
+    ///
+    /// An error is wrapped with the failed attempts (if the request was
+    /// retried) and the request that failed, and the call is reported to the
+    /// [`RequestMetrics`] (if any are installed).
+    fn finish<T>(
+        &self,
+        method: Method,
+        url: &Url,
+        history: &History,
+        result: Result<T>,
+    ) -> Result<T> {
+        let result = result.map_err(|err| err.retried(history).with_endpoint(method.clone(), url));
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_call(&CallRecord::new(
+                &method,
+                url,
+                history.attempts,
+                history.start.elapsed(),
+                result.as_ref().err(),
+            ));
+        }
+
+        result
+    }
+
+    /// Reads and deserializes the JSON body of a response.
+    ///
+    /// A response with an unsuccessful status is reported as an
+    /// [`Error::Http`] and a body that fails to deserialize is reported as an
+    /// [`Error::Decode`].
+    async fn read<Response>(&self, response: reqwest::Response) -> Result<Response>
+    where
+        Response: for<'de> Deserialize<'de>,
+    {
+        let url = response.url().clone();
+        let status = response.status();
+        trace!("{status} {}", self.redactor.headers(response.headers()));
+
+        let www_authenticate = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+        let bytes = response.bytes().await.map_err(Error::Reqwest)?;
+
+        trace!("{bytes:?}");
+
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(Error::Unauthorized {
+                status,
+                www_authenticate,
+                body: snippet(&bytes),
+                message: message::parse(&bytes),
+            });
+        }
+
+        if !status.is_success() {
+            return Err(Error::Http {
+                status,
+                url: Box::new(url),
+                body: snippet(&bytes),
+                message: message::parse(&bytes),
+            });
+        }
+
+        let mut deserializer = serde_json::Deserializer::from_slice(&bytes);
+        serde_path_to_error::deserialize(&mut deserializer).map_err(|err| Error::Decode {
+            status,
+            body: snippet(&bytes),
+            path: err.path().to_string(),
+            null: is_null_at(&bytes, err.path()),
+            source: err.into_inner(),
+        })
+    }
+
+    /// Gets the service information.
+    ///
+    /// This method makes a request to the `GET /service-info` endpoint.
+    pub async fn service_info(&self) -> Result<ServiceInfo> {
+        self.get("./service-info").await
+    }
+
+    /// Lists a single page of tasks within the service.
+    ///
+    /// This method makes a request to the `GET /tasks` endpoint. Without
+    /// parameters, the service lists its first page of tasks with the
+    /// [`View::Minimal`] view.
+    ///
+    /// The parameters are validated before the request is sent: providing
+    /// more tag values than tag keys is an
+    /// [`ValidationError::TagFilterArityMismatch`]. Tag keys without a tag
+    /// value are sent with an empty tag value.
+    ///
+    /// When the view is known ahead of time, [`Self::list_tasks_minimal()`],
+    /// [`Self::list_tasks_basic()`], and [`Self::list_tasks_full()`] return
+    /// the tasks without wrapping them in a [`task::Response`].
+    ///
+    /// # Examples
+    ///
+    /// Listing can be resumed later (e.g., by another invocation of a command
+    /// line tool) by storing the next page token.
+    ///
+    /// ```no_run
+    /// use tes::v1::client::Client;
+    /// use tes::v1::client::tasks::ListTasksParams;
+    /// use tes::v1::types::requests::PageToken;
+    ///
+    /// # async fn run(client: &Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut params = ListTasksParams::default();
+    ///
+    /// if let Ok(token) = std::fs::read_to_string("listing.state") {
+    ///     params.page_token = Some(token.parse::<PageToken>()?);
+    /// }
+    ///
+    /// let page = client.list_tasks(Some(&params)).await?;
+    /// println!("listed {} tasks", page.len());
+    ///
+    /// match page.next_page_token {
+    ///     Some(token) => std::fs::write("listing.state", token.to_string())?,
+    ///     None => std::fs::remove_file("listing.state")?,
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_tasks(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<task::Response>> {
+        let view = params.and_then(|params| params.view.as_ref());
+
+        match view {
+            None | Some(View::Minimal) => Ok(self
+                .list::<MinimalTask>(params, view)
+                .await?
+                .map_tasks(task::Response::Minimal)),
+            Some(View::Basic) => Ok(self
+                .list::<Task>(params, view)
+                .await?
+                .map_tasks(task::Response::Basic)),
+            Some(View::Full) => Ok(self
+                .list::<Task>(params, view)
+                .await?
+                .map_tasks(task::Response::Full)),
+        }
+    }
+
+    /// Lists a single page of tasks within the service with the
+    /// [`View::Minimal`] view.
+    ///
+    /// See [`Self::list_tasks()`]. The view of `params` (if any) is ignored.
+    pub async fn list_tasks_minimal(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<MinimalTask>> {
+        self.list(params, Some(&View::Minimal)).await
+    }
+
+    /// Lists a single page of tasks within the service with the
+    /// [`View::Basic`] view.
+    ///
+    /// See [`Self::list_tasks()`]. The view of `params` (if any) is ignored.
+    pub async fn list_tasks_basic(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<Task>> {
+        self.list(params, Some(&View::Basic)).await
+    }
+
+    /// Lists a single page of tasks within the service with the
+    /// [`View::Full`] view.
+    ///
+    /// See [`Self::list_tasks()`]. The view of `params` (if any) is ignored.
+    pub async fn list_tasks_full(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<Task>> {
+        self.list(params, Some(&View::Full)).await
+    }
+
+    /// Validates the parameters and makes a request to the `GET /tasks`
+    /// endpoint with the provided view (instead of the view of `params`).
+    async fn list<T>(
+        &self,
+        params: Option<&ListTasksParams>,
+        view: Option<&View>,
+    ) -> Result<ListTasks<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let default = ListTasksParams::default();
+        let params = params.unwrap_or(&default);
+
+        if params.tag_values.len() > params.tag_keys.len() {
+            return Err(Error::from(ValidationError::TagFilterArityMismatch {
+                keys: params.tag_keys.len(),
+                values: params.tag_values.len(),
+            }));
+        }
+
+        let query = params.query_with_view(view);
+
+        if query.is_empty() {
+            self.get("./tasks").await
+        } else {
+            self.get(format!("./tasks?{query}")).await
+        }
+    }
+
+    /// Streams all tasks within the service.
+    ///
+    /// This method is a convenience wrapper around [`Self::list_tasks()`] that
+    /// requests the next page once the tasks of the previous page have been
+    /// yielded, and stops once the service responds without a next page token
+    /// (or with an empty one). Listing starts from the page token of `params`
+    /// (if there is one).
+    ///
+    /// An error from any page is yielded and ends the stream. If the service
+    /// returns a page token that it already returned (which would otherwise
+    /// list the same pages forever), the stream ends with an
+    /// [`Error::PaginationLoop`]. Pages are only requested while the stream is
+    /// polled, so dropping the stream stops the listing.
+    ///
+    /// To skip tasks that the service lists more than once, use
+    /// [`Self::list_tasks_stream_deduplicated()`].
+    pub fn list_tasks_stream(
+        &self,
+        params: ListTasksParams,
+    ) -> impl Stream<Item = Result<task::Response>> + '_ {
+        let tokens = params.page_token.iter().cloned().collect();
+        let state = Listing::Page {
+            params,
+            page: 1,
+            tokens,
+        };
+
+        stream::try_unfold(state, move |state| async move {
+            let (mut params, page, mut tokens) = match state {
+                Listing::Page {
+                    params,
+                    page,
+                    tokens,
+                } => (params, page, tokens),
+                Listing::Loop { page } => return Err(Error::PaginationLoop { page }),
+                Listing::Done => return Ok(None),
+            };
+
+            debug!(
+                "reading task page {page} with token {:?}",
+                params.page_token
+            );
+            let response = self.list_tasks(Some(&params)).await?;
+
+            // NOTE: the tasks of a page are yielded before a loop is reported.
+            let state = match response.next_page_token {
+                None => Listing::Done,
+                Some(token) if tokens.contains(&token) => Listing::Loop { page: page + 1 },
+                Some(token) => {
+                    tokens.insert(token.clone());
+                    params.page_token = Some(token);
+
+                    Listing::Page {
+                        params,
+                        page: page + 1,
+                        tokens,
+                    }
+                }
+            };
+
+            Ok(Some((
+                stream::iter(response.tasks.into_iter().map(Ok)),
+                state,
+            )))
+        })
+        .try_flatten()
+    }
+
+    /// Streams all tasks within the service, skipping tasks that the service
+    /// already listed.
+    ///
+    /// See [`Self::list_tasks_stream()`]. Tasks are identified by their IDs
+    /// (tasks without an ID are never skipped), and only the IDs of the last
+    /// [`MAX_DEDUPLICATED_TASK_IDS`] tasks are remembered.
+    pub fn list_tasks_stream_deduplicated(
+        &self,
+        params: ListTasksParams,
+    ) -> impl Stream<Item = Result<task::Response>> + '_ {
+        let mut seen = RecentIds::default();
+
+        self.list_tasks_stream(params).try_filter(move |task| {
+            future::ready(task.id().map(|id| seen.insert(id)).unwrap_or(true))
+        })
+    }
+
+    /// Lists all tasks within the service.
+    ///
+    /// This method collects [`Self::list_tasks_stream()`]. To guard against
+    /// reading an unexpectedly large number of tasks into memory, use
+    /// [`Self::list_all_tasks_with_limit()`].
+    pub async fn list_all_tasks(&self, params: ListTasksParams) -> Result<Vec<task::Response>> {
+        self.list_tasks_stream(params).try_collect().await
+    }
+
+    /// Lists all tasks within the service, failing with an
+    /// [`Error::TooManyTasks`] if the service lists more than `max` tasks.
+    ///
+    /// No further pages are requested once the maximum is exceeded.
+    pub async fn list_all_tasks_with_limit(
+        &self,
+        params: ListTasksParams,
+        max: usize,
+    ) -> Result<Vec<task::Response>> {
+        let mut stream = pin!(self.list_tasks_stream(params));
+        let mut results = Vec::new();
+
+        while let Some(task) = stream.try_next().await? {
+            if results.len() == max {
+                return Err(Error::TooManyTasks { max });
+            }
+
+            results.push(task);
+        }
+
+        Ok(results)
+    }
+
+    /// Counts the tasks within the service by their state.
+    ///
+    /// This method streams the tasks matching `params` (see
+    /// [`Self::list_tasks_stream()`]) with the [`View::Minimal`] view (the view
+    /// of `params` is ignored). The counts are ordered by the lifecycle of a
+    /// task, and tasks without a state are counted as [`State::Unknown`].
+    pub async fn count_tasks_by_state(
+        &self,
+        mut params: ListTasksParams,
+    ) -> Result<BTreeMap<State, usize>> {
+        params.view = Some(View::Minimal);
+
+        let mut stream = pin!(self.list_tasks_stream(params));
+        let mut counts = BTreeMap::new();
+
+        while let Some(task) = stream.try_next().await? {
+            let state = task.state().unwrap_or_default();
+            *counts.entry(state).or_default() += 1;
+        }
+
+        Ok(counts)
+    }
+
+    /// Validates a task before it is submitted to the service.
+    ///
+    /// The structure of the task is checked first using
+    /// [`Task::validate_with()`] with the default options (without making a
+    /// request). See [`Self::validate_task_with()`] to use other options.
+    pub async fn validate_task(&self, task: &Task) -> Result<Vec<ValidationWarning>> {
+        self.validate_task_with(task, &Default::default()).await
+    }
+
+    /// Validates a task before it is submitted to the service with options
+    /// for checking its structure.
+    ///
+    /// The structure of the task is checked first using
+    /// [`Task::validate_with()`] with `options` (without making a request):
+    /// any violations are returned as an [`Error::Validation`] with
+    /// a [`ValidationError::InvalidTask`], and any warnings (such as volumes
+    /// that conflict with the paths of inputs) are returned as
+    /// [`ValidationWarning::Task`]s. The task is then checked against the
+    /// capabilities advertised by [`Self::service_info()`], with any potential
+    /// problems returned as [`ValidationWarning`]s: currently, these are the
+    /// inputs and outputs whose URLs have a scheme that is not among the
+    /// advertised storage (if the service advertises any).
+    pub async fn validate_task_with(
+        &self,
+        task: &Task,
+        options: &validation::Options,
+    ) -> Result<Vec<ValidationWarning>> {
+        let mut warnings = task
+            .validate_with(options)
+            .map_err(ValidationError::InvalidTask)?
+            .into_iter()
+            .map(ValidationWarning::Task)
+            .collect::<Vec<_>>();
+
+        let info = self.service_info().await?;
+        let supported = info.storage().cloned().unwrap_or_default();
+        if supported.is_empty() {
+            return Ok(warnings);
+        }
+
+        let schemes = supported
+            .iter()
+            .map(|storage| storage_scheme(storage).to_ascii_lowercase())
+            .collect::<HashSet<_>>();
+
+        warnings.extend(
+            task.inputs
+                .iter()
+                .flatten()
+                .filter(|input| input.content.is_none())
+                .filter_map(|input| input.url.as_deref())
+                .chain(
+                    task.outputs
+                        .iter()
+                        .flatten()
+                        .map(|output| output.url.as_str()),
+                )
+                .filter(|url| !schemes.contains(&storage_scheme(url).to_ascii_lowercase()))
+                .map(|url| ValidationWarning::UnsupportedStorage {
+                    url: url.to_string(),
+                    supported: supported.clone(),
+                }),
+        );
+
+        Ok(warnings)
+    }
+
+    /// Creates a task within the service.
+    ///
+    /// This method makes a request to the `POST /tasks` endpoint.
+    ///
+    /// As the service may create the task even if the client never receives
+    /// its response, the request is only retried when the client fails to
+    /// connect to the service (unless a [`RetryPolicy`](retry::RetryPolicy)
+    /// decides otherwise). To retry more failures without creating duplicate
+    /// tasks, enable idempotent submission with
+    /// [`Builder::idempotency_tag()`].
+    pub async fn create_task(&self, task: Task) -> Result<CreateTask> {
+        match &self.idempotency_tag {
+            Some(tag) => self.create_task_idempotently(tag, task).await,
+            None => self.post("./tasks", task, Operation::Create).await,
+        }
+    }
+
+    /// Creates a task within the service, tagging it with a generated
+    /// idempotency key.
+    ///
+    /// Before each retry, the tasks within the service are searched for the
+    /// key so that a task created by an earlier attempt is never duplicated.
+    async fn create_task_idempotently(&self, tag: &str, mut task: Task) -> Result<CreateTask> {
+        let key = idempotency_key();
+        task.tags
+            .get_or_insert_with(Default::default)
+            .insert(tag.to_string(), key.clone());
+
+        let body = Bytes::from(serde_json::to_vec(&task).map_err(Error::SerdeJSON)?);
+
+        // SAFETY: the base URL is validated upon creation of the [`Client`]
+        // and this endpoint is always joinable to it.
+        let url = self.url.join("./tasks").unwrap();
+        debug!("POST {url} {}", self.redactor.body(&body));
+
+        let mut history = History::new();
+        let result = async {
+            let sent = self
+                .send_recoverable(
+                    Method::POST,
+                    url.clone(),
+                    Some(body),
+                    Operation::Create,
+                    &mut history,
+                    || self.find_tagged_task(tag, &key),
+                )
+                .await?;
+
+            match sent {
+                Sent::Response(response) => self.read(response).await,
+                Sent::Recovered(id) => {
+                    debug!("task `{id}` was created by an earlier attempt");
+                    Ok(CreateTask { id })
+                }
+            }
+        }
+        .await;
+
+        self.finish(Method::POST, &url, &history, result)
+    }
+
+    /// Finds the ID of a task with the provided tag (if one exists).
+    async fn find_tagged_task(&self, tag: &str, value: &str) -> Result<Option<String>> {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("view", "MINIMAL")
+            .append_pair("tag_key", tag)
+            .append_pair("tag_value", value)
+            .finish();
+
+        let results = self
+            .get::<ListTasks<MinimalTask>>(format!("./tasks?{query}"))
+            .await?;
+
+        Ok(results.tasks.into_iter().next().map(|task| task.id))
+    }
+
+    /// Gets a specific task within the service.
+    ///
+    /// This method makes a request to the `GET /tasks/{id}` endpoint.
+    ///
+    /// If the service does not know of the task, the [inner](Error::inner)
+    /// error is an [`Error::TaskNotFound`].
+    pub async fn get_task(&self, id: impl AsRef<str>, view: View) -> Result<task::Response> {
+        let id = id.as_ref();
+        let url = format!("./tasks/{id}?view={view}");
+
+        let response = match view {
+            View::Minimal => self.get(url).await.map(task::Response::Minimal),
+            View::Basic => self.get(url).await.map(task::Response::Basic),
+            View::Full => self.get(url).await.map(task::Response::Full),
+        };
+
+        response.map_err(|err| err.for_task(id))
+    }
+
+    /// Cancels a task within the service.
+    ///
     /// This method makes a request to the `POST /tasks/{id}:cancel` endpoint.
+    ///
+    /// If the service does not know of the task, the [inner](Error::inner)
+    /// error is an [`Error::TaskNotFound`].
     pub async fn cancel_task(&self, id: impl AsRef<str>) -> Result<()> {
-        self.post(format!("./tasks/{}:cancel", id.as_ref()), ())
+        let id = id.as_ref();
+
+        // NOTE: the service responds with an empty object, which is ignored.
+        self.post::<_, serde::de::IgnoredAny>(format!("./tasks/{id}:cancel"), (), Operation::Cancel)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.for_task(id))
+    }
+
+    /// Cancels the tasks within the service that match a filter.
+    ///
+    /// This method streams the tasks matching `params` (see
+    /// [`Self::list_tasks_stream()`]) with the [`View::Minimal`] view (the view
+    /// of `params` is ignored), skips the tasks that are no longer executing
+    /// (see [`State::is_executing()`]), and cancels the rest using
+    /// [`Self::cancel_task()`] with at most [`CancelOptions::concurrency()`]
+    /// requests in flight. Tasks without a state are canceled.
+    ///
+    /// Every matching task is listed before any task is canceled. An error
+    /// when listing the tasks is returned, but a failure to cancel a task is
+    /// reported within the [`CancelReport`] without affecting the other tasks.
+    /// With [`CancelOptions::dry_run()`], no tasks are canceled, and the
+    /// report contains the tasks that would have been canceled.
+    pub async fn cancel_matching(
+        &self,
+        mut params: ListTasksParams,
+        options: &CancelOptions,
+    ) -> Result<CancelReport> {
+        params.view = Some(View::Minimal);
+
+        let mut stream = pin!(self.list_tasks_stream(params));
+        let mut report = CancelReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        let mut ids = Vec::new();
+
+        while let Some(task) = stream.try_next().await? {
+            // SAFETY: tasks are listed with the minimal view.
+            let task = task.into_minimal().unwrap();
+
+            if task.state.unwrap_or_default().is_executing() {
+                ids.push(task.id);
+            } else {
+                report.skipped += 1;
+            }
+        }
+
+        if options.dry_run {
+            report.canceled = ids;
+            return Ok(report);
+        }
+
+        let results = stream::iter(ids.into_iter().map(|id| async move {
+            let result = self.cancel_task(&id).await;
+            (id, result)
+        }))
+        .buffered(options.concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+        for (id, result) in results {
+            match result {
+                Ok(()) => report.canceled.push(id),
+                Err(err) => report.failed.push((id, err)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Waits for a task within the service to finish.
+    ///
+    /// This method polls the task using [`Self::get_task()`] with the
+    /// [`View::Minimal`] view (as often as the [`PollPolicy`] within the
+    /// [`WaitOptions`] decides) until it is no longer executing (i.e., until
+    /// [`State::is_executing()`] is `false`): this is the case when it is
+    /// [complete](State::Complete), failed with an
+    /// [executor error](State::ExecutorError) or a
+    /// [system error](State::SystemError), was [canceled](State::Canceled), or
+    /// was [preempted](State::Preempted). The finished task is then retrieved
+    /// with the view set within the [`WaitOptions`] (which is
+    /// [`View::Full`] by default).
+    ///
+    /// A task that is missing its state is considered to be in the
+    /// [`State::Unknown`] state, so it is polled again.
+    ///
+    /// Each poll is retried according to the retry configuration of the
+    /// client. If the timeout within the [`WaitOptions`] elapses before the
+    /// task has finished, an [`Error::WaitTimeout`] is returned (and the task
+    /// is canceled if [`WaitOptions::cancel_on_timeout()`] is set).
+    pub async fn wait_for_task(&self, id: impl AsRef<str>, options: &WaitOptions) -> Result<Task> {
+        let (_, result) = self
+            .wait_for_tasks([id.as_ref()], options)
+            .await
+            .pop()
+            .expect("a single task should have a single result");
+
+        result
+    }
+
+    /// Waits for multiple tasks within the service to finish.
+    ///
+    /// This method behaves like [`Self::wait_for_task()`] for every task, but
+    /// polls the tasks together: each task is polled according to the
+    /// [`PollPolicy`] within the [`WaitOptions`] (based on its own state), with
+    /// at most [`WaitOptions::concurrency()`] requests in flight at a time. The
+    /// timeout within the [`WaitOptions`] applies to the whole wait.
+    ///
+    /// Each task has its own result (in the order of `ids`), so a task that
+    /// fails to be polled (e.g., because the service does not know of it) does
+    /// not prevent waiting for the other tasks.
+    pub async fn wait_for_tasks<I>(
+        &self,
+        ids: I,
+        options: &WaitOptions,
+    ) -> Vec<(String, Result<Task>)>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let ids = ids.into_iter().map(Into::into).collect::<Vec<String>>();
+        let mut results = ids
+            .iter()
+            .map(|_| None)
+            .collect::<Vec<Option<Result<Task>>>>();
+        let mut pending = (0..ids.len())
+            .map(|index| Waiting {
+                index,
+                state: None,
+                unchanged: 0,
+                polls: 0,
+                last_change: Duration::ZERO,
+                due: Duration::ZERO,
+            })
+            .collect::<Vec<_>>();
+        let start = retry::Instant::now();
+
+        while !pending.is_empty() {
+            // NOTE: every task is polled once more when the timeout elapses.
+            let now = start.elapsed();
+            let expired = options.timeout.is_some_and(|timeout| now >= timeout);
+            let (due, mut waiting) = pending
+                .into_iter()
+                .partition::<Vec<_>, _>(|waiting| expired || waiting.due <= now);
+
+            let polls = stream::iter(due.into_iter().map(|waiting| {
+                let id = &ids[waiting.index];
+                async move { (waiting, self.get_task(id, View::Minimal).await) }
+            }))
+            .buffer_unordered(options.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+            let mut finished = Vec::new();
+
+            for (mut polled, result) in polls {
+                let state = result.map(|response| response.state().unwrap_or_default());
+
+                if let Ok(state) = state {
+                    let elapsed = start.elapsed();
+
+                    if polled.state == Some(state) {
+                        polled.unchanged = polled.unchanged.saturating_add(1);
+                    } else {
+                        polled.state = Some(state);
+                        polled.unchanged = 0;
+                        polled.last_change = elapsed;
+                    }
+
+                    polled.polls = polled.polls.saturating_add(1);
+
+                    if let Some(progress) = &options.progress {
+                        progress(&WaitProgress {
+                            id: ids[polled.index].clone(),
+                            state,
+                            elapsed,
+                            polls: polled.polls,
+                            last_change: polled.last_change,
+                        });
+                    }
+                }
+
+                match state {
+                    Ok(state) if state.is_executing() => {
+                        polled.due =
+                            start.elapsed() + options.poll.interval(state, polled.unchanged);
+                        waiting.push(polled);
+                    }
+                    Ok(_) => finished.push(polled.index),
+                    Err(err) => results[polled.index] = Some(Err(err)),
+                }
+            }
+
+            let fetches = stream::iter(finished.into_iter().map(|i| {
+                let id = &ids[i];
+                async move {
+                    // NOTE: `WaitOptions::view()` never sets the minimal view.
+                    let result = self
+                        .get_task(id, options.view.clone())
+                        .await
+                        .map(|response| {
+                            response
+                                .into_task()
+                                .expect("basic and full views should contain a task")
+                        });
+
+                    (i, result)
+                }
+            }))
+            .buffer_unordered(options.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+            for (i, result) in fetches {
+                results[i] = Some(result);
+            }
+
+            // NOTE: the polls complete in any order, but the tasks are polled
+            // in the order of `ids`.
+            waiting.sort_unstable_by_key(|waiting| waiting.index);
+
+            let Some(next) = waiting.iter().map(|waiting| waiting.due).min() else {
+                break;
+            };
+
+            let next = match options.timeout {
+                Some(timeout) if start.elapsed() >= timeout => {
+                    if options.cancel_on_timeout {
+                        self.cancel_tasks(
+                            waiting.iter().map(|waiting| ids[waiting.index].as_str()),
+                            options,
+                        )
+                        .await;
+                    }
+
+                    for waiting in waiting {
+                        results[waiting.index] = Some(Err(Error::WaitTimeout {
+                            id: ids[waiting.index].clone(),
+                            timeout,
+                            last_state: waiting.state,
+                        }));
+                    }
+
+                    break;
+                }
+                Some(timeout) => next.min(timeout),
+                None => next,
+            };
+
+            let delay = next.saturating_sub(start.elapsed());
+            trace!(
+                "{} task(s) still executing: polling again in {delay:?}",
+                waiting.len()
+            );
+
+            pending = waiting;
+            retry::sleep(delay).await;
+        }
+
+        ids.into_iter()
+            .zip(results)
+            .map(|(id, result)| (id, result.expect("every task should have a result")))
+            .collect()
+    }
+
+    /// Cancels the tasks that did not finish before the timeout of waiting for
+    /// them elapsed.
+    ///
+    /// A failure to cancel a task is only logged.
+    async fn cancel_tasks<'a>(&self, ids: impl Iterator<Item = &'a str>, options: &WaitOptions) {
+        stream::iter(ids.map(|id| async move {
+            if let Err(err) = self.cancel_task(id).await {
+                warn!("failed to cancel task `{id}` after waiting for it: {err}");
+            }
+        }))
+        .buffer_unordered(options.concurrency)
+        .collect::<()>()
+        .await;
+    }
+
+    /// Watches the state of a task within the service until it has finished.
+    ///
+    /// This method polls the task using [`Self::get_task()`] with the
+    /// [`View::Minimal`] view (as often as `policy` decides, which may also be
+    /// a fixed [`Duration`]) and yields a [`StateChange`]
+    /// whenever the state of the task differs from the previously observed
+    /// state (starting with the first observed state). The stream ends after
+    /// the task is no longer executing (see [`State::is_executing()`]) or
+    /// after the first error.
+    ///
+    /// A task that is missing its state is considered to be in the
+    /// [`State::Unknown`] state. Dropping the stream stops polling.
+    pub fn watch_task(
+        &self,
+        id: impl Into<String>,
+        policy: impl Into<PollPolicy>,
+    ) -> impl Stream<Item = Result<StateChange>> + '_ {
+        let policy = policy.into();
+        let watch = (id.into(), None::<State>, 0);
+
+        stream::try_unfold(watch, move |(id, last, mut unchanged)| {
+            let policy = policy.clone();
+
+            async move {
+                if last.is_some_and(|state| !state.is_executing()) {
+                    return Ok(None);
+                }
+
+                loop {
+                    if let Some(state) = last {
+                        retry::sleep(policy.interval(state, unchanged)).await;
+                    }
+
+                    let state = self
+                        .get_task(&id, View::Minimal)
+                        .await?
+                        .state()
+                        .unwrap_or_default();
+
+                    if last == Some(state) {
+                        unchanged = unchanged.saturating_add(1);
+                        continue;
+                    }
+
+                    let change = StateChange {
+                        from: last,
+                        to: state,
+                        observed_at: chrono::Utc::now(),
+                    };
+
+                    return Ok::<_, Error>(Some((change, (id, Some(state), 0))));
+                }
+            }
+        })
+    }
+
+    /// Tails the logs of a task within the service until it has finished.
+    ///
+    /// This method polls the task using [`Self::get_task()`] with the
+    /// [`View::Full`] view every `interval` and yields the output that was
+    /// appended to the standard output and standard error streams of each
+    /// executor since the previous poll (within the logs of the latest attempt
+    /// of the task). If the service truncates or replaces the output of a
+    /// stream, a [`LogChunk::Reset`] is yielded before the new output. The
+    /// stream ends after the task is no longer executing (see
+    /// [`State::is_executing()`]) or after the first error.
+    ///
+    /// Dropping the stream stops polling.
+    pub fn tail_logs(
+        &self,
+        id: impl Into<String>,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<LogChunk>> + '_ {
+        let tail = (id.into(), LogTail::default(), false, false);
+
+        stream::try_unfold(tail, move |(id, mut tail, finished, polled)| async move {
+            if finished {
+                return Ok(None);
+            }
+
+            if polled {
+                retry::sleep(interval).await;
+            }
+
+            let task = self
+                .get_task(&id, View::Full)
+                .await?
+                .into_task()
+                .expect("full views should contain a task");
+
+            let logs = task
+                .logs
+                .as_ref()
+                .and_then(|logs| logs.last())
+                .map(|log| log.logs.as_slice())
+                .unwrap_or_default();
+            let chunks = tail.update(logs);
+            let finished = !task.state.unwrap_or_default().is_executing();
+
+            Ok::<_, Error>(Some((
+                stream::iter(chunks.into_iter().map(Ok)),
+                (id, tail, finished, true),
+            )))
+        })
+        .try_flatten()
+    }
+
+    /// Creates a task within the service, returning a handle to it.
+    ///
+    /// This method creates the task using [`Self::create_task()`]. The handle
+    /// shares the connection pool of this client and can poll, wait for, and
+    /// cancel the task (see [`TaskHandle`]).
+    pub async fn submit(&self, task: Task) -> Result<TaskHandle> {
+        let created = self.create_task(task).await?;
+        Ok(TaskHandle::new(self.clone(), created.id))
+    }
+
+    /// Creates a task within the service and waits for it to finish.
+    ///
+    /// This method creates the task using [`Self::create_task()`] and then
+    /// waits for it using [`Self::wait_for_task()`], returning both the
+    /// created task and the finished task. To avoid leaving a task running
+    /// when the timeout elapses, set [`WaitOptions::cancel_on_timeout()`].
+    pub async fn create_task_and_wait(
+        &self,
+        task: Task,
+        options: &WaitOptions,
+    ) -> Result<(CreateTask, Task)> {
+        let created = self.create_task(task).await?;
+        let task = self.wait_for_task(&created.id, options).await?;
+        Ok((created, task))
+    }
+
+    /// Creates a task within the service and waits for it to finish before a
+    /// deadline, canceling it otherwise.
+    ///
+    /// This method creates the task using [`Self::create_task()`] and waits
+    /// for it using [`Self::wait_for_task()`] with `deadline` as the timeout
+    /// (overriding the timeout within the [`WaitOptions`]). If the deadline
+    /// passes before the task has finished, the task is canceled using
+    /// [`Self::cancel_task()`] (which is retried according to the retry
+    /// configuration of the client) and polled for up to the
+    /// [grace period](WaitOptions::cancel_grace_period()) until it is
+    /// [being canceled](State::Canceling) or [canceled](State::Canceled). An
+    /// [`Error::TaskDeadlineExceeded`] with the ID and the last observed state
+    /// of the task is then returned, so the logs of the task can still be
+    /// inspected. This is the case even if canceling or polling the task
+    /// fails (which is logged), so a passed deadline can always be told apart
+    /// from other errors.
+    ///
+    /// If the task finishes on its own while it is being canceled, it is
+    /// returned as if it had finished before the deadline.
+    pub async fn run_with_deadline(
+        &self,
+        task: Task,
+        deadline: Duration,
+        options: &WaitOptions,
+    ) -> Result<(CreateTask, Task)> {
+        let created = self.create_task(task).await?;
+        let id = created.id.as_str();
+
+        let waiting = WaitOptions {
+            cancel_on_timeout: false,
+            ..options.clone().timeout(deadline)
+        };
+
+        let observed = match self.wait_for_task(id, &waiting).await {
+            Err(Error::WaitTimeout { last_state, .. }) => last_state,
+            result => return result.map(|task| (created, task)),
+        };
+
+        if let Err(err) = self.cancel_task(id).await {
+            warn!("failed to cancel task `{id}` after its deadline passed: {err}");
+        }
+
+        let exceeded = |last_state| Error::TaskDeadlineExceeded {
+            id: id.to_string(),
+            deadline,
+            last_state,
+        };
+
+        let canceling = self.wait_for_cancellation(id, options).await;
+
+        match canceling {
+            Ok((_, Some(task))) => Ok((created, task)),
+            Ok((last_state, None)) => Err(exceeded(last_state.or(observed))),
+            Err(err) => {
+                warn!("failed to poll task `{id}` after its deadline passed: {err}");
+                Err(exceeded(observed))
+            }
+        }
+    }
+
+    /// Polls a task that is being canceled for up to the
+    /// [grace period](WaitOptions::cancel_grace_period()).
+    ///
+    /// Returns the last observed state of the task along with the task (with
+    /// the view set within the [`WaitOptions`]) if it finished in a state
+    /// other than [`State::Canceled`].
+    async fn wait_for_cancellation(
+        &self,
+        id: &str,
+        options: &WaitOptions,
+    ) -> Result<(Option<State>, Option<Task>)> {
+        let start = retry::Instant::now();
+        let mut last = None;
+        let mut unchanged = 0u32;
+
+        loop {
+            let state = self
+                .get_task(id, View::Minimal)
+                .await?
+                .state()
+                .unwrap_or_default();
+
+            if last == Some(state) {
+                unchanged = unchanged.saturating_add(1);
+            } else {
+                last = Some(state);
+                unchanged = 0;
+            }
+
+            match state {
+                State::Canceling | State::Canceled => return Ok((last, None)),
+                state if !state.is_executing() => {
+                    // NOTE: `WaitOptions::view()` never sets the minimal view.
+                    let task = self
+                        .get_task(id, options.view.clone())
+                        .await?
+                        .into_task()
+                        .expect("basic and full views should contain a task");
+
+                    return Ok((last, Some(task)));
+                }
+                _ => {}
+            }
+
+            let remaining = options.cancel_grace_period.saturating_sub(start.elapsed());
+            if remaining.is_zero() {
+                return Ok((last, None));
+            }
+
+            retry::sleep(options.poll.interval(state, unchanged).min(remaining)).await;
+        }
+    }
+
+    /// Creates a task within the service, waits for it to finish, and
+    /// resubmits it while it fails in a state that the [`ResubmitPolicy`]
+    /// resubmits.
+    ///
+    /// Each attempt creates the task using [`Self::create_task()`] and waits
+    /// for it using [`Self::wait_for_task()`]. Before each resubmission, the
+    /// task is passed to the [mutation](ResubmitPolicy::mutate()) function of
+    /// the policy (if any). Once the task finishes in a state that is not
+    /// resubmitted, or the policy's maximum number of attempts is reached, the
+    /// final attempt and the IDs of every attempted task are returned.
+    ///
+    /// An error when creating or waiting for an attempt is returned
+    /// immediately (without resubmitting the task) as an
+    /// [`Error::Resubmission`] with the IDs of every task created so far, as
+    /// they may still be executing.
+    pub async fn run_with_resubmission(
+        &self,
+        mut task: Task,
+        policy: &ResubmitPolicy,
+        options: &WaitOptions,
+    ) -> Result<Resubmission> {
+        let mut attempts = Vec::new();
+
+        loop {
+            // NOTE: the ID of each attempt is recorded before waiting for it,
+            // so that the caller can clean up every created task on errors.
+            let created = self.create_task(task.clone()).await;
+            let result = match created {
+                Ok(created) => {
+                    attempts.push(created.id);
+                    let id = &attempts[attempts.len() - 1];
+                    let waited = self.wait_for_task(id, options).await;
+                    waited
+                }
+                Err(err) => Err(err),
+            };
+
+            let finished = match result {
+                Ok(finished) => finished,
+                Err(err) => {
+                    return Err(Error::Resubmission {
+                        attempts,
+                        source: Box::new(err),
+                    });
+                }
+            };
+
+            let attempt = attempts.len() as u32;
+            let resubmit = finished
+                .state
+                .is_some_and(|state| policy.retry_on.contains(&state));
+
+            if !resubmit || attempt >= policy.max_attempts {
+                return Ok(Resubmission {
+                    task: finished,
+                    attempts,
+                });
+            }
+
+            debug!(
+                "task `{}` finished as {:?}: resubmitting it (attempt {} of {})",
+                attempts[attempts.len() - 1],
+                finished.state,
+                attempt + 1,
+                policy.max_attempts
+            );
+
+            if let Some(mutate) = &policy.mutate {
+                mutate(&mut task, attempt + 1);
+            }
+        }
+    }
+
+    /// Downloads the outputs of a finished task into a directory.
+    ///
+    /// The outputs are those within the latest log of the task (see
+    /// [`Task::latest_log()`]), so the task should be retrieved with the
+    /// [`View::Full`] view (as returned by [`Self::wait_for_task()`]). Each
+    /// output is placed within `dir` at its path within the container (with
+    /// any leading `/` removed).
+    ///
+    /// Outputs with the `http` and `https` schemes are downloaded. Outputs
+    /// with the `file` scheme are only copied locally from within the
+    /// directory set with [`download::DownloadOptions::local_root()`], as their
+    /// URLs are reported by the service: by default, they fail with
+    /// [`download::Error::LocalFileNotAllowed`]. The credentials of this
+    /// client are only sent for outputs served from the same origin as the
+    /// service: other outputs are downloaded with a client without any
+    /// configured authentication or headers. When the task log reports the
+    /// size of an output, the size of the downloaded file is verified against
+    /// it. Each output is written to a partial file (its destination with a
+    /// `.part` extension appended) that is only moved into place once it has
+    /// been verified, so a failed download leaves its destination untouched.
+    ///
+    /// A failure to download one output does not stop the others from being
+    /// downloaded, so the result for each output is returned (along with its
+    /// URL) in the order the outputs are listed.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "client-download"))]
+    pub async fn download_outputs(
+        &self,
+        task: &Task,
+        dir: impl AsRef<std::path::Path>,
+        options: &download::DownloadOptions,
+    ) -> Vec<(
+        String,
+        std::result::Result<download::DownloadedFile, download::Error>,
+    )> {
+        let dir = dir.as_ref();
+        let outputs = task
+            .latest_log()
+            .and_then(|log| log.outputs.as_deref())
+            .unwrap_or_default();
+
+        // NOTE: the plain client is only built if an output is served from
+        // elsewhere.
+        let plain = std::sync::OnceLock::new();
+
+        let results = stream::iter(outputs)
+            .map(|output| {
+                let same_origin = Url::parse(&output.url)
+                    .map(|url| url.origin() == self.url.origin())
+                    .unwrap_or_default();
+                let client = if same_origin {
+                    &self.client
+                } else {
+                    plain.get_or_init(|| {
+                        reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build()
+                    })
+                };
+
+                async move {
+                    let result = download::download(client, output, dir, options).await;
+                    (output.url.clone(), result)
+                }
+            })
+            .buffered(options.concurrency)
+            .collect()
+            .await;
+
+        results
+    }
+
+    /// Reruns a task within the service.
+    ///
+    /// This method retrieves the full view of the task using
+    /// [`Self::get_task()`], converts it into a submittable [`Task`] via its
+    /// `TryFrom<task::Response>` implementation, and submits it using
+    /// [`Self::create_task()`].
+    pub async fn rerun_task(&self, id: impl AsRef<str>) -> Result<CreateTask> {
+        self.rerun_task_with(id, |_| {}).await
+    }
+
+    /// Reruns a task within the service after modifying it.
+    ///
+    /// This method behaves like [`Self::rerun_task()`], but calls `f` with the
+    /// task before it is resubmitted. This is useful for, say, retrying a task
+    /// with more memory.
+    pub async fn rerun_task_with<F>(&self, id: impl AsRef<str>, f: F) -> Result<CreateTask>
+    where
+        F: FnOnce(&mut Task),
+    {
+        let response = self.get_task(id, View::Full).await?;
+        let mut task = Task::try_from(response).map_err(Error::Task)?;
+        f(&mut task);
+
+        self.create_task(task).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt as _;
+    use tokio::io::AsyncWriteExt as _;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::v1::client::test_server;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+
+    #[tokio::test]
+    async fn tls_failures_mention_the_host() {
+        // A server that does not speak TLS.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(_) => return,
+                };
+
+                let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await;
+            }
+        });
+
+        let err = Builder::default()
+            .url_from_string(format!("https://{addr}/"))
+            .unwrap()
+            .retries(0)
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await
+            .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .starts_with("POST /tasks: failed to connect to `127.0.0.1`: "),
+            "{err}"
+        );
+
+        // NOTE: the underlying cause is only reported by the source chain.
+        let inner = err.inner();
+        let Error::Reqwest(reqwest) = inner else {
+            panic!("unexpected error: {inner}");
+        };
+        assert_eq!(
+            inner.to_string(),
+            format!("failed to connect to `127.0.0.1`: {reqwest}")
+        );
+        assert!(std::error::Error::source(inner).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_honor_retry_after() {
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(20)).to_rfc2822();
+        let server = Server::scripted(vec![
+            Response::new(503, "").header("retry-after", "10"),
+            Response::new(429, "").header("retry-after", date.replace("+0000", "GMT")),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(2)
+            // NOTE: the idle timeout of the pool would otherwise advance the
+            // paused clock.
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        client.get_task("123", View::Minimal).await.unwrap();
+        let elapsed = start.elapsed();
+
+        // NOTE: the date is truncated to whole seconds and compared against
+        // the (unpaused) system clock, so the second delay may be shorter.
+        assert_eq!(server.requests().len(), 3);
+        assert!(elapsed >= Duration::from_secs(28), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(31), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_after_is_capped() {
+        let server = Server::scripted(vec![
+            Response::new(503, "").header("retry-after", "3600"),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(1)
+            .max_retry_after(Duration::from_secs(5))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        client.get_task("123", View::Minimal).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert!(elapsed >= Duration::from_secs(5), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(6), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_respect_the_deadline() {
+        let server = Server::start(|_| Response::new(503, "")).await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retry_strategy(
+                strategy::default_backoff_with(10, Duration::from_secs(1), Duration::from_secs(60))
+                    .without_jitter(),
+            )
+            .retry_deadline(Duration::from_secs(10))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        let err = client.service_info().await.unwrap_err();
+        let elapsed = start.elapsed();
+
+        // NOTE: the retries after 1, 2, and 4 seconds fit within the deadline,
+        // but the retry after a further 8 seconds does not.
+        assert!(elapsed >= Duration::from_secs(7), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(8), "{elapsed:?}");
+        assert_eq!(server.requests().len(), 4);
+
+        match err.inner() {
+            Error::DeadlineExceeded { deadline, last } => {
+                assert_eq!(*deadline, Duration::from_secs(10));
+                assert!(
+                    matches!(**last, Error::RetriesExhausted { status, retries: 3 } if status == 503)
+                );
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deadline_is_checked_before_sleeping() {
+        let server = Server::start(|_| Response::new(503, "").header("retry-after", "60")).await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .retry_deadline(Duration::from_secs(30))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let start = tokio::time::Instant::now();
+        let err = client.service_info().await.unwrap_err();
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            err.to_string(),
+            "GET /service-info: retry deadline of 30s exceeded: service responded with 503 \
+             Service Unavailable after 0 retries"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn too_many_requests_are_retried() {
+        let server = Server::scripted(vec![
+            Response::new(429, ""),
+            Response::new(429, ""),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let task = Builder::default()
+            .url(server.url())
+            .retries(2)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .get_task("123", View::Minimal)
+            .await
+            .unwrap();
+
+        assert_eq!(task.into_minimal().unwrap().id, "123");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exhausted_retries_report_the_status() {
+        let server = Server::scripted(vec![Response::new(429, "")]).await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .retries(2)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .get_task("123", View::Minimal)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.inner(),
+            Error::RetriesExhausted {
+                status: StatusCode::TOO_MANY_REQUESTS,
+                retries: 2
+            }
+        ));
+        let elapsed = err.elapsed().unwrap();
+        let delays = err.attempts().iter().filter_map(Attempt::delay).sum();
+        assert!(elapsed >= delays);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "GET /tasks/123: failed after 3 attempts in {:.1}s (429, 429, 429): service \
+                 responded with 429 Too Many Requests after 2 retries",
+                elapsed.as_secs_f64()
+            )
+        );
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    /// Gets the idempotency key from a request to create a task.
+    fn idempotency_key_of(request: &test_server::Request) -> String {
+        let task: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        task["tags"][DEFAULT_IDEMPOTENCY_TAG]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn task_creation_is_not_retried_once_sent() {
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::json(r#"{"id":"123"}"#),
+        ])
+        .await;
+
+        let result = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idempotent_submission_finds_a_created_task() {
+        // NOTE: the first attempt created the task, but the service failed to
+        // respond.
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::json(r#"{"tasks":[{"id":"123","state":"QUEUED"}]}"#),
+        ])
+        .await;
+
+        let created = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .idempotency_tag(DEFAULT_IDEMPOTENCY_TAG)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await
+            .unwrap();
+
+        assert_eq!(created.id, "123");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(
+            requests[1].path,
+            format!(
+                "/tasks?view=MINIMAL&tag_key={DEFAULT_IDEMPOTENCY_TAG}&tag_value={}",
+                idempotency_key_of(&requests[0])
+            )
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idempotent_submission_retries_a_missing_task() {
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::json(r#"{"tasks":[]}"#),
+            Response::json(r#"{"id":"456"}"#),
+        ])
+        .await;
+
+        let created = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .idempotency_tag(DEFAULT_IDEMPOTENCY_TAG)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await
+            .unwrap();
+
+        assert_eq!(created.id, "456");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].method, "POST");
+        assert_eq!(
+            idempotency_key_of(&requests[0]),
+            idempotency_key_of(&requests[2])
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn large_bodies_are_resent_intact() {
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::json(r#"{"tasks":[]}"#),
+            Response::json(r#"{"id":"123"}"#),
+        ])
+        .await;
+
+        let content = "ACGT".repeat(1 << 18);
+        let task = Task {
+            inputs: Some(vec![crate::v1::types::task::Input {
+                path: String::from("/data/reads.fa"),
+                content: Some(content.clone()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        Builder::default()
+            .url(server.url())
+            .retries(1)
+            .idempotency_tag(DEFAULT_IDEMPOTENCY_TAG)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .create_task(task)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].body, requests[2].body);
+
+        let sent: Task = serde_json::from_slice(&requests[2].body).unwrap();
+        assert_eq!(sent.inputs.unwrap()[0].content.as_deref(), Some(&*content));
+    }
+
+    #[tokio::test]
+    async fn tag_filters_are_validated() {
+        let server = Server::scripted(vec![Response::json(
+            r#"{"tasks":[],"next_page_token":null}"#,
+        )])
+        .await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let params = |keys: &[&str], values: &[&str]| ListTasksParams {
+            tag_keys: keys.iter().map(ToString::to_string).collect(),
+            tag_values: values.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+
+        client
+            .list_tasks(Some(&params(&["a", "b"], &["1", "2"])))
+            .await
+            .unwrap();
+        client
+            .list_tasks(Some(&params(&["a", "b"], &["1"])))
+            .await
+            .unwrap();
+
+        let err = client
+            .list_tasks(Some(&params(&["a"], &["1", "2"])))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Validation(ValidationError::TagFilterArityMismatch { keys: 1, values: 2 })
+        ));
+
+        let paths = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "/tasks?tag_key=a&tag_value=1&tag_key=b&tag_value=2",
+                "/tasks?tag_key=a&tag_value=1&tag_key=b&tag_value=",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn listing_with_a_view() {
+        let server = Server::scripted(vec![Response::json(
+            r#"{"tasks":[{"id":"123","state":"RUNNING","executors":[{"image":"ubuntu","command":["true"]}]}]}"#,
+        )])
+        .await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        // NOTE: the view of the parameters is ignored.
+        let params = ListTasksParams::builder()
+            .name_prefix("a")
+            .view(View::Full)
+            .build();
+        let tasks = client.list_tasks_minimal(Some(&params)).await.unwrap();
+        assert_eq!(tasks.tasks[0].id, "123");
+
+        let tasks = client.list_tasks_basic(None).await.unwrap();
+        assert_eq!(tasks.tasks[0].executors[0].image, "ubuntu");
+
+        let tasks = client.list_tasks_full(None).await.unwrap();
+        assert_eq!(tasks.tasks[0].id.as_deref(), Some("123"));
+
+        client.list_tasks(None).await.unwrap();
+
+        let paths = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "/tasks?name_prefix=a&view=MINIMAL",
+                "/tasks?view=BASIC",
+                "/tasks?view=FULL",
+                "/tasks",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn counting_tasks_by_state() {
+        let server = Server::scripted(vec![
+            Response::json(
+                r#"{"tasks":[{"id":"1","state":"COMPLETE"},{"id":"2","state":"RUNNING"},{"id":"3"}],"next_page_token":"a"}"#,
+            ),
+            Response::json(
+                r#"{"tasks":[{"id":"4","state":"COMPLETE"},{"id":"5","state":"QUEUED"}]}"#,
+            ),
+        ])
+        .await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let params = ListTasksParams::builder()
+            .tag_exists("project")
+            .view(View::Full)
+            .build();
+        let counts = client.count_tasks_by_state(params).await.unwrap();
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [
+                (State::Unknown, 1),
+                (State::Queued, 1),
+                (State::Running, 1),
+                (State::Complete, 2),
+            ]
+        );
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[1].path,
+            "/tasks?tag_key=project&tag_value=&page_token=a&view=MINIMAL"
+        );
+    }
+
+    #[tokio::test]
+    async fn validating_tasks() {
+        use crate::v1::types::task::Executor;
+        use crate::v1::types::task::Input;
+        use crate::v1::types::task::Output;
+
+        let server = Server::start(|_| {
+            Response::json(
+                r#"{"id":"org.ga4gh.myservice","name":"My Server","type":{"group":"org.ga4gh","artifact":"tes","version":"1.0.0"},"organization":{"name":"My Organization","url":"https://example.com/"},"version":"1.5.0","storage":["file:///path/to/local/funnel-storage","s3://ohsu-compbio-funnel/storage"]}"#,
+            )
+        })
+        .await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let mut task = Task {
+            executors: vec![Executor {
+                image: String::from("ubuntu"),
+                workdir: Some(String::from("work")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        match client.validate_task(&task).await {
+            Err(Error::Validation(ValidationError::InvalidTask(errors))) => assert_eq!(
+                errors
+                    .iter()
+                    .map(validation::Error::field)
+                    .collect::<Vec<_>>(),
+                ["executors[0].command", "executors[0].workdir"]
+            ),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        task.executors[0].command = vec![String::from("true")];
+
+        // NOTE: the structure is checked without making a request.
+        assert!(server.requests().is_empty());
+
+        task.executors[0].workdir = None;
+        task.inputs = Some(vec![
+            Input {
+                url: Some(String::from("S3://bucket/a.txt")),
+                path: String::from("/inputs/a.txt"),
+                ..Default::default()
+            },
+            Input {
+                url: Some(String::from("gs://bucket/b.txt")),
+                path: String::from("/inputs/b.txt"),
+                ..Default::default()
+            },
+            Input {
+                path: String::from("/inputs/c.txt"),
+                content: Some(String::from("hello")),
+                ..Default::default()
+            },
+        ]);
+        task.outputs = Some(vec![Output {
+            url: String::from("https://example.com/d.txt"),
+            path: String::from("/outputs/d.txt"),
+            ..Default::default()
+        }]);
+
+        let warnings = client.validate_task(&task).await.unwrap();
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|warning| match warning {
+                    ValidationWarning::UnsupportedStorage { url, supported } => {
+                        assert_eq!(supported.len(), 2);
+                        url.as_str()
+                    }
+                    warning => panic!("unexpected warning: {warning}"),
+                })
+                .collect::<Vec<_>>(),
+            ["gs://bucket/b.txt", "https://example.com/d.txt"]
+        );
+        assert!(warnings[0].to_string().contains("gs://bucket/b.txt"));
+        assert_eq!(server.requests().len(), 1);
+
+        let options = validation::Options::default().content_limit(4, validation::Severity::Error);
+        match client.validate_task_with(&task, &options).await {
+            Err(Error::Validation(ValidationError::InvalidTask(errors))) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].field(), "inputs[2].content");
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+        assert_eq!(server.requests().len(), 1);
+
+        let options =
+            validation::Options::default().content_limit(4, validation::Severity::Warning);
+        let warnings = client.validate_task_with(&task, &options).await.unwrap();
+        assert!(matches!(
+            &warnings[0],
+            ValidationWarning::Task(validation::Error::ContentTooLarge { size: 5, .. })
+        ));
+        assert_eq!(warnings.len(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reporting_wait_progress() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE","executors":[]}"#),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let options = WaitOptions::default()
+            .interval(Duration::from_secs(2))
+            .on_progress({
+                let reported = reported.clone();
+                move |progress| reported.lock().unwrap().push(progress.clone())
+            });
+        client.wait_for_task("123", &options).await.unwrap();
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(
+            reported
+                .iter()
+                .map(|progress| (progress.state, progress.polls))
+                .collect::<Vec<_>>(),
+            [
+                (State::Queued, 1),
+                (State::Running, 2),
+                (State::Running, 3),
+                (State::Complete, 4)
+            ]
+        );
+        assert!(reported.iter().all(|progress| progress.id == "123"));
+
+        // NOTE: the state did not change between the second and third polls.
+        assert_eq!(reported[2].last_change, reported[1].elapsed);
+        assert!(reported[2].elapsed >= Duration::from_secs(4));
+        assert_eq!(reported[3].last_change, reported[3].elapsed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_tasks() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE","executors":[]}"#),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default().interval(Duration::from_secs(2));
+        let start = tokio::time::Instant::now();
+        let task = client.wait_for_task("123", &options).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(task.state, Some(State::Complete));
+        assert!(elapsed >= Duration::from_secs(6), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(7), "{elapsed:?}");
+
+        let paths = server
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "/tasks/123?view=MINIMAL",
+                "/tasks/123?view=MINIMAL",
+                "/tasks/123?view=MINIMAL",
+                "/tasks/123?view=MINIMAL",
+                "/tasks/123?view=FULL",
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_preempted_tasks() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"PREEMPTED"}"#),
+            Response::json(include_str!(
+                "../../tests/fixtures/responses/task-preempted.json"
+            )),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default().interval(Duration::from_secs(2));
+        let task = client.wait_for_task("123", &options).await.unwrap();
+
+        assert_eq!(task.state, Some(State::Preempted));
+        assert_eq!(
+            task.system_log_lines().collect::<Vec<_>>(),
+            ["the instance running the task was preempted"]
+        );
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_tasks_times_out() {
+        let server = Server::start(|_| Response::json(r#"{"id":"123","state":"RUNNING"}"#)).await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default()
+            .interval(Duration::from_secs(5))
+            .timeout(Duration::from_secs(12))
+            .view(View::Minimal);
+        let start = tokio::time::Instant::now();
+        let err = client.wait_for_task("123", &options).await.unwrap_err();
+        let elapsed = start.elapsed();
+
+        assert!(matches!(
+            &err,
+            Error::WaitTimeout {
+                id,
+                last_state: Some(State::Running),
+                ..
+            } if id == "123"
+        ));
+        assert!(err.is_timeout());
+        assert_eq!(
+            err.to_string(),
+            "task `123` did not finish within 12.0s (last state: RUNNING)"
+        );
+
+        // NOTE: the final poll happens when the timeout elapses.
+        assert_eq!(server.requests().len(), 4);
+        assert!(elapsed >= Duration::from_secs(12), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(13), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn creating_and_waiting_for_tasks() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"EXECUTOR_ERROR"}"#),
+            Response::json(
+                r#"{"id":"123","state":"EXECUTOR_ERROR","executors":[],"logs":[{"logs":[{"exit_code":1}]}]}"#,
+            ),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let (created, task) = client
+            .create_task_and_wait(Task::default(), &WaitOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(created.id, "123");
+        assert_eq!(task.state, Some(State::ExecutorError));
+        assert_eq!(task.logs.unwrap()[0].logs[0].exit_code, Some(1));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/tasks");
+        assert_eq!(requests[3].path, "/tasks/123?view=FULL");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_tasks_cancels_them_on_timeout() {
+        let server = Server::start(|request| match request.method.as_str() {
+            "POST" if request.path == "/tasks" => Response::json(r#"{"id":"123"}"#),
+            "POST" => Response::json("{}"),
+            _ => Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default()
+            .timeout(Duration::from_secs(7))
+            .cancel_on_timeout(true);
+        let err = client
+            .create_task_and_wait(Task::default(), &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::WaitTimeout {
+                last_state: Some(State::Queued),
+                ..
+            }
+        ));
+
+        // NOTE: queued tasks are polled every 30 seconds by default, so the
+        // task is polled when the wait starts and when the timeout elapses.
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        let last = requests.last().unwrap();
+        assert_eq!(last.method, "POST");
+        assert_eq!(last.path, "/tasks/123:cancel");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_multiple_tasks() {
+        let polls = std::sync::Mutex::new(0);
+        let server = Server::start(move |request| match request.path.as_str() {
+            "/tasks/1?view=MINIMAL" => Response::json(r#"{"id":"1","state":"CANCELED"}"#),
+            "/tasks/1?view=BASIC" => {
+                Response::json(r#"{"id":"1","state":"CANCELED","executors":[]}"#)
+            }
+            "/tasks/3?view=MINIMAL" => {
+                let mut polls = polls.lock().unwrap();
+                *polls += 1;
+                match *polls {
+                    1 => Response::json(r#"{"id":"3","state":"INITIALIZING"}"#),
+                    _ => Response::json(r#"{"id":"3","state":"COMPLETE"}"#),
+                }
+            }
+            "/tasks/3?view=BASIC" => {
+                Response::json(r#"{"id":"3","state":"COMPLETE","executors":[]}"#)
+            }
+            "/tasks/4?view=MINIMAL" => Response::json(r#"{"id":"4","state":"PAUSED"}"#),
+            _ => Response::new(404, ""),
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default()
+            .interval(Duration::from_secs(2))
+            .timeout(Duration::from_secs(3))
+            .view(View::Basic);
+        let results = client.wait_for_tasks(["1", "2", "3", "4"], &options).await;
+
+        let ids = results
+            .iter()
+            .map(|(id, _)| id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "2", "3", "4"]);
+        assert_eq!(results[0].1.as_ref().unwrap().state, Some(State::Canceled));
+        assert!(matches!(
+            results[1].1.as_ref().unwrap_err().inner(),
+            Error::TaskNotFound { id } if id == "2"
+        ));
+        assert_eq!(results[2].1.as_ref().unwrap().state, Some(State::Complete));
+        assert!(matches!(
+            results[3].1.as_ref().unwrap_err(),
+            Error::WaitTimeout {
+                last_state: Some(State::Paused),
+                ..
+            }
+        ));
+
+        // NOTE: finished and unknown tasks are not polled again.
+        let requests = server.requests();
+        let count = |path: &str| {
+            requests
+                .iter()
+                .filter(|request| request.path == path)
+                .count()
+        };
+        assert_eq!(count("/tasks/1?view=MINIMAL"), 1);
+        assert_eq!(count("/tasks/2?view=MINIMAL"), 1);
+        assert_eq!(count("/tasks/3?view=MINIMAL"), 2);
+        assert_eq!(count("/tasks/4?view=MINIMAL"), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_multiple_tasks_caps_concurrency() {
+        let server = Server::start(|request| {
+            let response = if request.path.ends_with("MINIMAL") {
+                Response::json(r#"{"id":"1","state":"COMPLETE"}"#)
+            } else {
+                Response::json(r#"{"id":"1","state":"COMPLETE","executors":[]}"#)
+            };
+
+            response.delay(Duration::from_secs(1))
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default().concurrency(2);
+        let start = tokio::time::Instant::now();
+        let results = client
+            .wait_for_tasks((1..=6).map(|id| id.to_string()), &options)
+            .await;
+        let elapsed = start.elapsed();
+
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(server.requests().len(), 12);
+
+        // NOTE: with two requests in flight, each of the six polls and the six
+        // retrievals take three rounds of a second.
+        assert!(elapsed >= Duration::from_secs(6), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(7), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watching_tasks() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+            Response::json(r#"{"id":"123","state":"QUEUED"}"#),
+            Response::json(r#"{"id":"123","state":"INITIALIZING"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE"}"#),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let changes = client
+            .watch_task("123", Duration::from_secs(1))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let transitions = changes
+            .iter()
+            .map(|change| (change.from, change.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            transitions,
+            [
+                (None, State::Queued),
+                (Some(State::Queued), State::Initializing),
+                (Some(State::Initializing), State::Running),
+                (Some(State::Running), State::Complete),
+            ]
+        );
+        assert!(
+            changes
+                .windows(2)
+                .all(|pair| pair[0].observed_at <= pair[1].observed_at)
+        );
+
+        // NOTE: the stream ends without polling the finished task again.
+        let requests = server.requests();
+        assert_eq!(requests.len(), 6);
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.path == "/tasks/123?view=MINIMAL")
+        );
+
+        let server = Server::start(|_| Response::new(404, "")).await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+        let results = client
+            .watch_task("456", Duration::from_secs(1))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0].as_ref().unwrap_err().inner(),
+            Error::TaskNotFound { id } if id == "456"
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tailing_logs() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"QUEUED","executors":[]}"#),
+            Response::json(
+                r#"{"id":"123","state":"RUNNING","executors":[],"logs":[{"logs":[{"stdout":"one\n"}]}]}"#,
+            ),
+            Response::json(
+                r#"{"id":"123","state":"RUNNING","executors":[],"logs":[{"logs":[{"stdout":"one\n"}]}]}"#,
+            ),
+            Response::json(
+                r#"{"id":"123","state":"RUNNING","executors":[],"logs":[{"logs":[{"stdout":"one\ntwo\n","stderr":"warning\n"}]}]}"#,
+            ),
+            Response::json(
+                r#"{"id":"123","state":"COMPLETE","executors":[],"logs":[{"logs":[{"stdout":"two\nthree\n","stderr":"warning\n"}]}]}"#,
+            ),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let chunks = client
+            .tail_logs("123", Duration::from_secs(1))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        let output = |stream, text: &str| LogChunk::Output {
+            executor: 0,
+            stream,
+            text: text.to_string(),
+        };
+        assert_eq!(
+            chunks,
+            [
+                output(wait::OutputStream::Stdout, "one\n"),
+                output(wait::OutputStream::Stdout, "two\n"),
+                output(wait::OutputStream::Stderr, "warning\n"),
+                LogChunk::Reset {
+                    executor: 0,
+                    stream: wait::OutputStream::Stdout,
+                },
+                output(wait::OutputStream::Stdout, "two\nthree\n"),
+            ]
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 5);
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.path == "/tasks/123?view=FULL")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_adapts_to_states() {
+        /// Starts a server that walks a task from queued to complete and
+        /// records when it was polled.
+        async fn walk() -> (Server, Arc<std::sync::Mutex<Vec<Duration>>>) {
+            let states = [
+                "QUEUED", "QUEUED", "RUNNING", "RUNNING", "RUNNING", "COMPLETE",
+            ];
+            let start = tokio::time::Instant::now();
+            let times = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+            let recorded = times.clone();
+            let server = Server::start(move |_| {
+                let mut times = recorded.lock().unwrap();
+                let state = states[times.len().min(states.len() - 1)];
+                times.push(start.elapsed());
+                Response::json(format!(
+                    r#"{{"id":"123","state":"{state}","executors":[]}}"#
+                ))
+            })
+            .await;
+
+            (server, times)
+        }
+
+        let policy = PollPolicy::fixed(Duration::from_secs(1))
+            .state_interval(State::Queued, Duration::from_secs(10))
+            .state_interval(State::Running, Duration::from_secs(2))
+            .growth(2.0)
+            .max_interval(Duration::from_secs(30));
+
+        // NOTE: the interval doubles while the state is unchanged (from 10
+        // to 20 seconds while queued and from 2 to 4 to 8 seconds while
+        // running).
+        let expected = [0, 10, 30, 32, 36, 44].map(Duration::from_secs);
+
+        let (server, times) = walk().await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+        let options = WaitOptions::default().poll_policy(policy.clone());
+        let task = client.wait_for_task("123", &options).await.unwrap();
+        assert_eq!(task.state, Some(State::Complete));
+
+        let times = times.lock().unwrap().clone();
+        assert_eq!(times.len(), expected.len() + 1);
+        for (time, expected) in times.iter().zip(&expected) {
+            assert!(*time >= *expected, "{times:?}");
+            assert!(*time < *expected + Duration::from_millis(500), "{times:?}");
+        }
+
+        let (server, times) = walk().await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+        let changes = client
+            .watch_task("123", policy)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 3);
+
+        let times = times.lock().unwrap().clone();
+        assert_eq!(times.len(), expected.len());
+        for (time, expected) in times.iter().zip(&expected) {
+            assert!(*time >= *expected, "{times:?}");
+            assert!(*time < *expected + Duration::from_millis(500), "{times:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn canceling_matching_tasks() {
+        let server = Server::start(|request| match request.path.as_str() {
+            "/tasks?tag_key=run_id&tag_value=abc123&view=MINIMAL" => Response::json(
+                r#"{"tasks":[{"id":"1","state":"RUNNING"},{"id":"2","state":"COMPLETE"},{"id":"3"}],"next_page_token":"a"}"#,
+            ),
+            "/tasks?tag_key=run_id&tag_value=abc123&page_token=a&view=MINIMAL" => Response::json(
+                r#"{"tasks":[{"id":"4","state":"CANCELED"},{"id":"5","state":"QUEUED"},{"id":"6","state":"SYSTEM_ERROR"}]}"#,
+            ),
+            "/tasks/5:cancel" => Response::new(404, ""),
+            path if path.ends_with(":cancel") => Response::json("{}"),
+            _ => Response::new(400, ""),
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .try_build()
+            .unwrap();
+        let params = ListTasksParams::builder()
+            .tag("run_id", "abc123")
+            .view(View::Full)
+            .build();
+
+        let report = client
+            .cancel_matching(params.clone(), &CancelOptions::default().dry_run(true))
+            .await
+            .unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.canceled, ["1", "3", "5"]);
+        assert_eq!(report.skipped, 3);
+        assert!(report.is_success());
+        assert!(
+            server
+                .requests()
+                .iter()
+                .all(|request| request.method == "GET")
+        );
+
+        let report = client
+            .cancel_matching(params, &CancelOptions::default().concurrency(2))
+            .await
+            .unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.canceled, ["1", "3"]);
+        assert_eq!(report.skipped, 3);
+        assert!(!report.is_success());
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, "5");
+        assert!(matches!(
+            report.failed[0].1.inner(),
+            Error::TaskNotFound { id } if id == "5"
+        ));
+
+        let mut canceled = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "POST")
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        canceled.sort();
+        assert_eq!(
+            canceled,
+            ["/tasks/1:cancel", "/tasks/3:cancel", "/tasks/5:cancel"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_with_deadlines() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123"}"#),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE"}"#),
+            Response::json(r#"{"id":"123","state":"COMPLETE","executors":[]}"#),
+        ])
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default().interval(Duration::from_secs(1));
+        let (created, task) = client
+            .run_with_deadline(Task::default(), Duration::from_secs(60), &options)
+            .await
+            .unwrap();
+        assert_eq!(created.id, "123");
+        assert_eq!(task.state, Some(State::Complete));
+        assert!(
+            server
+                .requests()
+                .iter()
+                .all(|request| !request.path.ends_with(":cancel"))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_past_their_deadlines() {
+        let canceled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let (flag, count) = (canceled.clone(), attempts.clone());
+        let server = Server::start(move |request| {
+            use std::sync::atomic::Ordering;
+
+            match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/tasks") => Response::json(r#"{"id":"123"}"#),
+                // NOTE: the first attempt to cancel the task fails.
+                ("POST", _) if count.fetch_add(1, Ordering::SeqCst) == 0 => Response::new(503, ""),
+                ("POST", _) => {
+                    flag.store(true, Ordering::SeqCst);
+                    Response::json("{}")
+                }
+                (_, "/tasks/123?view=FULL") => {
+                    Response::json(r#"{"id":"123","state":"CANCELED","executors":[]}"#)
+                }
+                _ if flag.load(Ordering::SeqCst) => {
+                    Response::json(r#"{"id":"123","state":"CANCELED"}"#)
+                }
+                _ => Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            }
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .retries(1)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default()
+            .interval(Duration::from_secs(1))
+            .cancel_on_timeout(false);
+        let err = client
+            .run_with_deadline(Task::default(), Duration::from_secs(5), &options)
+            .await
+            .unwrap_err();
+
+        match &err {
+            Error::TaskDeadlineExceeded {
+                id,
+                deadline,
+                last_state,
+            } => {
+                assert_eq!(id, "123");
+                assert_eq!(*deadline, Duration::from_secs(5));
+                assert_eq!(*last_state, Some(State::Canceled));
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+        assert!(err.is_timeout());
+        assert_eq!(
+            err.to_string(),
+            "task `123` did not finish within its deadline of 5.0s and was canceled (last state: \
+             CANCELED)"
+        );
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_that_fail_to_be_canceled_past_their_deadlines() {
+        let server =
+            Server::start(
+                |request| match (request.method.as_str(), request.path.as_str()) {
+                    ("POST", "/tasks") => Response::json(r#"{"id":"123"}"#),
+                    ("POST", _) => Response::new(500, ""),
+                    _ => Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+                },
+            )
+            .await;
+        let client = Builder::default()
+            .url(server.url())
+            .retries(1)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default()
+            .interval(Duration::from_secs(1))
+            .cancel_grace_period(Duration::from_secs(3));
+        let err = client
+            .run_with_deadline(Task::default(), Duration::from_secs(5), &options)
+            .await
+            .unwrap_err();
+
+        // NOTE: the failure to cancel the task does not hide the deadline.
+        match &err {
+            Error::TaskDeadlineExceeded { id, last_state, .. } => {
+                assert_eq!(id, "123");
+                assert_eq!(*last_state, Some(State::Running));
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        let cancels = server
+            .requests()
+            .iter()
+            .filter(|request| request.path.ends_with(":cancel"))
+            .count();
+        assert_eq!(cancels, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_that_are_being_canceled_past_their_deadlines() {
+        let server =
+            Server::start(
+                |request| match (request.method.as_str(), request.path.as_str()) {
+                    ("POST", "/tasks") => Response::json(r#"{"id":"123"}"#),
+                    ("POST", _) => Response::json("{}"),
+                    _ => Response::json(r#"{"id":"123","state":"CANCELING"}"#),
+                },
+            )
+            .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let options = WaitOptions::default().interval(Duration::from_secs(1));
+        let start = tokio::time::Instant::now();
+        let err = client
+            .run_with_deadline(Task::default(), Duration::from_secs(5), &options)
+            .await
+            .unwrap_err();
+
+        // NOTE: a task that is being canceled does not wait out the grace period.
+        assert!(start.elapsed() < Duration::from_secs(6));
+        assert!(matches!(
+            err,
+            Error::TaskDeadlineExceeded {
+                last_state: Some(State::Canceling),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_that_finish_while_being_canceled() {
+        let server =
+            Server::start(
+                |request| match (request.method.as_str(), request.path.as_str()) {
+                    ("POST", "/tasks") => Response::json(r#"{"id":"123"}"#),
+                    // NOTE: the service refuses to cancel a task that has finished.
+                    ("POST", _) => Response::new(400, r#"{"message":"task already finished"}"#),
+                    (_, "/tasks/123?view=FULL") => {
+                        Response::json(r#"{"id":"123","state":"COMPLETE","executors":[]}"#)
+                    }
+                    _ => Response::json(r#"{"id":"123","state":"COMPLETE"}"#),
+                },
+            )
+            .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        // NOTE: a zero deadline passes before the task is observed to have
+        // finished.
+        let (_, task) = client
+            .run_with_deadline(Task::default(), Duration::ZERO, &WaitOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(task.state, Some(State::Complete));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_tasks_with_resubmission() {
+        let created = std::sync::atomic::AtomicUsize::new(0);
+        let server = Server::start(move |request| {
+            use std::sync::atomic::Ordering;
+
+            if request.method == "POST" {
+                let id = created.fetch_add(1, Ordering::SeqCst) + 1;
+                return Response::json(format!(r#"{{"id":"{id}"}}"#));
+            }
+
+            // NOTE: the first two attempts are preempted.
+            let state = match request.path.split(['/', '?']).nth(2) {
+                Some("1") | Some("2") => "PREEMPTED",
+                _ => "COMPLETE",
+            };
+            Response::json(format!(r#"{{"id":"1","state":"{state}","executors":[]}}"#))
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let policy = ResubmitPolicy::default().mutate(|task, attempt| {
+            task.name = Some(format!("attempt-{attempt}"));
+            if attempt == 3 {
+                task.resources
+                    .get_or_insert_with(Default::default)
+                    .preemptible = Some(false);
+            }
+        });
+        let task = Task {
+            resources: Some(crate::v1::types::task::Resources {
+                preemptible: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let resubmission = client
+            .run_with_resubmission(task, &policy, &WaitOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(resubmission.task.state, Some(State::Complete));
+        assert_eq!(resubmission.attempts, ["1", "2", "3"]);
+
+        let submitted = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "POST")
+            .map(|request| serde_json::from_slice::<Task>(&request.body).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(submitted.len(), 3);
+        assert_eq!(submitted[0].name, None);
+        assert_eq!(submitted[1].name.as_deref(), Some("attempt-2"));
+        assert_eq!(submitted[2].name.as_deref(), Some("attempt-3"));
+        let preemptible = submitted
+            .iter()
+            .map(|task| task.resources.as_ref().unwrap().preemptible)
+            .collect::<Vec<_>>();
+        assert_eq!(preemptible, [Some(true), Some(true), Some(false)]);
+
+        // NOTE: the final attempt is returned once every attempt was used.
+        let resubmission = client
+            .run_with_resubmission(
+                Task::default(),
+                &ResubmitPolicy::default().max_attempts(1),
+                &WaitOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resubmission.task.state, Some(State::Complete));
+        assert_eq!(resubmission.attempts, ["4"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resubmission_errors_report_the_attempts() {
+        let created = std::sync::atomic::AtomicUsize::new(0);
+        let server = Server::start(move |request| {
+            use std::sync::atomic::Ordering;
+
+            if request.method == "POST" {
+                let id = created.fetch_add(1, Ordering::SeqCst) + 1;
+                return Response::json(format!(r#"{{"id":"{id}"}}"#));
+            }
+
+            // NOTE: the second attempt is lost by the service.
+            match request.path.split(['/', '?']).nth(2) {
+                Some("1") => Response::json(r#"{"id":"1","state":"PREEMPTED","executors":[]}"#),
+                _ => Response::new(404, ""),
+            }
+        })
+        .await;
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let err = client
+            .run_with_resubmission(
+                Task::default(),
+                &ResubmitPolicy::default(),
+                &WaitOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+        match &err {
+            Error::Resubmission { attempts, .. } => assert_eq!(attempts, &["1", "2"]),
+            err => panic!("unexpected error: {err}"),
+        }
+        assert!(matches!(err.inner(), Error::TaskNotFound { id } if id == "2"));
+        assert!(
+            err.to_string()
+                .starts_with("failed after attempting task(s) `1`, `2`: "),
+            "{err}"
+        );
+    }
+
+    /// Gets the parameters to list tasks with the minimal view.
+    fn minimal() -> ListTasksParams {
+        ListTasksParams::builder().view(View::Minimal).build()
+    }
+
+    #[tokio::test]
+    async fn task_streams_follow_pages() {
+        let server = Server::start(|request| match request.path.as_str() {
+            "/tasks?view=MINIMAL" => Response::json(
+                r#"{"tasks":[{"id":"1","state":"COMPLETE"},{"id":"2","state":"COMPLETE"}],"next_page_token":"a"}"#,
+            ),
+            // NOTE: an empty page may still be followed by more tasks.
+            "/tasks?page_token=a&view=MINIMAL" => {
+                Response::json(r#"{"tasks":[],"next_page_token":"b"}"#)
+            }
+            "/tasks?page_token=b&view=MINIMAL" => Response::json(
+                r#"{"tasks":[{"id":"3","state":"RUNNING"}],"next_page_token":""}"#,
+            ),
+            _ => Response::new(500, ""),
+        })
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .try_build()
+            .unwrap();
+
+        let ids = client
+            .list_tasks_stream(minimal())
+            .map_ok(|task| task.into_minimal().unwrap().id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, ["1", "2", "3"]);
+        assert_eq!(server.requests().len(), 3);
+
+        // NOTE: pages are only requested as the stream is polled.
+        {
+            let mut stream = pin!(client.list_tasks_stream(minimal()));
+            stream.try_next().await.unwrap().unwrap();
+            stream.try_next().await.unwrap().unwrap();
+        }
+        assert_eq!(server.requests().len(), 4);
+
+        let tasks = client.list_all_tasks(minimal()).await.unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        let tasks = client
+            .list_all_tasks_with_limit(minimal(), 3)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        let err = client
+            .list_all_tasks_with_limit(minimal(), 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::TooManyTasks { max: 2 }));
+        assert_eq!(
+            err.to_string(),
+            "the service listed more than the maximum of 2 tasks"
+        );
+    }
+
+    #[tokio::test]
+    async fn task_streams_detect_misbehaving_services() {
+        let server = Server::start(|request| match request.path.as_str() {
+            "/tasks?view=MINIMAL" => Response::json(
+                r#"{"tasks":[{"id":"1","state":"COMPLETE"},{"id":"2","state":"COMPLETE"}],"next_page_token":"a"}"#,
+            ),
+            // NOTE: this page repeats a task and the page token.
+            _ => Response::json(
+                r#"{"tasks":[{"id":"2","state":"COMPLETE"},{"id":"3","state":"COMPLETE"}],"next_page_token":"a"}"#,
+            ),
+        })
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .try_build()
+            .unwrap();
+
+        let results = client
+            .list_tasks_stream(minimal())
+            .collect::<Vec<_>>()
+            .await;
+        let ids = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok())
+            .map(|task| task.as_minimal().unwrap().id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "2", "2", "3"]);
+
+        let err = results.last().unwrap().as_ref().unwrap_err();
+        assert!(matches!(err, Error::PaginationLoop { page: 3 }));
+        assert_eq!(
+            err.to_string(),
+            "the service returned the token for page 3 more than once while listing tasks"
+        );
+        assert_eq!(server.requests().len(), 2);
+
+        let results = client
+            .list_tasks_stream_deduplicated(minimal())
+            .collect::<Vec<_>>()
+            .await;
+        let ids = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok())
+            .map(|task| task.as_minimal().unwrap().id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["1", "2", "3"]);
+        assert!(matches!(
+            results.last().unwrap(),
+            Err(Error::PaginationLoop { .. })
+        ));
+
+        // NOTE: a page token that the listing started from also counts.
+        let params = ListTasksParams::builder()
+            .page_token(PageToken::new("a").unwrap())
+            .view(View::Minimal)
+            .build();
+        let err = client.list_all_tasks(params).await.unwrap_err();
+        assert!(matches!(err, Error::PaginationLoop { page: 2 }));
+    }
+
+    #[test]
+    fn recent_ids_are_bounded() {
+        let mut ids = RecentIds::default();
+        assert!(ids.insert("0"));
+        assert!(!ids.insert("0"));
+
+        for id in 1..MAX_DEDUPLICATED_TASK_IDS {
+            assert!(ids.insert(&id.to_string()));
+        }
+
+        assert!(!ids.insert("0"));
+        assert!(ids.insert(&MAX_DEDUPLICATED_TASK_IDS.to_string()));
+        assert!(ids.insert("0"));
+        assert_eq!(ids.ids.len(), MAX_DEDUPLICATED_TASK_IDS);
+    }
+
+    #[tokio::test]
+    async fn task_streams_end_with_errors() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"tasks":[{"id":"1","state":"COMPLETE"}],"next_page_token":"a"}"#),
+            Response::new(400, r#"{"message":"invalid page token"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(0)
+            .try_build()
+            .unwrap();
+
+        let results = client
+            .list_tasks_stream(minimal())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(err.server_message(), Some("invalid page token"));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn validation_errors() {
+        let err = Error::from(ValidationError::TagFilterArityMismatch { keys: 1, values: 2 });
+        assert!(matches!(
+            err,
+            Error::Validation(ValidationError::TagFilterArityMismatch { keys: 1, values: 2 })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid request: 2 tag value(s) were provided for only 1 tag key(s)"
+        );
+
+        let err = Error::from(ValidationError::PageSizeTooLarge {
+            given: 4096,
+            max: 2047,
+        });
+        assert!(matches!(
+            err,
+            Error::Validation(ValidationError::PageSizeTooLarge {
+                given: 4096,
+                max: 2047
+            })
+        ));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn decode_failures_are_permanent() {
+        let server = Server::start(|_| Response::new(200, "<html>not json</html>")).await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap()
+            .get_task("123", View::Minimal)
+            .await
+            .unwrap_err();
+
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(err.endpoint(), Some((&Method::GET, "/tasks/123")));
+        match err.inner() {
+            Error::Decode { status, body, .. } => {
+                assert_eq!(*status, StatusCode::OK);
+                assert_eq!(body, "<html>not json</html>");
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        assert_eq!(
+            err.to_string(),
+            "GET /tasks/123: failed to decode the response (200 OK) at `.`: expected value at \
+             line 1 column 1; body: \"<html>not json</html>\""
+        );
+
+        #[cfg(feature = "diagnostics")]
+        assert!(
+            miette::Diagnostic::help(&err)
+                .unwrap()
+                .to_string()
+                .contains("`/ga4gh/tes/v1` prefix")
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_failures_report_the_path() {
+        let server = Server::scripted(vec![Response::json(
+            r#"{"tasks":[
+                {"id":"1","state":"COMPLETE","executors":[]},
+                {"id":"2","state":"COMPLETE","executors":[],"logs":[{"logs":[
+                    {"start_time":"2024-01-01T00:00:00Z","exit_code":0},
+                    {"start_time":"2024-01-01T00:00:00Z","exit_code":"1"}
+                ]}]}
+            ]}"#,
+        )])
+        .await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .try_build()
+            .unwrap()
+            .list_tasks(Some(&ListTasksParams::builder().view(View::Full).build()))
+            .await
+            .unwrap_err();
+
+        match err.inner() {
+            Error::Decode { path, .. } => assert_eq!(path, "tasks[1].logs[0].logs[1].exit_code"),
+            err => panic!("unexpected error: {err}"),
+        }
+
+        assert!(
+            err.to_string()
+                .contains("at `tasks[1].logs[0].logs[1].exit_code`: invalid type: string"),
+            "{err}"
+        );
+
+        // NOTE: a TES service returning an unexpected value is pointed at the
+        // failing path rather than at the URL.
+        #[cfg(feature = "diagnostics")]
+        assert_eq!(
+            miette::Diagnostic::help(&err).unwrap().to_string(),
+            "the service does not conform to the TES specification: report the value at \
+             `tasks[1].logs[0].logs[1].exit_code` to the maintainers of the service"
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_failures_report_nulls() {
+        // NOTE: this is the shape of a response from a service that returns
+        // `null` for empty lists.
+        let server = Server::scripted(vec![
+            Response::json(
+                r#"{
+                    "id": "b9b8d3f1",
+                    "state": "COMPLETE",
+                    "name": "hello",
+                    "executors": [{"image": "alpine", "command": ["echo", "hello"]}],
+                    "logs": [{"logs": null, "start_time": "2024-06-04T12:58:19Z"}],
+                    "creation_time": "2024-06-04T12:58:10Z"
+                }"#,
+            ),
+            Response::json(r#"{"tasks":[{"id":"1"},{"id":"2","state":"bogus"}]}"#),
+        ])
+        .await;
+
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let err = client.get_task("b9b8d3f1", View::Full).await.unwrap_err();
+        match err.inner() {
+            Error::Decode { path, null, .. } => {
+                assert_eq!(path, "logs[0].logs");
+                assert!(null);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+        assert!(
+            err.to_string().starts_with(
+                "GET /tasks/b9b8d3f1: failed to decode the response (200 OK): the service \
+                 returned null for `logs[0].logs`, which the TES specification does not allow"
+            ),
+            "{err}"
+        );
+
+        // NOTE: other failures are not reported as nulls.
+        let err = client.list_tasks(Some(&minimal())).await.unwrap_err();
+        match err.inner() {
+            Error::Decode { path, null, .. } => {
+                assert_eq!(path, "tasks[1].state");
+                assert!(!null);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn decode_snippets_are_truncated() {
+        assert_eq!(snippet(b"{}"), "{}");
+
+        let body = vec![b'x'; MAX_BODY_SNIPPET_LEN * 4];
+        let snippet = snippet(&body);
+        assert_eq!(snippet.len(), MAX_BODY_SNIPPET_LEN + 3);
+        assert!(snippet.ends_with("x..."));
+    }
+
+    #[tokio::test]
+    async fn rejections_include_the_body() {
+        let server = Server::scripted(vec![Response::new(
+            400,
+            r#"{"message":"executors must not be empty"}"#,
+        )])
+        .await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.inner(),
+            Error::Http {
+                status: StatusCode::BAD_REQUEST,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            r#"POST /tasks: service responded with 400 Bad Request: {"message":"executors must not be empty"}"#
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_tasks_are_not_found() {
+        let server = Server::scripted(vec![
+            Response::new(404, r#"{"message":"task not found"}"#),
+            Response::new(404, ""),
+        ])
+        .await;
+
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let err = client.get_task("123", View::Minimal).await.unwrap_err();
+        assert!(matches!(err.inner(), Error::TaskNotFound { id } if id == "123"));
+        assert_eq!(err.to_string(), "GET /tasks/123: task `123` was not found");
+
+        let err = client.cancel_task("456").await.unwrap_err();
+        assert!(matches!(err.inner(), Error::TaskNotFound { id } if id == "456"));
+        assert_eq!(err.endpoint(), Some((&Method::POST, "/tasks/456:cancel")));
+    }
+
+    #[test]
+    fn error_helpers() {
+        let http = |status| Error::Http {
+            status,
+            url: Box::new("http://localhost/tasks".parse().unwrap()),
+            body: String::new(),
+            message: None,
+        };
+
+        let err = http(StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(err.is_retryable());
+        assert!(!err.is_timeout());
+        assert!(!err.is_connect());
+
+        let err = http(StatusCode::BAD_REQUEST)
+            .with_endpoint(Method::POST, &"http://localhost/tasks".parse().unwrap());
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert!(!err.is_retryable());
+
+        let err = Error::RetriesExhausted {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            retries: 3,
+        };
+        assert_eq!(err.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(err.is_retryable());
+
+        let err = Error::DeadlineExceeded {
+            deadline: Duration::from_secs(10),
+            last: Box::new(http(StatusCode::BAD_GATEWAY)),
+        };
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+        assert!(err.is_retryable());
+        assert!(err.is_timeout());
+
+        let err = Error::Unauthorized {
+            status: StatusCode::FORBIDDEN,
+            www_authenticate: None,
+            body: String::new(),
+            message: None,
+        };
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+        assert!(!err.is_retryable());
+
+        let err = Error::TaskNotFound {
+            id: String::from("123"),
+        };
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        assert!(!err.is_retryable());
+
+        assert!(Error::CircuitOpen.is_retryable());
+        assert_eq!(Error::CircuitOpen.status(), None);
+
+        let err = Error::Auth("the token file is missing".into());
+        assert_eq!(err.status(), None);
+        assert!(!err.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn error_helpers_for_reqwest_errors() {
+        // NOTE: the listener is dropped so that nothing accepts connections.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let err = Builder::default()
+            .url_from_string(format!("http://{addr}/"))
+            .unwrap()
+            .retries(0)
+            .try_build()
+            .unwrap()
+            .service_info()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.inner(), Error::Reqwest(_)));
+        assert!(err.is_connect());
+        assert!(!err.is_timeout());
+        assert!(err.is_retryable());
+        assert_eq!(err.status(), None);
+
+        let server =
+            Server::scripted(vec![Response::json("{}").delay(Duration::from_secs(10))]).await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .with_http_client(
+                reqwest::Client::builder()
+                    .timeout(Duration::from_millis(50))
+                    .build()
+                    .unwrap(),
+            )
+            .retries(0)
+            .try_build()
+            .unwrap()
+            .service_info()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.inner(), Error::Reqwest(_)));
+        assert!(err.is_timeout());
+        assert!(!err.is_connect());
+        assert!(err.is_retryable());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejected_credentials_are_unauthorized() {
+        let server = Server::scripted(vec![
+            Response::new(401, "")
+                .header("www-authenticate", r#"Bearer realm="tes", scope="tasks""#),
+            Response::new(403, r#"{"message":"forbidden"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let err = client.get_task("123", View::Minimal).await.unwrap_err();
+        match err.inner() {
+            Error::Unauthorized {
+                status,
+                www_authenticate,
+                body,
+                ..
+            } => {
+                assert_eq!(*status, StatusCode::UNAUTHORIZED);
+                assert_eq!(
+                    www_authenticate.as_deref(),
+                    Some(r#"Bearer realm="tes", scope="tasks""#)
+                );
+                assert!(body.is_empty());
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+        assert_eq!(
+            err.to_string(),
+            r#"GET /tasks/123: service rejected the credentials with 401 Unauthorized (WWW-Authenticate: Bearer realm="tes", scope="tasks")"#
+        );
+
+        let err = client.service_info().await.unwrap_err();
+        match err.inner() {
+            Error::Unauthorized {
+                status,
+                www_authenticate,
+                body,
+                ..
+            } => {
+                assert_eq!(*status, StatusCode::FORBIDDEN);
+                assert_eq!(*www_authenticate, None);
+                assert_eq!(body, r#"{"message":"forbidden"}"#);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+        assert_eq!(
+            err.to_string(),
+            r#"GET /service-info: service rejected the credentials with 403 Forbidden: {"message":"forbidden"}"#
+        );
+
+        assert_eq!(err.server_message(), Some("forbidden"));
+
+        // NOTE: neither response is retried.
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[tokio::test]
+    async fn diagnostics() {
+        use miette::Diagnostic as _;
+
+        let server = Server::scripted(vec![Response::new(401, "")]).await;
+        let err = Builder::default()
+            .url(server.url())
+            .try_build()
+            .unwrap()
+            .service_info()
             .await
+            .unwrap_err();
+
+        // NOTE: the code and help come from the underlying error.
+        assert_eq!(err.code().unwrap().to_string(), "tes::client::unauthorized");
+        assert!(err.help().unwrap().to_string().contains("have not expired"));
+
+        let err = Error::from(ValidationError::PageSizeTooLarge {
+            given: 4096,
+            max: 2047,
+        });
+        assert_eq!(
+            err.code().unwrap().to_string(),
+            "tes::client::validation::page_size"
+        );
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "use a page size of at most 2047"
+        );
+
+        let err = Builder::default().try_build().unwrap_err();
+        assert_eq!(
+            err.code().unwrap().to_string(),
+            "tes::client::builder::missing"
+        );
+        assert!(err.help().is_some());
+        assert!(
+            Error::SerdeJSON(serde_json::from_str::<()>("").unwrap_err())
+                .help()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn server_messages() {
+        let server = Server::scripted(vec![
+            Response::new(
+                400,
+                r#"{"error":"executors must not be empty","code":3,"message":"executors must not be empty"}"#,
+            ),
+            Response::new(409, "task is already complete"),
+            Response::new(400, ""),
+        ])
+        .await;
+
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let err = client.create_task(Task::default()).await.unwrap_err();
+        assert_eq!(err.server_message(), Some("executors must not be empty"));
+
+        // NOTE: bodies that are not JSON are reported as is.
+        let err = client.create_task(Task::default()).await.unwrap_err();
+        assert_eq!(err.server_message(), Some("task is already complete"));
+
+        let err = client.create_task(Task::default()).await.unwrap_err();
+        assert_eq!(err.server_message(), None);
+        assert_eq!(Error::CircuitOpen.server_message(), None);
     }
 }
@@ -0,0 +1,297 @@
+//! Authentication for requests made by a [`Client`](super::Client).
+//!
+//! Credentials are provided by an [`AuthProvider`], which is invoked before
+//! every attempt of every request (including retries). This allows providers
+//! to rotate credentials without rebuilding the client.
+
+use std::sync::Arc;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use reqwest::Request;
+use reqwest::Response;
+use reqwest::header::AUTHORIZATION;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderValue;
+use reqwest_middleware::Next;
+
+/// A boxed error returned from an [`AuthProvider`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// An error related to creating the credentials for an [`AuthProvider`].
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The provided credentials were invalid.
+    ///
+    /// The credentials themselves are intentionally not included in this error.
+    InvalidCredentials(&'static str),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidCredentials(reason) => write!(f, "invalid credentials: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A provider of credentials for requests.
+///
+/// On `wasm32` targets, the returned future is not required to be [`Send`]
+/// (implementations should use `#[async_trait(?Send)]` there).
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+pub trait AuthProvider: Send + Sync + 'static {
+    /// Adds credentials to the headers of a request.
+    ///
+    /// This is called before every attempt of every request.
+    async fn authorize(&self, headers: &mut HeaderMap) -> Result<(), BoxError>;
+}
+
+/// A provider that sends a static `Authorization` header.
+#[derive(Clone)]
+pub struct StaticToken(HeaderValue);
+
+impl StaticToken {
+    /// Creates a new provider that sends `Authorization: Bearer <token>`.
+    ///
+    /// # Errors
+    ///
+    /// The token must be a valid header value (visible ASCII characters).
+    pub fn bearer(token: impl AsRef<str>) -> Result<Self, Error> {
+        let mut value =
+            HeaderValue::from_str(&format!("Bearer {}", token.as_ref())).map_err(|_| {
+                Error::InvalidCredentials("the bearer token is not a valid header value")
+            })?;
+        value.set_sensitive(true);
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AuthProvider for StaticToken {
+    async fn authorize(&self, headers: &mut HeaderMap) -> Result<(), BoxError> {
+        headers.insert(AUTHORIZATION, self.0.clone());
+        Ok(())
+    }
+}
+
+/// A provider that sends credentials for HTTP basic authentication.
+#[derive(Clone)]
+pub struct BasicAuth(HeaderValue);
+
+impl BasicAuth {
+    /// Creates a new provider for HTTP basic authentication.
+    ///
+    /// The credentials are encoded as described in [RFC 7617].
+    ///
+    /// # Errors
+    ///
+    /// Per [RFC 7617], the `username` cannot contain a colon (`:`).
+    ///
+    /// [RFC 7617]: https://datatracker.ietf.org/doc/html/rfc7617
+    pub fn new(
+        username: impl AsRef<str>,
+        password: Option<impl AsRef<str>>,
+    ) -> Result<Self, Error> {
+        let username = username.as_ref();
+
+        if username.contains(':') {
+            return Err(Error::InvalidCredentials(
+                "the username for basic authentication cannot contain a colon",
+            ));
+        }
+
+        let credentials = match password {
+            Some(password) => format!("{username}:{}", password.as_ref()),
+            None => format!("{username}:"),
+        };
+
+        // SAFETY: the base64 alphabet is entirely made up of visible ASCII
+        // characters, so this will always unwrap.
+        let mut value =
+            HeaderValue::from_str(&format!("Basic {}", STANDARD.encode(credentials))).unwrap();
+        value.set_sensitive(true);
+
+        Ok(Self(value))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl AuthProvider for BasicAuth {
+    async fn authorize(&self, headers: &mut HeaderMap) -> Result<(), BoxError> {
+        headers.insert(AUTHORIZATION, self.0.clone());
+        Ok(())
+    }
+}
+
+/// An error returned from an [`AuthProvider`] within the middleware.
+///
+/// This wrapper exists so the error can be identified when it is returned
+/// from the middleware stack.
+#[derive(Debug)]
+pub(crate) struct Failure(pub(crate) BoxError);
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// A middleware that invokes an [`AuthProvider`] for every request.
+pub(crate) struct Middleware(pub(crate) Arc<dyn AuthProvider>);
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl reqwest_middleware::Middleware for Middleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        self.0
+            .authorize(req.headers_mut())
+            .await
+            .map_err(|err| reqwest_middleware::Error::middleware(Failure(err)))?;
+
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::v1::client;
+    use crate::v1::client::tasks::View;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+    use crate::v1::types::Task;
+
+    /// A provider that counts the number of times it is invoked.
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl AuthProvider for Counter {
+        async fn authorize(&self, headers: &mut HeaderMap) -> Result<(), BoxError> {
+            let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            headers.insert(AUTHORIZATION, format!("Bearer {count}").parse().unwrap());
+            Ok(())
+        }
+    }
+
+    /// A provider that always fails.
+    struct Failing;
+
+    #[async_trait::async_trait]
+    impl AuthProvider for Failing {
+        async fn authorize(&self, _: &mut HeaderMap) -> Result<(), BoxError> {
+            Err("the token file is missing".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn basic_auth() {
+        let mut headers = HeaderMap::new();
+        BasicAuth::new("user", Some("pass"))
+            .unwrap()
+            .authorize(&mut headers)
+            .await
+            .unwrap();
+
+        let value = headers.get(AUTHORIZATION).unwrap();
+        assert_eq!(value, "Basic dXNlcjpwYXNz");
+        assert!(value.is_sensitive());
+
+        let mut headers = HeaderMap::new();
+        BasicAuth::new("user", None::<&str>)
+            .unwrap()
+            .authorize(&mut headers)
+            .await
+            .unwrap();
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Basic dXNlcjo=");
+    }
+
+    #[test]
+    fn basic_auth_rejects_colons_in_username() {
+        let err = BasicAuth::new("us:er", Some("pass")).err().unwrap();
+        assert!(matches!(err, Error::InvalidCredentials(_)));
+        assert!(!err.to_string().contains("pass"));
+    }
+
+    #[tokio::test]
+    async fn bearer_token() {
+        let mut headers = HeaderMap::new();
+        StaticToken::bearer("abc.def")
+            .unwrap()
+            .authorize(&mut headers)
+            .await
+            .unwrap();
+
+        let value = headers.get(AUTHORIZATION).unwrap();
+        assert_eq!(value, "Bearer abc.def");
+        assert!(value.is_sensitive());
+    }
+
+    #[test]
+    fn bearer_token_rejects_invalid_header_values() {
+        let err = StaticToken::bearer("abc\ndef").err().unwrap();
+        assert!(matches!(err, Error::InvalidCredentials(_)));
+    }
+
+    #[tokio::test]
+    async fn provider_runs_once_per_attempt() {
+        let server = Server::scripted(vec![
+            Response::new(500, ""),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let provider = Arc::new(Counter::default());
+        let client = client::Builder::default()
+            .url(server.url())
+            .auth_provider(provider.clone())
+            .retries(1)
+            .try_build()
+            .unwrap();
+
+        client.get_task("123", View::Minimal).await.unwrap();
+        assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("authorization").unwrap(), "Bearer 1");
+        assert_eq!(requests[1].header("authorization").unwrap(), "Bearer 2");
+    }
+
+    #[tokio::test]
+    async fn provider_errors() {
+        let server = Server::scripted(vec![Response::json(r#"{"id":"123"}"#)]).await;
+        let err = client::Builder::default()
+            .url(server.url())
+            .auth_provider(Arc::new(Failing))
+            .try_build()
+            .unwrap()
+            .create_task(Task::default())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.inner(), client::Error::Auth(_)));
+        assert!(err.to_string().contains("the token file is missing"));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! A blocking client for interacting with a Task Execution Service (TES)
+//! service.
+//!
+//! The blocking [`Client`] wraps an asynchronous [`Client`](super::Client)
+//! and drives it on a private, single-threaded runtime. As such, it shares the
+//! same [`Builder`](super::Builder) (including headers, authentication, and
+//! retries) with the asynchronous client.
+//!
+//! # Panics
+//!
+//! Like the blocking client within `reqwest`, this client must not be used from
+//! within an asynchronous context (e.g., from within a `tokio` task or an
+//! `async` function driven by a runtime). Every method blocks the current
+//! thread on the private runtime, which panics when the thread is already
+//! driving asynchronous tasks, as does dropping the client there. Use the
+//! asynchronous [`Client`](super::Client) from asynchronous code instead, or
+//! move the blocking client to a dedicated thread (e.g., with
+//! `tokio::task::spawn_blocking`).
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+
+use crate::v1::client::Result;
+use crate::v1::client::ValidationWarning;
+use crate::v1::client::cancel::CancelOptions;
+use crate::v1::client::cancel::CancelReport;
+use crate::v1::client::resubmit::Resubmission;
+use crate::v1::client::resubmit::ResubmitPolicy;
+use crate::v1::client::tasks::ListTasksParams;
+use crate::v1::client::tasks::View;
+use crate::v1::client::wait::WaitOptions;
+use crate::v1::types::Task;
+use crate::v1::types::responses::CreateTask;
+use crate::v1::types::responses::ListTasks;
+use crate::v1::types::responses::ServiceInfo;
+use crate::v1::types::responses::task;
+use crate::v1::types::responses::task::MinimalTask;
+use crate::v1::types::task::State;
+use crate::v1::types::task::validation;
+
+/// A blocking client for interacting with a service.
+#[derive(Debug)]
+pub struct Client {
+    /// The underlying asynchronous client.
+    inner: super::Client,
+
+    /// The runtime on which requests are driven.
+    runtime: Runtime,
+}
+
+impl Client {
+    /// Creates a new blocking client from an asynchronous client.
+    pub(crate) fn new(inner: super::Client) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        Ok(Self { inner, runtime })
+    }
+
+    /// Gets an empty builder for a [`Client`].
+    ///
+    /// Blocking clients are built with
+    /// [`Builder::try_build_blocking()`](super::Builder::try_build_blocking).
+    pub fn builder() -> super::Builder {
+        super::Builder::default()
+    }
+
+    /// Gets the service information.
+    ///
+    /// See [`Client::service_info()`](super::Client::service_info).
+    pub fn service_info(&self) -> Result<ServiceInfo> {
+        self.runtime.block_on(self.inner.service_info())
+    }
+
+    /// Validates a task before it is submitted to the service.
+    ///
+    /// See [`Client::validate_task()`](super::Client::validate_task).
+    pub fn validate_task(&self, task: &Task) -> Result<Vec<ValidationWarning>> {
+        self.runtime.block_on(self.inner.validate_task(task))
+    }
+
+    /// Validates a task before it is submitted to the service with options
+    /// for checking its structure.
+    ///
+    /// See [`Client::validate_task_with()`](super::Client::validate_task_with).
+    pub fn validate_task_with(
+        &self,
+        task: &Task,
+        options: &validation::Options,
+    ) -> Result<Vec<ValidationWarning>> {
+        self.runtime
+            .block_on(self.inner.validate_task_with(task, options))
+    }
+
+    /// Lists a single page of tasks within the service.
+    ///
+    /// See [`Client::list_tasks()`](super::Client::list_tasks).
+    pub fn list_tasks(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<task::Response>> {
+        self.runtime.block_on(self.inner.list_tasks(params))
+    }
+
+    /// Lists a single page of tasks within the service with the minimal view.
+    ///
+    /// See [`Client::list_tasks_minimal()`](super::Client::list_tasks_minimal).
+    pub fn list_tasks_minimal(
+        &self,
+        params: Option<&ListTasksParams>,
+    ) -> Result<ListTasks<MinimalTask>> {
+        self.runtime.block_on(self.inner.list_tasks_minimal(params))
+    }
+
+    /// Lists a single page of tasks within the service with the basic view.
+    ///
+    /// See [`Client::list_tasks_basic()`](super::Client::list_tasks_basic).
+    pub fn list_tasks_basic(&self, params: Option<&ListTasksParams>) -> Result<ListTasks<Task>> {
+        self.runtime.block_on(self.inner.list_tasks_basic(params))
+    }
+
+    /// Lists a single page of tasks within the service with the full view.
+    ///
+    /// See [`Client::list_tasks_full()`](super::Client::list_tasks_full).
+    pub fn list_tasks_full(&self, params: Option<&ListTasksParams>) -> Result<ListTasks<Task>> {
+        self.runtime.block_on(self.inner.list_tasks_full(params))
+    }
+
+    /// Lists all tasks within the service.
+    ///
+    /// See [`Client::list_all_tasks()`](super::Client::list_all_tasks).
+    pub fn list_all_tasks(&self, params: ListTasksParams) -> Result<Vec<task::Response>> {
+        self.runtime.block_on(self.inner.list_all_tasks(params))
+    }
+
+    /// Lists all tasks within the service, failing if the service lists more
+    /// than `max` tasks.
+    ///
+    /// See
+    /// [`Client::list_all_tasks_with_limit()`](super::Client::list_all_tasks_with_limit).
+    pub fn list_all_tasks_with_limit(
+        &self,
+        params: ListTasksParams,
+        max: usize,
+    ) -> Result<Vec<task::Response>> {
+        self.runtime
+            .block_on(self.inner.list_all_tasks_with_limit(params, max))
+    }
+
+    /// Counts the tasks within the service by their state.
+    ///
+    /// See [`Client::count_tasks_by_state()`](super::Client::count_tasks_by_state).
+    pub fn count_tasks_by_state(&self, params: ListTasksParams) -> Result<BTreeMap<State, usize>> {
+        self.runtime
+            .block_on(self.inner.count_tasks_by_state(params))
+    }
+
+    /// Creates a task within the service.
+    ///
+    /// See [`Client::create_task()`](super::Client::create_task).
+    pub fn create_task(&self, task: Task) -> Result<CreateTask> {
+        self.runtime.block_on(self.inner.create_task(task))
+    }
+
+    /// Gets a specific task within the service.
+    ///
+    /// See [`Client::get_task()`](super::Client::get_task).
+    pub fn get_task(&self, id: impl AsRef<str>, view: View) -> Result<task::Response> {
+        self.runtime.block_on(self.inner.get_task(id, view))
+    }
+
+    /// Cancels a task within the service.
+    ///
+    /// See [`Client::cancel_task()`](super::Client::cancel_task).
+    pub fn cancel_task(&self, id: impl AsRef<str>) -> Result<()> {
+        self.runtime.block_on(self.inner.cancel_task(id))
+    }
+
+    /// Cancels the tasks within the service that match a filter.
+    ///
+    /// See [`Client::cancel_matching()`](super::Client::cancel_matching).
+    pub fn cancel_matching(
+        &self,
+        params: ListTasksParams,
+        options: &CancelOptions,
+    ) -> Result<CancelReport> {
+        self.runtime
+            .block_on(self.inner.cancel_matching(params, options))
+    }
+
+    /// Waits for a task within the service to finish.
+    ///
+    /// See [`Client::wait_for_task()`](super::Client::wait_for_task).
+    pub fn wait_for_task(&self, id: impl AsRef<str>, options: &WaitOptions) -> Result<Task> {
+        self.runtime.block_on(self.inner.wait_for_task(id, options))
+    }
+
+    /// Waits for multiple tasks within the service to finish.
+    ///
+    /// See [`Client::wait_for_tasks()`](super::Client::wait_for_tasks).
+    pub fn wait_for_tasks<I>(&self, ids: I, options: &WaitOptions) -> Vec<(String, Result<Task>)>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.runtime
+            .block_on(self.inner.wait_for_tasks(ids, options))
+    }
+
+    /// Creates a task within the service and waits for it to finish.
+    ///
+    /// See [`Client::create_task_and_wait()`](super::Client::create_task_and_wait).
+    pub fn create_task_and_wait(
+        &self,
+        task: Task,
+        options: &WaitOptions,
+    ) -> Result<(CreateTask, Task)> {
+        self.runtime
+            .block_on(self.inner.create_task_and_wait(task, options))
+    }
+
+    /// Creates a task within the service and waits for it to finish before a
+    /// deadline, canceling it otherwise.
+    ///
+    /// See [`Client::run_with_deadline()`](super::Client::run_with_deadline).
+    pub fn run_with_deadline(
+        &self,
+        task: Task,
+        deadline: Duration,
+        options: &WaitOptions,
+    ) -> Result<(CreateTask, Task)> {
+        self.runtime
+            .block_on(self.inner.run_with_deadline(task, deadline, options))
+    }
+
+    /// Creates a task within the service, waits for it to finish, and
+    /// resubmits it while it fails in a state that the [`ResubmitPolicy`]
+    /// resubmits.
+    ///
+    /// See [`Client::run_with_resubmission()`](super::Client::run_with_resubmission).
+    pub fn run_with_resubmission(
+        &self,
+        task: Task,
+        policy: &ResubmitPolicy,
+        options: &WaitOptions,
+    ) -> Result<Resubmission> {
+        self.runtime
+            .block_on(self.inner.run_with_resubmission(task, policy, options))
+    }
+
+    /// Reruns a task within the service.
+    ///
+    /// See [`Client::rerun_task()`](super::Client::rerun_task).
+    pub fn rerun_task(&self, id: impl AsRef<str>) -> Result<CreateTask> {
+        self.runtime.block_on(self.inner.rerun_task(id))
+    }
+
+    /// Reruns a task within the service after modifying it.
+    ///
+    /// See [`Client::rerun_task_with()`](super::Client::rerun_task_with).
+    pub fn rerun_task_with<F>(&self, id: impl AsRef<str>, f: F) -> Result<CreateTask>
+    where
+        F: FnOnce(&mut Task),
+    {
+        self.runtime.block_on(self.inner.rerun_task_with(id, f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+
+    /// Starts a scripted server on a separate runtime.
+    ///
+    /// The runtime must be kept alive for the duration of the test.
+    fn server(responses: Vec<Response>) -> (Runtime, Server) {
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(Server::scripted(responses));
+        (runtime, server)
+    }
+
+    #[test]
+    fn creates_and_lists_tasks() {
+        let (_runtime, server) = server(vec![
+            Response::json(r#"{"id":"123"}"#),
+            Response::json(r#"{"tasks":[{"id":"123","state":"QUEUED"}]}"#),
+        ]);
+
+        let client = Builder::default()
+            .url(server.url())
+            .try_build_blocking()
+            .unwrap();
+
+        assert_eq!(client.create_task(Task::default()).unwrap().id, "123");
+        let tasks = client
+            .list_all_tasks(ListTasksParams::builder().view(View::Minimal).build())
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/tasks");
+        assert_eq!(requests[1].method, "GET");
+        assert_eq!(requests[1].path, "/tasks?view=MINIMAL");
+    }
+
+    #[test]
+    fn panics_within_asynchronous_contexts() {
+        let (runtime, server) = server(vec![Response::json("{}")]);
+        let client = Builder::default()
+            .url(server.url())
+            .try_build_blocking()
+            .unwrap();
+
+        let panic = runtime.block_on(async {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| client.service_info()))
+                .unwrap_err()
+        });
+        let message = panic
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| panic.downcast_ref::<&str>().copied())
+            .unwrap();
+        assert!(
+            message.contains("Cannot start a runtime from within a runtime"),
+            "{message}"
+        );
+
+        // NOTE: the request is never sent, and the client is dropped outside of
+        // the runtime.
+        assert!(server.requests().is_empty());
+        drop(client);
+    }
+
+    #[test]
+    fn retries() {
+        let (_runtime, server) = server(vec![
+            Response::new(503, ""),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ]);
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(1)
+            .try_build_blocking()
+            .unwrap();
+
+        let task = client.get_task("123", View::Minimal).unwrap();
+        assert_eq!(task.into_minimal().unwrap().id, "123");
+        assert_eq!(server.requests().len(), 2);
+    }
+}
@@ -0,0 +1,163 @@
+//! A circuit breaker for requests made by a [`Client`](super::Client).
+//!
+//! After a number of consecutive failures (failing to reach the service or
+//! receiving a `5xx` response), the circuit opens and requests fail
+//! immediately. Once the cool-down has elapsed, a single probe request is let
+//! through: if it succeeds, the circuit closes again; otherwise, it stays open
+//! for another cool-down.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::v1::client::retry::Instant;
+
+/// The state of a circuit breaker.
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// Requests are allowed.
+    Closed {
+        /// The number of consecutive failures.
+        failures: u32,
+    },
+
+    /// Requests fail immediately.
+    Open {
+        /// When a probe request is allowed.
+        until: Instant,
+    },
+
+    /// A probe request is in flight.
+    HalfOpen {
+        /// When another probe request is allowed (should the current probe
+        /// never complete).
+        until: Instant,
+    },
+}
+
+/// A circuit breaker shared between clones of a client.
+#[derive(Clone, Debug)]
+pub(crate) struct CircuitBreaker {
+    /// The number of consecutive failures that opens the circuit.
+    threshold: u32,
+
+    /// How long the circuit stays open before a probe request is allowed.
+    cooldown: Duration,
+
+    /// The state of the circuit.
+    state: Arc<Mutex<State>>,
+}
+
+impl CircuitBreaker {
+    /// Creates a new, closed circuit breaker.
+    pub(crate) fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    /// Returns whether a request may be sent.
+    pub(crate) fn allow(&self) -> bool {
+        // SAFETY: the lock is never held across a panic.
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } | State::HalfOpen { until } if now >= until => {
+                *state = State::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records the result of a request.
+    pub(crate) fn record(&self, failed: bool) {
+        // SAFETY: the lock is never held across a panic.
+        let mut state = self.state.lock().unwrap();
+
+        *state = match (*state, failed) {
+            (_, false) => State::Closed { failures: 0 },
+            (State::Closed { failures }, true) if failures + 1 < self.threshold => State::Closed {
+                failures: failures + 1,
+            },
+            (State::Open { until }, true) => State::Open { until },
+            (_, true) => State::Open {
+                until: Instant::now() + self.cooldown,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::Error;
+    use crate::v1::client::tasks::View;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_probes_reopen_the_circuit() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+
+        assert!(breaker.allow());
+        breaker.record(true);
+        assert!(breaker.allow());
+        breaker.record(true);
+        assert!(!breaker.allow());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(breaker.allow());
+
+        // NOTE: only a single probe is allowed at a time.
+        assert!(!breaker.allow());
+        breaker.record(true);
+        assert!(!breaker.allow());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(breaker.allow());
+        breaker.record(false);
+        assert!(breaker.allow());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn opens_and_recovers() {
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::new(500, ""),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(5)
+            .circuit_breaker(2, Duration::from_secs(30))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        // NOTE: the circuit opens after the first retry, so the remaining
+        // retries fail immediately.
+        let err = client.get_task("123", View::Minimal).await.unwrap_err();
+        assert!(matches!(err.inner(), Error::CircuitOpen));
+        assert_eq!(server.requests().len(), 2);
+
+        let clone = client.clone();
+        let err = clone.get_task("123", View::Minimal).await.unwrap_err();
+        assert!(matches!(err.inner(), Error::CircuitOpen));
+        assert_eq!(server.requests().len(), 2);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        clone.get_task("123", View::Minimal).await.unwrap();
+        client.get_task("123", View::Minimal).await.unwrap();
+        assert_eq!(server.requests().len(), 4);
+    }
+}
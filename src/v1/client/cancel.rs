@@ -0,0 +1,79 @@
+//! Canceling multiple tasks submitted through a [`Client`](super::Client).
+//!
+//! See [`Client::cancel_matching()`](super::Client::cancel_matching).
+
+use crate::v1::client::Error;
+
+/// The maximum number of cancel requests in flight by default.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Options for canceling the tasks that match a filter.
+#[derive(Clone, Debug)]
+pub struct CancelOptions {
+    /// The maximum number of cancel requests in flight.
+    pub(crate) concurrency: usize,
+
+    /// Whether to only report the tasks that would be canceled.
+    pub(crate) dry_run: bool,
+}
+
+impl CancelOptions {
+    /// Sets the maximum number of cancel requests in flight.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set concurrency. By default, the
+    /// concurrency is [`DEFAULT_CONCURRENCY`]. A concurrency of zero is
+    /// treated as a concurrency of one.
+    pub fn concurrency(mut self, value: usize) -> Self {
+        self.concurrency = value.max(1);
+        self
+    }
+
+    /// Sets whether to only report the tasks that would be canceled (without
+    /// canceling them).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, the tasks are
+    /// canceled.
+    pub fn dry_run(mut self, value: bool) -> Self {
+        self.dry_run = value;
+        self
+    }
+}
+
+impl Default for CancelOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            dry_run: false,
+        }
+    }
+}
+
+/// A report of canceling the tasks that match a filter.
+#[derive(Debug, Default)]
+pub struct CancelReport {
+    /// Whether this was a dry run (so no tasks were actually canceled).
+    pub dry_run: bool,
+
+    /// The IDs of the canceled tasks (or of the tasks that would have been
+    /// canceled for a dry run), in the order they were listed.
+    pub canceled: Vec<String>,
+
+    /// The number of matching tasks that were skipped because they already
+    /// finished.
+    pub skipped: usize,
+
+    /// The IDs of the tasks that failed to be canceled (along with why), in
+    /// the order they were listed.
+    pub failed: Vec<(String, Error)>,
+}
+
+impl CancelReport {
+    /// Returns whether every task that was not skipped was canceled.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
@@ -0,0 +1,639 @@
+//! Downloading the outputs of tasks submitted through a
+//! [`Client`](super::Client).
+//!
+//! See [`Client::download_outputs()`](super::Client::download_outputs).
+
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware as ReqwestClient;
+use tokio::io::AsyncWriteExt as _;
+use url::Url;
+
+use crate::v1::types::task::OutputFileLog;
+
+/// The maximum number of outputs downloaded at once by default.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// An error downloading an output.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The URL of the output could not be parsed.
+    InvalidUrl(String),
+
+    /// The URL of the output has a scheme that cannot be downloaded (e.g.,
+    /// `s3` or `gs`).
+    UnsupportedScheme(String),
+
+    /// The path of the output cannot be placed within the destination
+    /// directory (e.g., because it is empty or contains `..`).
+    InvalidPath(String),
+
+    /// The destination of the output already exists (and overwriting was not
+    /// enabled).
+    Exists(PathBuf),
+
+    /// The output is a local file outside of the
+    /// [local root](DownloadOptions::local_root()) (or no local root was set).
+    LocalFileNotAllowed(PathBuf),
+
+    /// The server responded with an unsuccessful status code.
+    Status(StatusCode),
+
+    /// The size of the downloaded output differs from its reported size.
+    SizeMismatch {
+        /// The size reported within the task log.
+        expected: u64,
+
+        /// The size of the downloaded output.
+        actual: u64,
+    },
+
+    /// The request for the output failed.
+    Request(Box<super::Error>),
+
+    /// An I/O error occurred.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidUrl(url) => write!(f, "invalid output URL `{url}`"),
+            Error::UnsupportedScheme(scheme) => {
+                write!(f, "outputs with the `{scheme}` scheme cannot be downloaded")
+            }
+            Error::InvalidPath(path) => write!(f, "invalid output path `{path}`"),
+            Error::Exists(path) => write!(f, "`{path}` already exists", path = path.display()),
+            Error::LocalFileNotAllowed(path) => write!(
+                f,
+                "copying the local file `{path}` is not allowed",
+                path = path.display()
+            ),
+            Error::Status(status) => write!(f, "the server responded with {status}"),
+            Error::SizeMismatch { expected, actual } => write!(
+                f,
+                "expected the output to be {expected} bytes, but downloaded {actual} bytes"
+            ),
+            Error::Request(err) => write!(f, "{err}"),
+            Error::Io(err) => write!(f, "i/o error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Request(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let code = match self {
+            Error::InvalidUrl(_) => "tes::client::download::invalid_url",
+            Error::UnsupportedScheme(_) => "tes::client::download::unsupported_scheme",
+            Error::InvalidPath(_) => "tes::client::download::invalid_path",
+            Error::Exists(_) => "tes::client::download::exists",
+            Error::LocalFileNotAllowed(_) => "tes::client::download::local_file_not_allowed",
+            Error::Status(_) => "tes::client::download::status",
+            Error::SizeMismatch { .. } => "tes::client::download::size_mismatch",
+            Error::Request(_) => "tes::client::download::request",
+            Error::Io(_) => "tes::client::download::io",
+        };
+
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        match self {
+            Error::UnsupportedScheme(_) => Some(Box::new(
+                "download the output with a client for its storage provider",
+            )),
+            Error::Exists(_) => Some(Box::new("enable overwriting or remove the file")),
+            Error::LocalFileNotAllowed(_) => Some(Box::new(
+                "set a local root containing the file if the service is trusted",
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Options for downloading the outputs of a task.
+#[derive(Clone, Debug)]
+pub struct DownloadOptions {
+    /// The maximum number of outputs downloaded at once.
+    pub(crate) concurrency: usize,
+
+    /// Whether to overwrite existing files.
+    pub(crate) overwrite: bool,
+
+    /// The directory from within which local files may be copied (if any).
+    pub(crate) local_root: Option<PathBuf>,
+}
+
+impl DownloadOptions {
+    /// Sets the maximum number of outputs downloaded at once.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set concurrency. By default, the
+    /// concurrency is [`DEFAULT_CONCURRENCY`]. A concurrency of zero is
+    /// treated as a concurrency of one.
+    pub fn concurrency(mut self, value: usize) -> Self {
+        self.concurrency = value.max(1);
+        self
+    }
+
+    /// Sets whether to overwrite files that already exist within the
+    /// destination directory.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, an output
+    /// whose destination already exists fails with [`Error::Exists`].
+    pub fn overwrite(mut self, value: bool) -> Self {
+        self.overwrite = value;
+        self
+    }
+
+    /// Sets the directory from within which outputs with the `file` scheme
+    /// may be copied.
+    ///
+    /// The URLs of outputs are reported by the service, so copying local files
+    /// is disabled by default: otherwise, a service could have any file
+    /// readable by the client (e.g., `file:///etc/shadow`) copied into the
+    /// destination directory. Symbolic links are resolved before checking that
+    /// a file is within the directory.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set directory. By default,
+    /// outputs with the `file` scheme fail with [`Error::LocalFileNotAllowed`].
+    pub fn local_root(mut self, value: impl Into<PathBuf>) -> Self {
+        self.local_root = Some(value.into());
+        self
+    }
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            overwrite: false,
+            local_root: None,
+        }
+    }
+}
+
+/// A downloaded output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownloadedFile {
+    /// The URL the output was downloaded from.
+    pub url: String,
+
+    /// The local path the output was downloaded to.
+    pub path: PathBuf,
+
+    /// The size of the output in bytes.
+    pub size: u64,
+}
+
+/// Gets the destination of an output path within a directory.
+///
+/// Root and prefix components are dropped so that absolute paths within the
+/// container are placed relative to the directory.
+fn destination(dir: &Path, path: &str) -> Result<PathBuf, Error> {
+    let mut destination = dir.to_path_buf();
+    let mut empty = true;
+
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => {
+                destination.push(part);
+                empty = false;
+            }
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+            Component::ParentDir => return Err(Error::InvalidPath(path.to_string())),
+        }
+    }
+
+    if empty {
+        return Err(Error::InvalidPath(path.to_string()));
+    }
+
+    Ok(destination)
+}
+
+/// Downloads an output into a directory.
+///
+/// `client` is used for outputs with the `http` and `https` schemes.
+pub(crate) async fn download(
+    client: &ReqwestClient,
+    output: &OutputFileLog,
+    dir: &Path,
+    options: &DownloadOptions,
+) -> Result<DownloadedFile, Error> {
+    let url = Url::parse(&output.url).map_err(|_| Error::InvalidUrl(output.url.clone()))?;
+    if !matches!(url.scheme(), "http" | "https" | "file") {
+        return Err(Error::UnsupportedScheme(url.scheme().to_string()));
+    }
+
+    let source = match url.scheme() {
+        "file" => Some(local_source(&url, options).await?),
+        _ => None,
+    };
+
+    let path = destination(dir, &output.path)?;
+    if !options.overwrite && tokio::fs::try_exists(&path).await.map_err(Error::Io)? {
+        return Err(Error::Exists(path));
+    }
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(Error::Io)?;
+    }
+
+    // NOTE: the output is written to a partial file that is only moved into
+    // place once it has been verified, so a failed download never leaves a
+    // truncated file at (or replaces an existing file with) the destination.
+    let part = partial(&path);
+    let result = match write(client, url, source.as_deref(), output, &part).await {
+        Ok(size) => tokio::fs::rename(&part, &path)
+            .await
+            .map(|_| size)
+            .map_err(Error::Io),
+        Err(err) => Err(err),
+    };
+
+    let size = match result {
+        Ok(size) => size,
+        Err(err) => {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(err);
+        }
+    };
+
+    Ok(DownloadedFile {
+        url: output.url.clone(),
+        path,
+        size,
+    })
+}
+
+/// Gets the path of the partial file an output is written to before it is
+/// moved to `path`.
+fn partial(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Gets the path of a local output, ensuring that it is within the
+/// [local root](DownloadOptions::local_root()).
+async fn local_source(url: &Url, options: &DownloadOptions) -> Result<PathBuf, Error> {
+    let path = url
+        .to_file_path()
+        .map_err(|_| Error::InvalidUrl(url.to_string()))?;
+
+    let Some(root) = &options.local_root else {
+        return Err(Error::LocalFileNotAllowed(path));
+    };
+
+    // NOTE: both paths are canonicalized so that neither `..` components nor
+    // symbolic links can escape the root.
+    let root = tokio::fs::canonicalize(root).await.map_err(Error::Io)?;
+    let source = match tokio::fs::canonicalize(&path).await {
+        Ok(source) => source,
+        Err(_) => return Err(Error::LocalFileNotAllowed(path)),
+    };
+
+    if !source.starts_with(&root) {
+        return Err(Error::LocalFileNotAllowed(path));
+    }
+
+    Ok(source)
+}
+
+/// Writes an output into a file and verifies its size, returning the number of
+/// bytes written.
+///
+/// Local outputs are copied from `source` rather than fetched.
+async fn write(
+    client: &ReqwestClient,
+    url: Url,
+    source: Option<&Path>,
+    output: &OutputFileLog,
+    path: &Path,
+) -> Result<u64, Error> {
+    let size = match source {
+        Some(source) => tokio::fs::copy(source, path).await.map_err(Error::Io)?,
+        None => fetch(client, url, path).await?,
+    };
+
+    // NOTE: a missing or unparsable size is not verified.
+    if let Ok(expected) = output.size() {
+        if expected != size {
+            return Err(Error::SizeMismatch {
+                expected,
+                actual: size,
+            });
+        }
+    }
+
+    Ok(size)
+}
+
+/// Fetches a URL into a file, returning the number of bytes written.
+async fn fetch(client: &ReqwestClient, url: Url, path: &Path) -> Result<u64, Error> {
+    let request = |err: super::Error| Error::Request(Box::new(err));
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| request(err.into()))?;
+
+    if !response.status().is_success() {
+        return Err(Error::Status(response.status()));
+    }
+
+    let mut file = tokio::fs::File::create(path).await.map_err(Error::Io)?;
+    let mut size = 0;
+
+    loop {
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|err| request(super::Error::Reqwest(err)))?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        file.write_all(&chunk).await.map_err(Error::Io)?;
+        size += chunk.len() as u64;
+    }
+
+    file.flush().await.map_err(Error::Io)?;
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+    use crate::v1::types::Task;
+    use crate::v1::types::task::TaskLog;
+
+    /// A temporary directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        /// Creates a new temporary directory.
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "tes-download-{name}-{pid}",
+                pid = std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Creates an output file log.
+    fn output(url: impl Into<String>, path: &str, size: &str) -> OutputFileLog {
+        OutputFileLog {
+            url: url.into(),
+            path: path.to_string(),
+            size_bytes: size.to_string(),
+        }
+    }
+
+    #[test]
+    fn destinations() {
+        let dir = Path::new("out");
+
+        assert_eq!(
+            destination(dir, "/data/a.txt").unwrap(),
+            dir.join("data").join("a.txt")
+        );
+        assert_eq!(
+            destination(dir, "./b/c.txt").unwrap(),
+            dir.join("b").join("c.txt")
+        );
+        assert!(matches!(
+            destination(dir, "/data/../etc/passwd"),
+            Err(Error::InvalidPath(_))
+        ));
+        assert!(matches!(destination(dir, "/"), Err(Error::InvalidPath(_))));
+    }
+
+    #[tokio::test]
+    async fn downloading_outputs() {
+        let server = Server::start(|request| match request.path.as_str() {
+            "/outputs/a.txt" => Response::new(200, "hello"),
+            "/outputs/b.txt" => Response::new(200, "truncated"),
+            _ => Response::new(404, ""),
+        })
+        .await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let sources = TempDir::new("sources");
+        let local = sources.0.join("c.txt");
+        std::fs::write(&local, "local").unwrap();
+
+        let url = |path: &str| server.url().join(path).unwrap().to_string();
+        let task = Task {
+            logs: Some(vec![TaskLog {
+                outputs: Some(vec![
+                    output(url("outputs/a.txt"), "/data/a.txt", "5"),
+                    output(url("outputs/b.txt"), "/data/b.txt", "100"),
+                    output(
+                        Url::from_file_path(&local).unwrap(),
+                        "/data/nested/c.txt",
+                        "",
+                    ),
+                    output("s3://bucket/d.txt", "/data/d.txt", "1"),
+                    output(url("outputs/e.txt"), "/data/e.txt", "1"),
+                    output(url("outputs/a.txt"), "/data/../a.txt", "5"),
+                ]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let dest = TempDir::new("dest");
+        let options = DownloadOptions::default()
+            .concurrency(2)
+            .local_root(&sources.0);
+        let results = client.download_outputs(&task, &dest.0, &options).await;
+        assert_eq!(results.len(), 6);
+
+        let file = results[0].1.as_ref().unwrap();
+        assert_eq!(file.path, dest.0.join("data").join("a.txt"));
+        assert_eq!(file.size, 5);
+        assert_eq!(std::fs::read_to_string(&file.path).unwrap(), "hello");
+
+        assert!(matches!(
+            results[1].1,
+            Err(Error::SizeMismatch {
+                expected: 100,
+                actual: 9
+            })
+        ));
+        assert!(!dest.0.join("data").join("b.txt").exists());
+        assert!(!dest.0.join("data").join("b.txt.part").exists());
+
+        let file = results[2].1.as_ref().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dest.0.join("data/nested/c.txt")).unwrap(),
+            "local"
+        );
+        assert_eq!(file.size, 5);
+
+        assert!(matches!(&results[3].1, Err(Error::UnsupportedScheme(s)) if s == "s3"));
+        assert!(matches!(
+            results[4].1,
+            Err(Error::Status(StatusCode::NOT_FOUND))
+        ));
+        assert!(matches!(results[5].1, Err(Error::InvalidPath(_))));
+
+        // NOTE: existing files are only replaced when overwriting is enabled.
+        let task = Task {
+            logs: Some(vec![TaskLog {
+                outputs: Some(vec![output(url("outputs/a.txt"), "/data/a.txt", "5")]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let results = client
+            .download_outputs(&task, &dest.0, &DownloadOptions::default())
+            .await;
+        assert!(matches!(results[0].1, Err(Error::Exists(_))));
+
+        let options = DownloadOptions::default().overwrite(true);
+        let results = client.download_outputs(&task, &dest.0, &options).await;
+        assert_eq!(results[0].1.as_ref().unwrap().size, 5);
+
+        // NOTE: a failed download does not replace an existing file.
+        let task = Task {
+            logs: Some(vec![TaskLog {
+                outputs: Some(vec![output(url("outputs/b.txt"), "/data/a.txt", "100")]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let results = client.download_outputs(&task, &dest.0, &options).await;
+        assert!(matches!(results[0].1, Err(Error::SizeMismatch { .. })));
+        assert_eq!(
+            std::fs::read_to_string(dest.0.join("data").join("a.txt")).unwrap(),
+            "hello"
+        );
+        assert!(!dest.0.join("data").join("a.txt.part").exists());
+    }
+
+    #[tokio::test]
+    async fn local_files_are_opt_in() {
+        let client = Builder::default()
+            .url("http://localhost/".parse::<Url>().unwrap())
+            .try_build()
+            .unwrap();
+
+        let sources = TempDir::new("outside");
+        let secret = sources.0.join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+        let root = sources.0.join("root");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("allowed.txt"), "allowed").unwrap();
+
+        let file = |path: &Path| Url::from_file_path(path).unwrap().to_string();
+        let task = Task {
+            logs: Some(vec![TaskLog {
+                outputs: Some(vec![
+                    output(file(&secret), "/data/secret.txt", ""),
+                    output(file(&root.join("allowed.txt")), "/data/allowed.txt", ""),
+                    // NOTE: `..` cannot be used to escape the local root.
+                    output(
+                        file(&root.join("..").join("secret.txt")),
+                        "/data/escaped.txt",
+                        "",
+                    ),
+                ]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        // NOTE: local files are not copied by default.
+        let dest = TempDir::new("local-dest");
+        let results = client
+            .download_outputs(&task, &dest.0, &DownloadOptions::default())
+            .await;
+        assert!(
+            results
+                .iter()
+                .all(|(_, result)| matches!(result, Err(Error::LocalFileNotAllowed(_))))
+        );
+        assert!(!dest.0.join("data").exists());
+
+        let options = DownloadOptions::default().local_root(&root);
+        let results = client.download_outputs(&task, &dest.0, &options).await;
+        assert!(matches!(&results[0].1, Err(Error::LocalFileNotAllowed(path)) if *path == secret));
+        assert_eq!(
+            std::fs::read_to_string(&results[1].1.as_ref().unwrap().path).unwrap(),
+            "allowed"
+        );
+        assert!(matches!(results[2].1, Err(Error::LocalFileNotAllowed(_))));
+        assert!(!dest.0.join("data").join("secret.txt").exists());
+
+        // NOTE: symbolic links cannot be used to escape the local root.
+        #[cfg(unix)]
+        {
+            let link = root.join("link.txt");
+            std::os::unix::fs::symlink(&secret, &link).unwrap();
+
+            let task = Task {
+                logs: Some(vec![TaskLog {
+                    outputs: Some(vec![output(file(&link), "/data/link.txt", "")]),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            };
+            let results = client.download_outputs(&task, &dest.0, &options).await;
+            assert!(matches!(results[0].1, Err(Error::LocalFileNotAllowed(_))));
+        }
+        assert!(!dest.0.join("data").join("escaped.txt").exists());
+        assert_eq!(
+            results[0].1.as_ref().unwrap_err().to_string(),
+            format!(
+                "copying the local file `{}` is not allowed",
+                secret.display()
+            )
+        );
+    }
+
+    #[test]
+    fn partial_files() {
+        assert_eq!(
+            partial(Path::new("out/data/a.txt")),
+            Path::new("out/data/a.txt.part")
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Handles to tasks submitted through a [`Client`].
+//!
+//! A [`TaskHandle`] is returned by [`Client::submit()`] and pairs the ID of the
+//! created task with the client that created it, so the task can be polled,
+//! waited on, and canceled without threading both around. Clones of a client
+//! share the same connection pool, so a handle is cheap to create.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use tracing::warn;
+
+use crate::v1::client::Client;
+use crate::v1::client::Result;
+use crate::v1::client::tasks::View;
+use crate::v1::client::wait::WaitOptions;
+use crate::v1::types::Task;
+use crate::v1::types::task::State;
+use crate::v1::types::task::TaskLog;
+
+/// A handle to a task within the service.
+///
+/// By default, dropping a handle leaves the task alone. With
+/// [`Self::cancel_on_drop()`], the handle becomes a guard that cancels the
+/// task (on a best-effort basis) if it is dropped before the task was observed
+/// to have finished:
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use tes::v1::client::Client;
+/// use tes::v1::client::wait::WaitOptions;
+/// use tes::v1::types::Task;
+///
+/// # async fn run(client: &Client, task: Task) -> Result<(), Box<dyn std::error::Error>> {
+/// let handle = client.submit(task).await?.cancel_on_drop();
+///
+/// // NOTE: if waiting fails (e.g., because the timeout elapses), the handle
+/// // is dropped by `?` and the task is canceled.
+/// let options = WaitOptions::default().timeout(Duration::from_secs(3600));
+/// let task = handle.wait(&options).await?;
+///
+/// println!("task `{}` finished as {:?}", handle.id(), task.state);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TaskHandle {
+    /// The client that created the task.
+    client: Client,
+
+    /// The ID of the task.
+    id: String,
+
+    /// Whether to cancel the task when the handle is dropped.
+    cancel_on_drop: bool,
+
+    /// Whether the task was observed to have finished (or was canceled).
+    finished: AtomicBool,
+}
+
+impl TaskHandle {
+    /// Creates a new handle to the task with the provided ID.
+    pub(crate) fn new(client: Client, id: String) -> Self {
+        Self {
+            client,
+            id,
+            cancel_on_drop: false,
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// Gets the ID of the task.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Cancels the task when the handle is dropped before the task was
+    /// observed to have finished.
+    ///
+    /// The task is canceled from a task spawned onto the current Tokio
+    /// runtime, so it may not be canceled if the runtime is shutting down (or
+    /// if there is no runtime, which is the case on WebAssembly). A failure to
+    /// cancel the task is only logged.
+    pub fn cancel_on_drop(mut self) -> Self {
+        self.cancel_on_drop = true;
+        self
+    }
+
+    /// Consumes the handle without canceling the task (even with
+    /// [`Self::cancel_on_drop()`]), returning the ID of the task.
+    pub fn detach(mut self) -> String {
+        self.cancel_on_drop = false;
+        std::mem::take(&mut self.id)
+    }
+
+    /// Gets the state of the task.
+    ///
+    /// See [`Client::get_task()`]. A task that is missing its state is
+    /// considered to be in the [`State::Unknown`] state.
+    pub async fn status(&self) -> Result<State> {
+        let state = self
+            .client
+            .get_task(&self.id, View::Minimal)
+            .await?
+            .into_minimal()
+            .and_then(|task| task.state)
+            .unwrap_or_default();
+
+        self.observe(state);
+        Ok(state)
+    }
+
+    /// Waits for the task to finish.
+    ///
+    /// See [`Client::wait_for_task()`].
+    pub async fn wait(&self, options: &WaitOptions) -> Result<Task> {
+        let task = self.client.wait_for_task(&self.id, options).await?;
+        self.finished.store(true, Ordering::Relaxed);
+        Ok(task)
+    }
+
+    /// Cancels the task.
+    ///
+    /// See [`Client::cancel_task()`].
+    pub async fn cancel(&self) -> Result<()> {
+        self.client.cancel_task(&self.id).await?;
+        self.finished.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Gets the logs of the task (one for each attempt).
+    ///
+    /// This retrieves the task with the [`View::Full`] view (see
+    /// [`Client::get_task()`]).
+    pub async fn logs(&self) -> Result<Vec<TaskLog>> {
+        let task = self
+            .client
+            .get_task(&self.id, View::Full)
+            .await?
+            .into_task()
+            .expect("full views should contain a task");
+
+        self.observe(task.state.unwrap_or_default());
+        Ok(task.logs.unwrap_or_default())
+    }
+
+    /// Records whether an observed state means the task has finished.
+    fn observe(&self, state: State) {
+        if !state.is_executing() {
+            self.finished.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        if !self.cancel_on_drop || *self.finished.get_mut() {
+            return;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let client = self.client.clone();
+                let id = std::mem::take(&mut self.id);
+
+                runtime.spawn(async move {
+                    if let Err(err) = client.cancel_task(&id).await {
+                        warn!("failed to cancel task `{id}` after its handle was dropped: {err}");
+                    }
+                });
+            }
+            Err(_) => warn!(
+                "task `{id}` was not canceled after its handle was dropped: there is no Tokio \
+                 runtime",
+                id = self.id
+            ),
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        warn!(
+            "task `{id}` was not canceled after its handle was dropped: canceling on drop is not \
+             supported on WebAssembly",
+            id = self.id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+
+    /// Starts a server that creates a task that stays in `state`.
+    async fn server(state: &'static str) -> Server {
+        Server::start(move |request| match request.method.as_str() {
+            "POST" if request.path == "/tasks" => Response::json(r#"{"id":"123"}"#),
+            "POST" => Response::json("{}"),
+            _ => Response::json(format!(
+                r#"{{"id":"123","state":"{state}","executors":[],"logs":[{{"logs":[]}}]}}"#
+            )),
+        })
+        .await
+    }
+
+    /// Gets whether the task was canceled within the service.
+    async fn canceled(server: &Server) -> bool {
+        // NOTE: the task is canceled from a spawned task.
+        for _ in 0..20 {
+            if server
+                .requests()
+                .iter()
+                .any(|r| r.path == "/tasks/123:cancel")
+            {
+                return true;
+            }
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        false
+    }
+
+    #[tokio::test]
+    async fn handles() {
+        let server = server("RUNNING").await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let handle = client.submit(Task::default()).await.unwrap();
+        assert_eq!(handle.id(), "123");
+        assert_eq!(handle.status().await.unwrap(), State::Running);
+        assert_eq!(handle.logs().await.unwrap().len(), 1);
+
+        handle.cancel().await.unwrap();
+        assert!(canceled(&server).await);
+    }
+
+    #[tokio::test]
+    async fn handles_cancel_on_drop() {
+        let server = server("QUEUED").await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let handle = client.submit(Task::default()).await.unwrap();
+        drop(handle);
+        assert!(!canceled(&server).await);
+
+        let handle = client.submit(Task::default()).await.unwrap();
+        assert_eq!(handle.cancel_on_drop().detach(), "123");
+        assert!(!canceled(&server).await);
+
+        let handle = client
+            .submit(Task::default())
+            .await
+            .unwrap()
+            .cancel_on_drop();
+        assert_eq!(handle.status().await.unwrap(), State::Queued);
+        drop(handle);
+        assert!(canceled(&server).await);
+    }
+
+    #[tokio::test]
+    async fn finished_handles_are_not_canceled_on_drop() {
+        let server = server("COMPLETE").await;
+        let client = Builder::default().url(server.url()).try_build().unwrap();
+
+        let handle = client
+            .submit(Task::default())
+            .await
+            .unwrap()
+            .cancel_on_drop();
+        let task = handle.wait(&WaitOptions::default()).await.unwrap();
+        assert_eq!(task.state, Some(State::Complete));
+        drop(handle);
+
+        assert!(!canceled(&server).await);
+    }
+}
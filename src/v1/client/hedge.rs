@@ -0,0 +1,134 @@
+//! Hedging of read requests made by a [`Client`](super::Client).
+//!
+//! When hedging is enabled, a read request that has not completed within the
+//! hedge delay is sent again (up to a maximum number of times) and whichever
+//! request responds first is used. The remaining requests are aborted.
+
+use std::time::Duration;
+
+use futures_util::StreamExt as _;
+use futures_util::future::Either;
+use futures_util::stream::FuturesUnordered;
+use reqwest::Response;
+use reqwest_middleware::RequestBuilder;
+
+use crate::v1::client::retry;
+
+/// The configuration for hedging read requests.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Hedge {
+    /// The delay before each additional request is sent.
+    pub(crate) delay: Duration,
+
+    /// The maximum number of additional requests.
+    pub(crate) max: u32,
+}
+
+impl Hedge {
+    /// Sends a request, hedging it if it does not complete within the delay.
+    ///
+    /// `build` is called to build each request. The first successful response
+    /// is returned; an error is only returned once every request has failed
+    /// (a retry, if any, is left to the caller).
+    pub(crate) async fn send<F>(&self, build: F) -> reqwest_middleware::Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut in_flight = FuturesUnordered::new();
+        in_flight.push(build().send());
+        let mut hedges = 0;
+
+        loop {
+            let result = if hedges < self.max {
+                let timer = Box::pin(retry::sleep(self.delay));
+
+                match futures_util::future::select(in_flight.next(), timer).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right(_) => {
+                        hedges += 1;
+                        in_flight.push(build().send());
+                        continue;
+                    }
+                }
+            } else {
+                in_flight.next().await
+            };
+
+            // SAFETY: the set of requests in flight is never empty here: an
+            // error is returned as soon as the last request fails.
+            let result = result.unwrap();
+
+            if result.is_ok() || in_flight.is_empty() {
+                // NOTE: dropping the requests that are still in flight aborts
+                // them.
+                return result;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::tasks::View;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+    use crate::v1::types::Task;
+
+    #[tokio::test]
+    async fn slow_reads_are_hedged() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#).delay(Duration::from_secs(5)),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .hedge(Duration::from_millis(100), 1)
+            .try_build()
+            .unwrap();
+
+        let start = Instant::now();
+        client.get_task("123", View::Minimal).await.unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn fast_reads_are_not_hedged() {
+        let server =
+            Server::scripted(vec![Response::json(r#"{"id":"123","state":"RUNNING"}"#)]).await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .hedge(Duration::from_secs(5), 1)
+            .try_build()
+            .unwrap();
+
+        client.get_task("123", View::Minimal).await.unwrap();
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn posts_are_never_hedged() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123"}"#).delay(Duration::from_millis(500)),
+            Response::json(r#"{"id":"456"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .hedge(Duration::from_millis(10), 3)
+            .try_build()
+            .unwrap();
+
+        assert_eq!(client.create_task(Task::default()).await.unwrap().id, "123");
+        assert_eq!(server.requests().len(), 1);
+    }
+}
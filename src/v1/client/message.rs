@@ -0,0 +1,153 @@
+//! Extraction of human-readable messages from the bodies of error responses.
+//!
+//! TES implementations report errors in a handful of JSON shapes:
+//!
+//! * Funnel (via `grpc-gateway`) responds with an `error` (and a `message`)
+//!   alongside a gRPC status `code`.
+//! * TESK responds with an RFC 7807 problem, which has a `title` and a
+//!   `detail`.
+//! * Microsoft TES (ASP.NET Core) responds with an RFC 7807 problem that may
+//!   only have a `title`, optionally listing validation `errors` by field.
+//!
+//! Parsing is best-effort: any body that is not one of these shapes yields no
+//! message.
+
+use serde_json::Value;
+
+use crate::v1::client::MAX_BODY_SNIPPET_LEN;
+
+/// The keys that may hold a message, in order of preference.
+///
+/// The `detail` of a problem is preferred over its `title`, which is usually
+/// just the reason phrase of the status.
+const KEYS: &[&str] = &["message", "detail", "error", "title", "msg"];
+
+/// Extracts a message from the body of an error response.
+pub(crate) fn parse(body: &[u8]) -> Option<String> {
+    let value = serde_json::from_slice::<Value>(body).ok()?;
+    let object = value.as_object()?;
+
+    let mut message = KEYS.iter().find_map(|key| match object.get(*key)? {
+        Value::String(message) if !message.trim().is_empty() => Some(message.trim().to_string()),
+        // NOTE: some servers nest the message (e.g., `{"error": {"message":
+        // "..."}}`).
+        Value::Object(nested) => nested
+            .get("message")
+            .and_then(Value::as_str)
+            .filter(|message| !message.trim().is_empty())
+            .map(|message| message.trim().to_string()),
+        _ => None,
+    })?;
+
+    // ASP.NET Core lists validation errors by field separately from the title.
+    if let Some(Value::Object(errors)) = object.get("errors") {
+        let details = errors
+            .iter()
+            .flat_map(|(field, reasons)| {
+                reasons
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(move |reason| format!("{field}: {reason}"))
+            })
+            .collect::<Vec<_>>();
+
+        if !details.is_empty() {
+            message = format!("{message} ({})", details.join("; "));
+        }
+    }
+
+    Some(truncate(message))
+}
+
+/// Truncates a message to [`MAX_BODY_SNIPPET_LEN`] bytes.
+fn truncate(mut message: String) -> String {
+    if message.len() <= MAX_BODY_SNIPPET_LEN {
+        return message;
+    }
+
+    let mut end = MAX_BODY_SNIPPET_LEN;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    message.truncate(end);
+    message.push_str("...");
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn funnel() {
+        let body =
+            br#"{"error":"task not found","code":5,"message":"task not found","details":[]}"#;
+        assert_eq!(parse(body).as_deref(), Some("task not found"));
+
+        let body = br#"{"error":"executors must not be empty","code":3}"#;
+        assert_eq!(parse(body).as_deref(), Some("executors must not be empty"));
+    }
+
+    #[test]
+    fn tesk() {
+        let body = br#"{
+            "detail": "'executors' is a required property",
+            "status": 400,
+            "title": "Bad Request",
+            "type": "about:blank"
+        }"#;
+        assert_eq!(
+            parse(body).as_deref(),
+            Some("'executors' is a required property")
+        );
+    }
+
+    #[test]
+    fn microsoft_tes() {
+        let body = br#"{
+            "type": "https://tools.ietf.org/html/rfc7231#section-6.5.1",
+            "title": "One or more validation errors occurred.",
+            "status": 400,
+            "traceId": "00-6d7c8e4f1a2b3c4d-01",
+            "errors": {
+                "Executors": ["At least one executor is required."],
+                "Name": ["The name is too long.", "The name is invalid."]
+            }
+        }"#;
+        assert_eq!(
+            parse(body).as_deref(),
+            Some(
+                "One or more validation errors occurred. (Executors: At least one executor is \
+                 required.; Name: The name is too long.; Name: The name is invalid.)"
+            )
+        );
+
+        let body = br#"{"title":"Not Found","status":404}"#;
+        assert_eq!(parse(body).as_deref(), Some("Not Found"));
+    }
+
+    #[test]
+    fn other_shapes() {
+        let body = br#"{"message":"invalid page token","status_code":400}"#;
+        assert_eq!(parse(body).as_deref(), Some("invalid page token"));
+
+        let body = br#"{"error":{"code":403,"message":"quota exceeded"}}"#;
+        assert_eq!(parse(body).as_deref(), Some("quota exceeded"));
+
+        assert_eq!(parse(b""), None);
+        assert_eq!(parse(b"<html>Bad Gateway</html>"), None);
+        assert_eq!(parse(br#"["not", "an", "object"]"#), None);
+        assert_eq!(parse(br#"{"message":"  ","code":3}"#), None);
+    }
+
+    #[test]
+    fn long_messages_are_truncated() {
+        let body = serde_json::to_vec(&serde_json::json!({ "message": "é".repeat(1024) })).unwrap();
+        let message = parse(&body).unwrap();
+        assert!(message.ends_with("..."));
+        assert!(message.len() <= MAX_BODY_SNIPPET_LEN + 3);
+    }
+}
@@ -0,0 +1,308 @@
+//! Metrics for requests made by a [`Client`](super::Client).
+//!
+//! A [`RequestMetrics`] implementation is installed with
+//! [`Builder::metrics()`](super::Builder::metrics) and is called once for
+//! every attempt of every request (including retries) and once for every call
+//! (all of the attempts of a request). This allows, for example, exporting
+//! request, retry, and failure counts or latency histograms to a metrics
+//! system.
+
+use std::time::Duration;
+
+use reqwest::Method;
+use reqwest::StatusCode;
+use url::Url;
+
+use crate::v1::client::Error;
+use crate::v1::client::retry::RequestOutcome;
+
+/// A record of a single attempt of a request.
+#[derive(Debug)]
+pub struct RequestRecord<'a> {
+    /// The URL of the request.
+    url: &'a Url,
+
+    /// The outcome of the attempt.
+    outcome: &'a RequestOutcome<'a>,
+
+    /// How long the attempt took.
+    latency: Duration,
+}
+
+impl<'a> RequestRecord<'a> {
+    /// Creates a new record.
+    pub(crate) fn new(url: &'a Url, outcome: &'a RequestOutcome<'a>, latency: Duration) -> Self {
+        Self {
+            url,
+            outcome,
+            latency,
+        }
+    }
+
+    /// Gets the method of the request.
+    pub fn method(&self) -> &Method {
+        self.outcome.method()
+    }
+
+    /// Gets the URL of the request.
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+
+    /// Gets the attempt number (starting at one).
+    ///
+    /// Any attempt after the first is a retry.
+    pub fn attempt(&self) -> u32 {
+        self.outcome.attempt()
+    }
+
+    /// Gets the status of the response (if a response was received).
+    pub fn status(&self) -> Option<StatusCode> {
+        self.outcome.status()
+    }
+
+    /// Returns whether the attempt failed (i.e., no response was received or
+    /// the response did not have a successful status).
+    pub fn is_failure(&self) -> bool {
+        !self
+            .status()
+            .map(|status| status.is_success())
+            .unwrap_or(false)
+    }
+
+    /// Gets how long the attempt took (until the response headers were
+    /// received).
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Gets the outcome of the attempt.
+    pub fn outcome(&self) -> &RequestOutcome<'a> {
+        self.outcome
+    }
+}
+
+/// A record of a call to an endpoint: every attempt of a request (including
+/// retries) and reading its response.
+#[derive(Debug)]
+pub struct CallRecord<'a> {
+    /// The method of the request.
+    method: &'a Method,
+
+    /// The URL of the request.
+    url: &'a Url,
+
+    /// The number of attempts that were made.
+    attempts: u32,
+
+    /// How long the call took.
+    elapsed: Duration,
+
+    /// The error (if the call failed).
+    error: Option<&'a Error>,
+}
+
+impl<'a> CallRecord<'a> {
+    /// Creates a new record.
+    pub(crate) fn new(
+        method: &'a Method,
+        url: &'a Url,
+        attempts: u32,
+        elapsed: Duration,
+        error: Option<&'a Error>,
+    ) -> Self {
+        Self {
+            method,
+            url,
+            attempts,
+            elapsed,
+            error,
+        }
+    }
+
+    /// Gets the method of the request.
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /// Gets the URL of the request.
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+
+    /// Gets the number of attempts that were made.
+    ///
+    /// This is zero if the call failed before any request was sent (e.g.,
+    /// because the circuit breaker was open).
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Gets how long the call took (including every attempt, the delays
+    /// between them, and reading the final response).
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Gets the error (if the call failed).
+    pub fn error(&self) -> Option<&Error> {
+        self.error
+    }
+
+    /// Returns whether the call succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A sink for metrics about the requests made by a client.
+///
+/// Implementations must be cheap, as they are called for every attempt of
+/// every request, and thread-safe, as they are shared between clones of a
+/// client.
+pub trait RequestMetrics: Send + Sync + 'static {
+    /// Records a single attempt of a request.
+    fn record(&self, record: &RequestRecord<'_>);
+
+    /// Records a call to an endpoint once it has finished.
+    ///
+    /// By default, calls are not recorded.
+    fn record_call(&self, call: &CallRecord<'_>) {
+        let _ = call;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::tasks::View;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+
+    /// A collected record: the request, attempt, status, and whether the
+    /// attempt failed.
+    type Record = (String, u32, Option<u16>, bool);
+
+    /// Metrics that collect the records they are given.
+    #[derive(Default)]
+    struct Collect(Mutex<Vec<Record>>);
+
+    impl RequestMetrics for Collect {
+        fn record(&self, record: &RequestRecord<'_>) {
+            self.0.lock().unwrap().push((
+                format!("{} {}", record.method(), record.url().path()),
+                record.attempt(),
+                record.status().map(|status| status.as_u16()),
+                record.is_failure(),
+            ));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn records_every_attempt() {
+        let server = Server::scripted(vec![
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::new(503, ""),
+            Response::new(500, ""),
+            Response::json(r#"{"id":"456","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let metrics = Arc::new(Collect::default());
+        let client = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .metrics(metrics.clone())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        client.get_task("123", View::Minimal).await.unwrap();
+        client.clone().get_task("456", View::Minimal).await.unwrap();
+
+        let records = metrics.0.lock().unwrap();
+        assert_eq!(
+            *records,
+            [
+                (String::from("GET /tasks/123"), 1, Some(200), false),
+                (String::from("GET /tasks/456"), 1, Some(503), true),
+                (String::from("GET /tasks/456"), 2, Some(500), true),
+                (String::from("GET /tasks/456"), 3, Some(200), false),
+            ]
+        );
+
+        let retries = records
+            .iter()
+            .filter(|(_, attempt, ..)| *attempt > 1)
+            .count();
+        let failures = records.iter().filter(|(.., failure)| *failure).count();
+        assert_eq!((records.len(), retries, failures), (4, 2, 2));
+    }
+
+    /// A collected call: the request, number of attempts, how long it took,
+    /// and the status of the error (if the call failed).
+    type Call = (String, u32, Duration, Option<Option<u16>>);
+
+    /// Metrics that collect the calls they are given.
+    #[derive(Default)]
+    struct Calls(Mutex<Vec<Call>>);
+
+    impl RequestMetrics for Calls {
+        fn record(&self, _: &RequestRecord<'_>) {}
+
+        fn record_call(&self, call: &CallRecord<'_>) {
+            assert_eq!(call.is_success(), call.error().is_none());
+            self.0.lock().unwrap().push((
+                format!("{} {}", call.method(), call.url().path()),
+                call.attempts(),
+                call.elapsed(),
+                call.error()
+                    .map(|err| err.status().map(|status| status.as_u16())),
+            ));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn records_every_call() {
+        let server = Server::scripted(vec![
+            Response::new(503, ""),
+            Response::new(500, ""),
+            Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+            Response::new(404, ""),
+        ])
+        .await;
+
+        let metrics = Arc::new(Calls::default());
+        let client = Builder::default()
+            .url(server.url())
+            .retries(3)
+            .metrics(metrics.clone())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        client.get_task("123", View::Minimal).await.unwrap();
+        client.get_task("456", View::Minimal).await.unwrap_err();
+
+        let calls = metrics.0.lock().unwrap();
+        let summary = calls
+            .iter()
+            .map(|(request, attempts, _, error)| (request.as_str(), *attempts, *error))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                ("GET /tasks/123", 3, None),
+                ("GET /tasks/456", 1, Some(Some(404))),
+            ]
+        );
+
+        // NOTE: the clock is paused, so only the delays between attempts
+        // elapse.
+        assert!(calls[0].2 > Duration::ZERO);
+    }
+}
@@ -0,0 +1,354 @@
+//! Support for the OAuth 2.0 client credentials flow.
+//!
+//! Tokens are fetched lazily from the token endpoint the first time they are
+//! needed, cached, and refreshed shortly before they expire. If the service
+//! rejects a request with `401 Unauthorized`, the token is refreshed and the
+//! request is retried exactly once.
+
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use reqwest::Request;
+use reqwest::Response;
+use reqwest::StatusCode;
+use reqwest::header::AUTHORIZATION;
+use reqwest::header::HeaderValue;
+use reqwest_middleware::Middleware;
+use reqwest_middleware::Next;
+use tokio::sync::Mutex;
+use tracing::debug;
+use url::Url;
+
+use crate::v1::client::auth::Failure;
+
+/// The amount of time before a token expires that it is considered stale.
+const EXPIRATION_SKEW: Duration = Duration::from_secs(30);
+
+/// An error related to fetching an OAuth 2.0 token.
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error from `reqwest` when communicating with the token endpoint.
+    Reqwest(reqwest::Error),
+
+    /// The token endpoint responded with an unsuccessful status code.
+    Status(StatusCode),
+
+    /// The token returned by the token endpoint is not a valid header value.
+    InvalidToken,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Reqwest(err) => write!(f, "token endpoint request failed: {err}"),
+            Error::Status(status) => write!(f, "token endpoint responded with {status}"),
+            Error::InvalidToken => write!(
+                f,
+                "the token endpoint returned a token that is not a valid header value"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Reqwest(err) => Some(err),
+            Error::Status(_) | Error::InvalidToken => None,
+        }
+    }
+}
+
+/// The configuration for the OAuth 2.0 client credentials flow.
+#[derive(Clone)]
+pub struct ClientCredentials {
+    /// The URL of the token endpoint.
+    pub token_url: Url,
+
+    /// The client identifier.
+    pub client_id: String,
+
+    /// The client secret.
+    pub client_secret: String,
+
+    /// The scopes to request.
+    pub scopes: Vec<String>,
+}
+
+impl std::fmt::Debug for ClientCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientCredentials")
+            .field("token_url", &self.token_url)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"***")
+            .field("scopes", &self.scopes)
+            .finish()
+    }
+}
+
+/// A successful response from the token endpoint.
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    /// The access token.
+    access_token: String,
+
+    /// The number of seconds until the token expires.
+    expires_in: Option<u64>,
+}
+
+/// A cached token.
+struct Token {
+    /// The value of the `Authorization` header.
+    value: HeaderValue,
+
+    /// When the token expires (if known).
+    ///
+    /// `std::time::Instant` is not used here because it is unavailable on
+    /// `wasm32` targets.
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl Token {
+    /// Returns whether the token is expired or about to expire.
+    fn is_stale(&self) -> bool {
+        self.expires_at
+            .map(|at| Utc::now() + EXPIRATION_SKEW >= at)
+            .unwrap_or(false)
+    }
+}
+
+/// A middleware that authorizes requests using the OAuth 2.0 client
+/// credentials flow.
+pub(crate) struct OAuth2 {
+    /// The configuration.
+    credentials: ClientCredentials,
+
+    /// The client used to communicate with the token endpoint.
+    client: reqwest::Client,
+
+    /// The cached token.
+    ///
+    /// The lock is held while a token is being fetched so that concurrent
+    /// requests wait for a single refresh rather than each contacting the
+    /// token endpoint.
+    token: Mutex<Option<Token>>,
+}
+
+impl OAuth2 {
+    /// Creates a new middleware.
+    pub(crate) fn new(credentials: ClientCredentials, client: reqwest::Client) -> Self {
+        Self {
+            credentials,
+            client,
+            token: Default::default(),
+        }
+    }
+
+    /// Gets a valid header value, fetching a new token if needed.
+    ///
+    /// If `rejected` is provided and it is the currently cached token, a new
+    /// token is fetched regardless of its expiration.
+    async fn authorization(&self, rejected: Option<&HeaderValue>) -> Result<HeaderValue, Error> {
+        let mut token = self.token.lock().await;
+
+        if let Some(token) = token.as_ref() {
+            if !token.is_stale() && rejected != Some(&token.value) {
+                return Ok(token.value.clone());
+            }
+        }
+
+        let fetched = self.fetch().await?;
+        let value = fetched.value.clone();
+        *token = Some(fetched);
+
+        Ok(value)
+    }
+
+    /// Fetches a new token from the token endpoint.
+    async fn fetch(&self) -> Result<Token, Error> {
+        debug!(
+            "fetching an OAuth 2.0 token from {}",
+            self.credentials.token_url
+        );
+
+        let scope = self.credentials.scopes.join(" ");
+        let mut form = vec![("grant_type", "client_credentials")];
+
+        if !scope.is_empty() {
+            form.push(("scope", &scope));
+        }
+
+        let response = self
+            .client
+            .post(self.credentials.token_url.clone())
+            .basic_auth(
+                &self.credentials.client_id,
+                Some(&self.credentials.client_secret),
+            )
+            .form(&form)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        if !response.status().is_success() {
+            return Err(Error::Status(response.status()));
+        }
+
+        let response = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", response.access_token))
+            .map_err(|_| Error::InvalidToken)?;
+        value.set_sensitive(true);
+
+        Ok(Token {
+            value,
+            expires_at: response
+                .expires_in
+                .map(|seconds| Utc::now() + Duration::from_secs(seconds)),
+        })
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+impl Middleware for OAuth2 {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let value = self
+            .authorization(None)
+            .await
+            .map_err(|err| reqwest_middleware::Error::middleware(Failure(Box::new(err))))?;
+
+        // NOTE: if the request cannot be cloned, it cannot be retried after a
+        // `401 Unauthorized` response.
+        let retry = req.try_clone();
+        req.headers_mut().insert(AUTHORIZATION, value.clone());
+
+        let response = next.clone().run(req, extensions).await?;
+
+        match retry {
+            Some(mut req) if response.status() == StatusCode::UNAUTHORIZED => {
+                debug!("service responded with 401: refreshing the OAuth 2.0 token");
+
+                let value = self
+                    .authorization(Some(&value))
+                    .await
+                    .map_err(|err| reqwest_middleware::Error::middleware(Failure(Box::new(err))))?;
+
+                req.headers_mut().insert(AUTHORIZATION, value);
+                next.run(req, extensions).await
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::v1::client;
+    use crate::v1::client::test_server::Response;
+    use crate::v1::client::test_server::Server;
+    use crate::v1::types::Task;
+
+    /// Starts a server that issues tokens and accepts only the latest one.
+    async fn server(tokens: Arc<AtomicUsize>) -> Server {
+        Server::start(move |request| {
+            if request.path == "/token" {
+                let count = tokens.fetch_add(1, Ordering::SeqCst) + 1;
+                return Response::json(format!(
+                    r#"{{"access_token":"token-{count}","token_type":"Bearer","expires_in":300}}"#
+                ));
+            }
+
+            let expected = format!("Bearer token-{}", tokens.load(Ordering::SeqCst));
+            match request.header("authorization") {
+                Some(value) if value == expected => Response::json(r#"{"id":"123"}"#),
+                _ => Response::new(401, ""),
+            }
+        })
+        .await
+    }
+
+    /// Builds a client for the server.
+    fn client(server: &Server) -> client::Client {
+        client::Builder::default()
+            .url(server.url())
+            .oauth2_client_credentials(server.url().join("token").unwrap(), "id", "secret", ["tes"])
+            .try_build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn caches_tokens_across_concurrent_requests() {
+        let tokens = Arc::new(AtomicUsize::new(0));
+        let server = server(tokens.clone()).await;
+        let client = client(&server);
+
+        let results =
+            futures::future::join_all((0..5).map(|_| client.create_task(Task::default()))).await;
+
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(tokens.load(Ordering::SeqCst), 1);
+
+        let token = server
+            .requests()
+            .into_iter()
+            .find(|request| request.path == "/token")
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&token.body),
+            "grant_type=client_credentials&scope=tes"
+        );
+        assert_eq!(token.header("authorization").unwrap(), "Basic aWQ6c2VjcmV0");
+    }
+
+    #[tokio::test]
+    async fn refreshes_the_token_once_on_unauthorized() {
+        let tokens = Arc::new(AtomicUsize::new(0));
+        let server = server(tokens.clone()).await;
+        let client = client(&server);
+
+        client.create_task(Task::default()).await.unwrap();
+
+        // Simulates the service revoking the current token.
+        tokens.fetch_add(1, Ordering::SeqCst);
+
+        let created = client.create_task(Task::default()).await.unwrap();
+        assert_eq!(created.id, "123");
+        assert_eq!(tokens.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn token_endpoint_errors() {
+        let server = Server::scripted(vec![Response::new(400, "")]).await;
+        let err = client(&server)
+            .create_task(Task::default())
+            .await
+            .unwrap_err();
+
+        match err.inner() {
+            client::Error::Auth(err) => assert!(matches!(
+                err.downcast_ref::<Error>(),
+                Some(Error::Status(StatusCode::BAD_REQUEST))
+            )),
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+}
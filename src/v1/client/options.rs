@@ -6,7 +6,7 @@ use reqwest::header::HeaderMap;
 const DEFAULT_RETRIES: u32 = 3;
 
 /// Options used within a [`Client`](super::Client).
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Options {
     /// Headers to include in each request.
     pub headers: HeaderMap,
@@ -15,6 +15,17 @@ pub struct Options {
     pub retries: u32,
 }
 
+impl std::fmt::Debug for Options {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // NOTE: header values may contain credentials, so only the names of
+        // the headers are printed.
+        f.debug_struct("Options")
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field("retries", &self.retries)
+            .finish()
+    }
+}
+
 impl Default for Options {
     fn default() -> Self {
         Self {
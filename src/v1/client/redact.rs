@@ -0,0 +1,188 @@
+//! Redaction of sensitive values before they are logged.
+
+use std::fmt::Write as _;
+
+use reqwest::header::AUTHORIZATION;
+use reqwest::header::COOKIE;
+use reqwest::header::HeaderMap;
+use reqwest::header::HeaderName;
+use reqwest::header::PROXY_AUTHORIZATION;
+use reqwest::header::SET_COOKIE;
+
+/// The value printed in place of a redacted value.
+pub(crate) const REDACTED: &str = "***";
+
+/// The default maximum number of bytes of a request body that are logged.
+pub(crate) const DEFAULT_MAX_BODY_LEN: usize = 4096;
+
+/// The keys of JSON objects whose values are redacted from logged bodies.
+///
+/// The `content` of a task input may contain arbitrary (and potentially
+/// sensitive) file contents.
+const REDACTED_KEYS: &[&str] = &["content"];
+
+/// Redacts sensitive headers and request bodies before they are logged.
+#[derive(Clone, Debug)]
+pub(crate) struct Redactor {
+    /// The headers whose values are redacted.
+    headers: Vec<HeaderName>,
+
+    /// The maximum number of bytes of a body that are logged.
+    max_body_len: usize,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            headers: vec![AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE, SET_COOKIE],
+            max_body_len: DEFAULT_MAX_BODY_LEN,
+        }
+    }
+}
+
+impl Redactor {
+    /// Adds a header whose value is redacted.
+    pub(crate) fn add_header(&mut self, name: HeaderName) {
+        if !self.headers.contains(&name) {
+            self.headers.push(name);
+        }
+    }
+
+    /// Sets the maximum number of bytes of a body that are logged.
+    pub(crate) fn set_max_body_len(&mut self, len: usize) {
+        self.max_body_len = len;
+    }
+
+    /// Returns whether the value of a header is redacted.
+    pub(crate) fn is_redacted(&self, name: &HeaderName) -> bool {
+        self.headers.contains(name)
+    }
+
+    /// Formats headers for logging, redacting the value of any sensitive
+    /// header.
+    ///
+    /// Headers marked as sensitive are always redacted.
+    pub(crate) fn headers(&self, headers: &HeaderMap) -> String {
+        let mut result = String::new();
+
+        for (name, value) in headers {
+            if !result.is_empty() {
+                result.push_str(", ");
+            }
+
+            let value = if value.is_sensitive() || self.is_redacted(name) {
+                REDACTED
+            } else {
+                value.to_str().unwrap_or(REDACTED)
+            };
+
+            // SAFETY: writing to a `String` never fails.
+            write!(result, "{name}: {value}").unwrap();
+        }
+
+        result
+    }
+
+    /// Formats a JSON body for logging.
+    ///
+    /// The values of sensitive keys (such as the `content` of a task input)
+    /// are redacted and the result is truncated to the maximum body length.
+    pub(crate) fn body(&self, body: &[u8]) -> String {
+        let mut body = match serde_json::from_slice::<serde_json::Value>(body) {
+            Ok(mut value) => {
+                redact_keys(&mut value);
+                value.to_string()
+            }
+            Err(_) => String::from_utf8_lossy(body).into_owned(),
+        };
+
+        if body.len() > self.max_body_len {
+            let mut end = self.max_body_len;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            let truncated = body.len() - end;
+            body.truncate(end);
+
+            // SAFETY: writing to a `String` never fails.
+            write!(body, "... ({truncated} bytes truncated)").unwrap();
+        }
+
+        body
+    }
+}
+
+/// Recursively redacts the values of sensitive keys within a JSON value.
+fn redact_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_KEYS.contains(&key.as_str()) && !value.is_null() {
+                    *value = serde_json::Value::String(REDACTED.into());
+                } else {
+                    redact_keys(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(redact_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers.insert(COOKIE, HeaderValue::from_static("session=secret"));
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        headers.insert("x-request-id", HeaderValue::from_static("123"));
+
+        let mut redactor = Redactor::default();
+        redactor.add_header(HeaderName::from_static("x-api-key"));
+
+        assert_eq!(
+            redactor.headers(&headers),
+            "authorization: ***, cookie: ***, x-api-key: ***, x-request-id: 123"
+        );
+    }
+
+    #[test]
+    fn sensitive_headers() {
+        let mut value = HeaderValue::from_static("secret");
+        value.set_sensitive(true);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-token", value);
+
+        assert_eq!(Redactor::default().headers(&headers), "x-token: ***");
+    }
+
+    #[test]
+    fn body() {
+        let body = json!({
+            "name": "hello",
+            "inputs": [{ "path": "/data/file", "content": "secret" }, { "path": "/b" }]
+        })
+        .to_string();
+
+        assert_eq!(
+            Redactor::default().body(body.as_bytes()),
+            r#"{"inputs":[{"content":"***","path":"/data/file"},{"path":"/b"}],"name":"hello"}"#
+        );
+
+        let mut redactor = Redactor::default();
+        redactor.set_max_body_len(10);
+        assert_eq!(
+            redactor.body(br#"{"name":"hello world"}"#),
+            r#"{"name":"h... (12 bytes truncated)"#
+        );
+    }
+}
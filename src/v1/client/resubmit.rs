@@ -0,0 +1,123 @@
+//! Resubmitting tasks submitted through a [`Client`](super::Client) that
+//! failed.
+//!
+//! See [`Client::run_with_resubmission()`](super::Client::run_with_resubmission).
+
+use std::sync::Arc;
+
+use crate::v1::types::Task;
+use crate::v1::types::task::State;
+
+/// The maximum number of attempts by default.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// A function that modifies a task before it is resubmitted.
+type Mutate = dyn Fn(&mut Task, u32) + Send + Sync;
+
+/// A policy for resubmitting tasks that failed.
+///
+/// By default, a task is attempted up to [`DEFAULT_MAX_ATTEMPTS`] times, and
+/// it is resubmitted when it was [preempted](State::Preempted) or failed with
+/// a [`State::SystemError`].
+#[derive(Clone)]
+pub struct ResubmitPolicy {
+    /// The maximum number of attempts (including the first).
+    pub(crate) max_attempts: u32,
+
+    /// The states in which a finished task is resubmitted.
+    pub(crate) retry_on: Vec<State>,
+
+    /// The function that modifies a task before it is resubmitted.
+    pub(crate) mutate: Option<Arc<Mutate>>,
+}
+
+impl ResubmitPolicy {
+    /// Sets the maximum number of attempts (including the first).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set maximum. By default, the
+    /// maximum is [`DEFAULT_MAX_ATTEMPTS`]. A maximum of zero is treated as a
+    /// maximum of one.
+    pub fn max_attempts(mut self, value: u32) -> Self {
+        self.max_attempts = value.max(1);
+        self
+    }
+
+    /// Sets the states in which a finished task is resubmitted.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set states. By default, tasks in
+    /// the [`State::Preempted`] and [`State::SystemError`] states are
+    /// resubmitted.
+    pub fn retry_on(mut self, states: impl IntoIterator<Item = State>) -> Self {
+        self.retry_on = states.into_iter().collect();
+        self
+    }
+
+    /// Sets a function that modifies a task before it is resubmitted.
+    ///
+    /// The function is called with the task and the number of the attempt
+    /// about to be made (starting from two). For example, a task may be made
+    /// non-preemptible for its last attempt:
+    ///
+    /// ```
+    /// use tes::v1::client::resubmit::ResubmitPolicy;
+    ///
+    /// let policy = ResubmitPolicy::default()
+    ///     .max_attempts(3)
+    ///     .mutate(|task, attempt| {
+    ///         if attempt == 3 {
+    ///             task.resources
+    ///                 .get_or_insert_with(Default::default)
+    ///                 .preemptible = Some(false);
+    ///         }
+    ///     });
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set function. By default, a
+    /// task is resubmitted unchanged.
+    pub fn mutate<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Task, u32) + Send + Sync + 'static,
+    {
+        self.mutate = Some(Arc::new(f));
+        self
+    }
+}
+
+impl Default for ResubmitPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_on: vec![State::Preempted, State::SystemError],
+            mutate: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ResubmitPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResubmitPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("retry_on", &self.retry_on)
+            .field("mutate", &self.mutate.as_ref().map(|_| "<function>"))
+            .finish()
+    }
+}
+
+/// The result of running a task with resubmission.
+#[derive(Clone, Debug)]
+pub struct Resubmission {
+    /// The final attempt of the task.
+    ///
+    /// Its state may still be one that is resubmitted if every attempt was
+    /// used.
+    pub task: Task,
+
+    /// The IDs of the attempted tasks (from the first to the last).
+    pub attempts: Vec<String>,
+}
@@ -0,0 +1,909 @@
+//! Retrying of requests made by a [`Client`](super::Client).
+//!
+//! Whether a failed request is retried is decided by a [`RetryPolicy`]. By
+//! default ([`DefaultRetryPolicy`]), a request is retried when it fails with a
+//! transient error: a connection failure, a timeout, or a response with a
+//! `408 Request Timeout`, `429 Too Many Requests`, or `5xx` status.
+//!
+//! Between attempts, the client waits according to a retry strategy (by
+//! default, [a jittered exponential
+//! backoff](super::strategy::default_backoff)). If the service provides a
+//! `Retry-After` header (either in seconds or as an HTTP date) on a `429` or
+//! `503` response, the client waits at least that long (up to a configurable
+//! maximum).
+//!
+//! Each retry is logged as a warning and reported to the [`RetryObserver`] (if
+//! one is installed).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use reqwest::Method;
+use reqwest::Response;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use reqwest::header::RETRY_AFTER;
+use url::Url;
+
+use crate::v1::client::strategy;
+use crate::v1::client::strategy::Backoff;
+use crate::v1::client::strategy::RetryConfig;
+
+/// The default maximum delay honored from a `Retry-After` header.
+pub(crate) const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// Whether a request should be retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Retryability {
+    /// The failure is transient, so the request should be retried.
+    Retry,
+
+    /// The request should not be retried.
+    ///
+    /// This is also returned for successful requests.
+    Permanent,
+}
+
+/// The outcome of a single attempt of a request.
+#[derive(Debug)]
+pub struct RequestOutcome<'a> {
+    /// The method of the request.
+    method: &'a Method,
+
+    /// The attempt number (starting at one).
+    attempt: u32,
+
+    /// Whether the request can safely be repeated.
+    idempotent: bool,
+
+    /// The result of the attempt.
+    result: &'a reqwest_middleware::Result<Response>,
+}
+
+impl<'a> RequestOutcome<'a> {
+    /// Creates a new outcome.
+    pub(crate) fn new(
+        method: &'a Method,
+        attempt: u32,
+        idempotent: bool,
+        result: &'a reqwest_middleware::Result<Response>,
+    ) -> Self {
+        Self {
+            method,
+            attempt,
+            idempotent,
+            result,
+        }
+    }
+
+    /// Gets the method of the request.
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /// Gets the attempt number (starting at one).
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Returns whether the request can safely be repeated.
+    ///
+    /// Requests that create tasks are not idempotent (unless
+    /// [idempotent submission](super::Builder::idempotency_tag) is enabled):
+    /// repeating one after the service received it may create a duplicate
+    /// task.
+    pub fn is_idempotent(&self) -> bool {
+        self.idempotent
+    }
+
+    /// Gets the status of the response (if a response was received).
+    pub fn status(&self) -> Option<StatusCode> {
+        self.result.as_ref().ok().map(Response::status)
+    }
+
+    /// Gets the error (if no response was received).
+    pub fn error(&self) -> Option<&reqwest_middleware::Error> {
+        self.result.as_ref().err()
+    }
+
+    /// Gets the error from `reqwest` (if no response was received).
+    fn reqwest_error(&self) -> Option<&reqwest::Error> {
+        match self.result {
+            Err(reqwest_middleware::Error::Reqwest(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the attempt failed to connect to the service.
+    pub fn is_connect(&self) -> bool {
+        self.reqwest_error().map(is_connect).unwrap_or(false)
+    }
+
+    /// Returns whether the attempt timed out.
+    pub fn is_timeout(&self) -> bool {
+        self.reqwest_error()
+            .map(reqwest::Error::is_timeout)
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the service failed (i.e., it could not be reached or it
+    /// responded with a `5xx` status).
+    ///
+    /// Returns `None` if the attempt failed before anything was sent.
+    pub(crate) fn service_failed(&self) -> Option<bool> {
+        match self.status() {
+            Some(status) => Some(status.is_server_error()),
+            None if self.is_connect() || self.is_timeout() || self.is_request() => Some(true),
+            None => None,
+        }
+    }
+
+    /// Returns whether the attempt failed while sending the request or
+    /// reading the response (e.g., the connection was reset).
+    pub fn is_request(&self) -> bool {
+        self.reqwest_error().map(is_request).unwrap_or(false)
+    }
+}
+
+/// Returns whether an error from `reqwest` is a failure to connect to the
+/// service.
+pub(crate) fn is_connect(err: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    return err.is_connect();
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = err;
+        false
+    }
+}
+
+/// Returns whether an error from `reqwest` is a failure while sending the
+/// request or reading the response.
+pub(crate) fn is_request(err: &reqwest::Error) -> bool {
+    err.is_request() && !err.is_builder() && !err.is_body() && !err.is_decode()
+}
+
+/// Returns whether an error from `reqwest` (where no response was received)
+/// is transient.
+pub(crate) fn is_transient_error(err: &reqwest::Error) -> bool {
+    is_connect(err) || err.is_timeout() || is_request(err)
+}
+
+/// Returns whether a status indicates a transient failure.
+pub(crate) fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// A policy that decides whether a failed request should be retried.
+///
+/// A policy is installed with
+/// [`Builder::retry_policy()`](super::Builder::retry_policy). The number of
+/// retries and the delay between them are configured separately.
+pub trait RetryPolicy: Send + Sync + 'static {
+    /// Classifies the outcome of an attempt of a request.
+    fn classify(&self, outcome: &RequestOutcome<'_>) -> Retryability;
+}
+
+/// A retry of a request that is about to be made.
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    /// The URL of the request.
+    url: &'a Url,
+
+    /// The outcome of the failed attempt.
+    outcome: &'a RequestOutcome<'a>,
+
+    /// The delay before the retry.
+    delay: Duration,
+
+    /// Whether the delay was requested by the service.
+    requested: bool,
+}
+
+impl<'a> RetryEvent<'a> {
+    /// Creates a new event.
+    pub(crate) fn new(
+        url: &'a Url,
+        outcome: &'a RequestOutcome<'a>,
+        delay: Duration,
+        requested: bool,
+    ) -> Self {
+        Self {
+            url,
+            outcome,
+            delay,
+            requested,
+        }
+    }
+
+    /// Gets the method of the request.
+    pub fn method(&self) -> &Method {
+        self.outcome.method()
+    }
+
+    /// Gets the URL of the request.
+    pub fn url(&self) -> &Url {
+        self.url
+    }
+
+    /// Gets the number of the attempt that failed (starting at one).
+    ///
+    /// The upcoming retry is attempt `attempt() + 1`.
+    pub fn attempt(&self) -> u32 {
+        self.outcome.attempt()
+    }
+
+    /// Gets the outcome of the failed attempt.
+    pub fn outcome(&self) -> &RequestOutcome<'a> {
+        self.outcome
+    }
+
+    /// Gets the delay before the retry.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Returns whether the delay was requested by the service (using a
+    /// `Retry-After` header).
+    pub fn is_requested(&self) -> bool {
+        self.requested
+    }
+}
+
+/// A failed attempt of a request that was retried.
+///
+/// The attempts of a request that failed after being retried are reported by
+/// [`Error::attempts()`](super::Error::attempts).
+#[derive(Clone, Debug)]
+pub struct Attempt {
+    /// The attempt number (starting at one).
+    attempt: u32,
+
+    /// The status of the response (if a response was received).
+    status: Option<StatusCode>,
+
+    /// A short description of the failure.
+    reason: String,
+
+    /// The delay before the next attempt (if one was made).
+    delay: Option<Duration>,
+}
+
+impl Attempt {
+    /// Creates a new attempt from the outcome of a request.
+    pub(crate) fn new(outcome: &RequestOutcome<'_>, delay: Option<Duration>) -> Self {
+        let reason = match (outcome.status(), outcome.error()) {
+            (Some(status), _) => status.as_u16().to_string(),
+            (None, Some(reqwest_middleware::Error::Reqwest(err))) => describe(err),
+            (None, Some(err)) => err.to_string(),
+            (None, None) => String::from("unknown"),
+        };
+
+        Self {
+            attempt: outcome.attempt(),
+            status: outcome.status(),
+            reason,
+            delay,
+        }
+    }
+
+    /// Gets the attempt number (starting at one).
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Gets the status of the response (if a response was received).
+    pub fn status(&self) -> Option<StatusCode> {
+        self.status
+    }
+
+    /// Gets a short description of the failure (e.g., `timeout`, `503`, or
+    /// `connection refused`).
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Gets the delay before the next attempt.
+    ///
+    /// This is `None` for the final attempt.
+    pub fn delay(&self) -> Option<Duration> {
+        self.delay
+    }
+}
+
+/// The attempts made for a request.
+#[derive(Debug)]
+pub(crate) struct History {
+    /// The failed attempts.
+    pub(crate) failures: Vec<Attempt>,
+
+    /// The number of attempts that were made.
+    pub(crate) attempts: u32,
+
+    /// When the request was started.
+    pub(crate) start: Instant,
+}
+
+impl History {
+    /// Starts the history of a request.
+    pub(crate) fn new() -> Self {
+        Self {
+            failures: Vec::new(),
+            attempts: 0,
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns whether the request was retried.
+    pub(crate) fn is_retried(&self) -> bool {
+        self.failures.iter().any(|attempt| attempt.delay.is_some())
+    }
+}
+
+/// Describes an error from `reqwest` (where no response was received) in a
+/// few words.
+fn describe(err: &reqwest::Error) -> String {
+    if err.is_timeout() {
+        return String::from("timeout");
+    }
+
+    if is_connect(err) {
+        let mut source = std::error::Error::source(err);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<std::io::Error>() {
+                if err.kind() == std::io::ErrorKind::ConnectionRefused {
+                    return String::from("connection refused");
+                }
+            }
+
+            source = err.source();
+        }
+
+        return String::from("connection failed");
+    }
+
+    if is_request(err) {
+        return String::from("request failed");
+    }
+
+    err.to_string()
+}
+
+/// An observer that is notified whenever a request is retried.
+///
+/// An observer is installed with
+/// [`Builder::retry_observer()`](super::Builder::retry_observer) and is
+/// notified (in addition to the warning logged by the client) before the
+/// client waits to retry a request. This is useful for, say, counting retries
+/// or reporting progress.
+pub trait RetryObserver: Send + Sync + 'static {
+    /// Called before a request is retried.
+    fn on_retry(&self, event: &RetryEvent<'_>);
+}
+
+/// The default [`RetryPolicy`].
+///
+/// Connection failures, timeouts, errors while sending requests, and
+/// responses with a `408 Request Timeout`, `429 Too Many Requests`, or `5xx`
+/// status are retried.
+///
+/// Requests that are not [idempotent](RequestOutcome::is_idempotent) are only
+/// retried when the client failed to connect to the service (as nothing was
+/// sent).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRetryPolicy;
+
+impl RetryPolicy for DefaultRetryPolicy {
+    fn classify(&self, outcome: &RequestOutcome<'_>) -> Retryability {
+        if !outcome.is_idempotent() {
+            // NOTE: for any other failure, the service may have already
+            // processed the request.
+            return if outcome.is_connect() {
+                Retryability::Retry
+            } else {
+                Retryability::Permanent
+            };
+        }
+
+        let transient = match outcome.status() {
+            Some(status) => is_transient_status(status),
+            // NOTE: errors from the middleware (e.g., failing to obtain
+            // credentials) are never retried.
+            None => outcome.is_connect() || outcome.is_timeout() || outcome.is_request(),
+        };
+
+        if transient {
+            Retryability::Retry
+        } else {
+            Retryability::Permanent
+        }
+    }
+}
+
+/// A kind of operation performed by a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Operation {
+    /// A read (e.g., getting a task).
+    Read,
+
+    /// Creating a task.
+    Create,
+
+    /// Cancelling a task.
+    Cancel,
+}
+
+/// The configuration for retrying requests.
+#[derive(Clone)]
+pub(crate) struct Config {
+    /// The strategies for the delays between retries of each operation.
+    ///
+    /// A strategy is cloned for each request.
+    pub(crate) strategies: RetryConfig,
+
+    /// The maximum delay honored from a `Retry-After` header.
+    pub(crate) max_retry_after: Duration,
+
+    /// The policy that decides whether a request is retried.
+    pub(crate) policy: Arc<dyn RetryPolicy>,
+
+    /// The maximum time spent on a request (including retries).
+    pub(crate) deadline: Option<Duration>,
+
+    /// The observer notified of retries.
+    pub(crate) observer: Option<Arc<dyn RetryObserver>>,
+}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("strategies", &self.strategies)
+            .field("max_retry_after", &self.max_retry_after)
+            .field("policy", &"<policy>")
+            .field("deadline", &self.deadline)
+            .field("observer", &self.observer.as_ref().map(|_| "<observer>"))
+            .finish()
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            strategies: RetryConfig::uniform(strategy::default_backoff_with(
+                0,
+                strategy::DEFAULT_BASE,
+                strategy::DEFAULT_CAP,
+            )),
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
+            policy: Arc::new(DefaultRetryPolicy),
+            deadline: None,
+            observer: None,
+        }
+    }
+}
+
+impl Config {
+    /// Gets the strategy for an operation.
+    pub(crate) fn strategy(&self, operation: Operation) -> &Backoff {
+        match operation {
+            Operation::Read => &self.strategies.reads,
+            Operation::Create => &self.strategies.create,
+            Operation::Cancel => &self.strategies.cancel,
+        }
+    }
+
+    /// Gets the delay before a retry.
+    ///
+    /// `backoff` is the delay from the retry strategy. The second value
+    /// returned is whether the delay was requested by the service.
+    pub(crate) fn delay(&self, backoff: Duration, requested: Option<Duration>) -> (Duration, bool) {
+        match requested.map(|delay| delay.min(self.max_retry_after)) {
+            Some(requested) if requested > backoff => (requested, true),
+            _ => (backoff, false),
+        }
+    }
+}
+
+/// Gets the delay requested by the service for a response (if any).
+pub(crate) fn requested_delay(response: &Response) -> Option<Duration> {
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+            retry_after(response.headers(), Utc::now())
+        }
+        _ => None,
+    }
+}
+
+/// Parses the delay requested within a `Retry-After` header.
+///
+/// Both forms of the header (a number of seconds and an HTTP date) are
+/// supported. An HTTP date in the past is treated as no delay.
+pub(crate) fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+    Some((date - now).to_std().unwrap_or_default())
+}
+
+/// A measurement of a monotonic clock.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use tokio::time::Instant;
+/// A measurement of a monotonic clock.
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Waits for the provided duration.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await
+}
+
+/// Waits for the provided duration.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+    use crate::v1::client::Builder;
+    use crate::v1::client::tasks::View;
+    use crate::v1::client::test_server;
+    use crate::v1::client::test_server::Server;
+    use crate::v1::types::Task;
+
+    /// A policy that also retries `409 Conflict` responses.
+    struct RetryConflicts;
+
+    impl RetryPolicy for RetryConflicts {
+        fn classify(&self, outcome: &RequestOutcome<'_>) -> Retryability {
+            match outcome.status() {
+                Some(StatusCode::CONFLICT) => Retryability::Retry,
+                _ => DefaultRetryPolicy.classify(outcome),
+            }
+        }
+    }
+
+    /// A policy that retries every failed request.
+    struct RetryAll;
+
+    impl RetryPolicy for RetryAll {
+        fn classify(&self, outcome: &RequestOutcome<'_>) -> Retryability {
+            match outcome.status() {
+                Some(status) if status.is_success() => Retryability::Permanent,
+                _ => Retryability::Retry,
+            }
+        }
+    }
+
+    /// Gets the number of retries made before an error.
+    fn retries(err: crate::v1::client::Error) -> u32 {
+        match err.inner() {
+            crate::v1::client::Error::RetriesExhausted { retries, .. } => *retries,
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    /// An observer that collects the retries it is notified of.
+    #[derive(Default)]
+    struct Collect(std::sync::Mutex<Vec<(u32, Option<u16>, Duration)>>);
+
+    impl RetryObserver for Collect {
+        fn on_retry(&self, event: &RetryEvent<'_>) {
+            self.0.lock().unwrap().push((
+                event.attempt(),
+                event.outcome().status().map(|status| status.as_u16()),
+                event.delay(),
+            ));
+        }
+    }
+
+    /// Classifies a response with the provided status using the default
+    /// policy.
+    fn classify(status: u16, idempotent: bool) -> Retryability {
+        let response = http::Response::builder().status(status).body("").unwrap();
+        let result = Ok(Response::from(response));
+        DefaultRetryPolicy.classify(&RequestOutcome::new(&Method::GET, 1, idempotent, &result))
+    }
+
+    fn headers(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn default_policy() {
+        assert_eq!(classify(200, true), Retryability::Permanent);
+        assert_eq!(classify(400, true), Retryability::Permanent);
+        assert_eq!(classify(404, true), Retryability::Permanent);
+        assert_eq!(classify(408, true), Retryability::Retry);
+        assert_eq!(classify(409, true), Retryability::Permanent);
+        assert_eq!(classify(429, true), Retryability::Retry);
+        assert_eq!(classify(500, true), Retryability::Retry);
+        assert_eq!(classify(503, true), Retryability::Retry);
+
+        assert_eq!(classify(200, false), Retryability::Permanent);
+        assert_eq!(classify(429, false), Retryability::Permanent);
+        assert_eq!(classify(503, false), Retryability::Permanent);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn custom_policy() {
+        let server = Server::scripted(vec![
+            test_server::Response::new(409, ""),
+            test_server::Response::json(r#"{"id":"123"}"#),
+        ])
+        .await;
+
+        let client = Builder::default()
+            .url(server.url())
+            .retries(1)
+            .retry_policy(Arc::new(RetryConflicts))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        assert_eq!(client.create_task(Task::default()).await.unwrap().id, "123");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn observer() {
+        let server = Server::scripted(vec![
+            test_server::Response::new(503, ""),
+            test_server::Response::new(500, ""),
+            test_server::Response::json(r#"{"id":"123","state":"RUNNING"}"#),
+        ])
+        .await;
+
+        let observer = Arc::new(Collect::default());
+        let client = Builder::default()
+            .url(server.url())
+            .retry_strategy(strategy::default_backoff().without_jitter())
+            .retry_observer(observer.clone())
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        client.get_task("123", View::Minimal).await.unwrap();
+
+        assert_eq!(
+            *observer.0.lock().unwrap(),
+            [
+                (1, Some(503), Duration::from_secs(1)),
+                (2, Some(500), Duration::from_secs(2))
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn operations_use_their_own_strategies() {
+        let server = Server::start(|_| test_server::Response::new(503, "")).await;
+
+        let strategy = |retries| {
+            strategy::default_backoff_with(retries, strategy::DEFAULT_BASE, strategy::DEFAULT_CAP)
+        };
+
+        let client = Builder::default()
+            .url(server.url())
+            .retry_config(RetryConfig {
+                reads: strategy(2),
+                create: strategy(0),
+                cancel: strategy(1),
+            })
+            .retry_policy(Arc::new(RetryAll))
+            .pool_idle_timeout(None)
+            .try_build()
+            .unwrap();
+
+        let err = client.get_task("123", View::Minimal).await.unwrap_err();
+        assert_eq!(retries(err), 2);
+        let err = client.create_task(Task::default()).await.unwrap_err();
+        assert_eq!(retries(err), 0);
+        let err = client.cancel_task("123").await.unwrap_err();
+        assert_eq!(retries(err), 1);
+
+        let err = client
+            .with_retry_config(RetryConfig::uniform(strategy(4)))
+            .service_info()
+            .await
+            .unwrap_err();
+        assert_eq!(retries(err), 4);
+        assert_eq!(server.requests().len(), 3 + 1 + 2 + 5);
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(
+            retry_after(&headers("30"), Utc::now()),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(retry_after(&headers("soon"), Utc::now()), None);
+        assert_eq!(retry_after(&HeaderMap::new(), Utc::now()), None);
+    }
+
+    #[test]
+    fn retry_after_dates() {
+        let now = DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:30 GMT"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:27:00 GMT"), now),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn requested_delays() {
+        let config = Config {
+            max_retry_after: Duration::from_secs(10),
+            ..Default::default()
+        };
+
+        let backoff = Duration::from_secs(1);
+        assert_eq!(config.delay(backoff, None), (backoff, false));
+        assert_eq!(
+            config.delay(backoff, Some(Duration::from_secs(5))),
+            (Duration::from_secs(5), true)
+        );
+        assert_eq!(
+            config.delay(backoff, Some(Duration::from_secs(3600))),
+            (Duration::from_secs(10), true)
+        );
+        assert_eq!(
+            config.delay(Duration::from_secs(8), Some(Duration::from_secs(5))),
+            (Duration::from_secs(8), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_report_every_attempt() {
+        let server = Server::scripted(vec![
+            test_server::Response::json("{}").delay(Duration::from_secs(10)),
+            test_server::Response::new(503, ""),
+            test_server::Response::new(400, r#"{"message":"invalid view"}"#),
+        ])
+        .await;
+
+        let err = Builder::default()
+            .url(server.url())
+            .with_http_client(
+                reqwest::Client::builder()
+                    .timeout(Duration::from_millis(100))
+                    .build()
+                    .unwrap(),
+            )
+            .retry_strategy(
+                strategy::default_backoff_with(
+                    3,
+                    Duration::from_millis(10),
+                    Duration::from_millis(10),
+                )
+                .without_jitter(),
+            )
+            .try_build()
+            .unwrap()
+            .get_task("123", View::Minimal)
+            .await
+            .unwrap_err();
+
+        let attempts = err
+            .attempts()
+            .iter()
+            .map(|attempt| (attempt.attempt(), attempt.reason(), attempt.delay()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attempts,
+            [
+                (1, "timeout", Some(Duration::from_millis(10))),
+                (2, "503", Some(Duration::from_millis(10))),
+                (3, "400", None),
+            ]
+        );
+        assert_eq!(err.attempts()[2].status(), Some(StatusCode::BAD_REQUEST));
+
+        assert!(matches!(
+            err.inner(),
+            crate::v1::client::Error::Http {
+                status: StatusCode::BAD_REQUEST,
+                ..
+            }
+        ));
+        assert!(err.elapsed().unwrap() >= Duration::from_millis(120));
+        let message = err.to_string();
+        assert!(
+            message.starts_with("GET /tasks/123: failed after 3 attempts in "),
+            "{message}"
+        );
+        assert!(
+            message.ends_with(
+                r#"s (timeout, 503, 400): service responded with 400 Bad Request: {"message":"invalid view"}"#
+            ),
+            "{message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_failures_are_reported_for_every_attempt() {
+        // NOTE: the listener is dropped so that nothing accepts connections.
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let err = Builder::default()
+            .url_from_string(format!("http://{addr}/"))
+            .unwrap()
+            .retry_strategy(
+                strategy::default_backoff_with(
+                    1,
+                    Duration::from_millis(10),
+                    Duration::from_millis(10),
+                )
+                .without_jitter(),
+            )
+            .try_build()
+            .unwrap()
+            .service_info()
+            .await
+            .unwrap_err();
+
+        let reasons = err
+            .attempts()
+            .iter()
+            .map(Attempt::reason)
+            .collect::<Vec<_>>();
+        assert_eq!(reasons, ["connection refused", "connection refused"]);
+        assert!(err.is_connect());
+        let message = err.to_string();
+        assert!(
+            message.starts_with("GET /service-info: failed after 2 attempts in "),
+            "{message}"
+        );
+        assert!(
+            message.contains(
+                "s (connection refused, connection refused): failed to connect to `127.0.0.1`"
+            ),
+            "{message}"
+        );
+    }
+
+    #[tokio::test]
+    async fn requests_without_retries_report_no_attempts() {
+        let server = Server::scripted(vec![test_server::Response::new(400, "")]).await;
+        let err = Builder::default()
+            .url(server.url())
+            .try_build()
+            .unwrap()
+            .get_task("123", View::Minimal)
+            .await
+            .unwrap_err();
+
+        assert!(err.attempts().is_empty());
+        assert_eq!(
+            err.to_string(),
+            "GET /tasks/123: service responded with 400 Bad Request"
+        );
+    }
+}
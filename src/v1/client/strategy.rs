@@ -0,0 +1,223 @@
+//! Strategies for the delays between retries of a request.
+//!
+//! A strategy is an iterator of delays: each item is the delay before the next
+//! retry, and the number of items is the maximum number of retries. Strategies
+//! are installed with
+//! [`Builder::retry_strategy()`](super::Builder::retry_strategy).
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher as _;
+use std::hash::Hasher as _;
+use std::time::Duration;
+
+/// The default number of retries.
+pub const DEFAULT_ATTEMPTS: u32 = 3;
+
+/// The default delay before the first retry.
+pub const DEFAULT_BASE: Duration = Duration::from_secs(1);
+
+/// The default maximum delay between retries.
+pub const DEFAULT_CAP: Duration = Duration::from_secs(30);
+
+/// An exponential backoff with jitter.
+///
+/// The delay before the `n`th retry (starting at zero) is chosen uniformly at
+/// random between half of and the full value of `min(base * 2^n, cap)`. The
+/// randomness keeps many clients that fail at the same time from retrying in
+/// lockstep.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    /// The number of remaining retries.
+    remaining: u32,
+
+    /// The delay (before jitter) for the next retry.
+    next: Duration,
+
+    /// The maximum delay between retries.
+    cap: Duration,
+
+    /// Whether or not jitter is applied to the delays.
+    jitter: bool,
+
+    /// The state of the random number generator.
+    ///
+    /// This is zero until the first delay is generated so that each clone of
+    /// a strategy produces different delays.
+    state: u64,
+}
+
+impl Backoff {
+    /// Disables jitter, making the delays deterministic.
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = false;
+        self
+    }
+
+    /// Generates the next random number (using `xorshift64*`).
+    fn random(&mut self) -> u64 {
+        if self.state == 0 {
+            // NOTE: the state must never be zero.
+            self.state = random() | 1;
+        }
+
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+
+        let delay = self.next.min(self.cap);
+        self.next = self.next.saturating_mul(2);
+
+        if !self.jitter {
+            return Some(delay);
+        }
+
+        let half = delay / 2;
+        let range = (delay - half).as_nanos() as u64;
+        let jitter = match range {
+            0 => 0,
+            range => self.random() % (range + 1),
+        };
+
+        Some(half + Duration::from_nanos(jitter))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Backoff {}
+
+/// The strategies for retrying each kind of operation.
+///
+/// By default, every operation is retried using [`default_backoff()`]. Note
+/// that, regardless of its strategy, creating a task is only retried when the
+/// client fails to connect to the service (unless
+/// [idempotent submission](super::Builder::idempotency_tag) is enabled).
+#[derive(Clone, Debug)]
+pub struct RetryConfig {
+    /// The strategy for reads (e.g., getting or listing tasks and getting the
+    /// service information).
+    pub reads: Backoff,
+
+    /// The strategy for creating tasks.
+    pub create: Backoff,
+
+    /// The strategy for cancelling tasks.
+    pub cancel: Backoff,
+}
+
+impl RetryConfig {
+    /// Creates a configuration that uses the same strategy for every
+    /// operation.
+    pub fn uniform(strategy: Backoff) -> Self {
+        Self {
+            reads: strategy.clone(),
+            create: strategy.clone(),
+            cancel: strategy,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self::uniform(default_backoff())
+    }
+}
+
+/// Generates a random number.
+///
+/// The randomness comes from the random keys of the standard library's
+/// [`RandomState`], which is sufficient for jitter and unique identifiers but
+/// not for cryptographic purposes.
+pub(crate) fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    hasher.finish()
+}
+
+/// Gets the default strategy: three retries with a jittered exponential
+/// backoff starting at one second and capped at thirty seconds.
+pub fn default_backoff() -> Backoff {
+    default_backoff_with(DEFAULT_ATTEMPTS, DEFAULT_BASE, DEFAULT_CAP)
+}
+
+/// Gets a jittered exponential backoff with `attempts` retries, starting at
+/// `base` and capped at `cap`.
+pub fn default_backoff_with(attempts: u32, base: Duration, cap: Duration) -> Backoff {
+    Backoff {
+        remaining: attempts,
+        next: base,
+        cap,
+        jitter: true,
+        state: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_are_within_the_jitter_bounds() {
+        let base = Duration::from_millis(100);
+        let cap = Duration::from_millis(500);
+
+        for _ in 0..100 {
+            let delays = default_backoff_with(6, base, cap).collect::<Vec<_>>();
+            assert_eq!(delays.len(), 6);
+
+            for (i, delay) in delays.into_iter().enumerate() {
+                let full = (base * 2u32.pow(i as u32)).min(cap);
+                assert!(delay >= full / 2, "{delay:?} < {:?}", full / 2);
+                assert!(delay <= full, "{delay:?} > {full:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn delays_without_jitter() {
+        let delays = default_backoff_with(5, Duration::from_secs(1), Duration::from_secs(5))
+            .without_jitter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(delays, [1, 2, 4, 5, 5].map(Duration::from_secs).to_vec());
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let strategy = default_backoff_with(32, Duration::from_secs(1), Duration::from_secs(1));
+        let a = strategy.clone().collect::<Vec<_>>();
+        let b = strategy.collect::<Vec<_>>();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn delays_are_capped() {
+        assert_eq!(default_backoff().len(), 3);
+        assert_eq!(
+            default_backoff_with(0, DEFAULT_BASE, DEFAULT_CAP).next(),
+            None
+        );
+
+        // NOTE: the uncapped delay would overflow well before the last retry.
+        let last = default_backoff_with(100, DEFAULT_BASE, DEFAULT_CAP)
+            .last()
+            .unwrap();
+        assert!(last >= DEFAULT_CAP / 2 && last <= DEFAULT_CAP);
+    }
+}
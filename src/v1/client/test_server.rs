@@ -0,0 +1,283 @@
+//! A minimal, scripted HTTP server used for testing the client.
+
+// NOTE: not every helper is used with every combination of features.
+#![allow(dead_code)]
+
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use url::Url;
+
+/// A request received by the [`Server`].
+#[derive(Clone, Debug)]
+pub struct Request {
+    /// The HTTP method.
+    pub method: String,
+
+    /// The path, including the query string.
+    pub path: String,
+
+    /// The headers (with lowercased names).
+    pub headers: Vec<(String, String)>,
+
+    /// The body.
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Gets the value of a header by its lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response returned from the [`Server`].
+#[derive(Clone, Debug)]
+pub struct Response {
+    /// The status code.
+    pub status: u16,
+
+    /// Additional headers.
+    pub headers: Vec<(String, String)>,
+
+    /// The body.
+    pub body: Vec<u8>,
+
+    /// A delay before the response is written.
+    pub delay: Option<Duration>,
+}
+
+impl Response {
+    /// Creates a new response with the provided status and body.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Default::default(),
+            body: body.into(),
+            delay: None,
+        }
+    }
+
+    /// Creates a new `200 OK` response with a JSON body.
+    pub fn json(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, body).header("content-type", "application/json")
+    }
+
+    /// Adds a header to the response.
+    pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Delays writing the response.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// The handler called for each request.
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+/// A scripted HTTP server that records every request it receives.
+pub struct Server {
+    /// The base URL of the server.
+    url: Url,
+
+    /// The requests received by the server.
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl Server {
+    /// Starts a new server that responds to requests using `handler`.
+    pub async fn start(handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(_) => return,
+                };
+
+                tokio::spawn(serve(stream, handler.clone(), recorded.clone()));
+            }
+        });
+
+        Self {
+            url: format!("http://{addr}/").parse().unwrap(),
+            requests,
+        }
+    }
+
+    /// Starts a new server listening on the Unix domain socket at `path` that
+    /// responds to requests using `handler`.
+    ///
+    /// The base URL of the server is `http://localhost/`.
+    #[cfg(unix)]
+    pub async fn start_unix(
+        path: &Path,
+        handler: impl Fn(&Request) -> Response + Send + Sync + 'static,
+    ) -> Self {
+        let listener = tokio::net::UnixListener::bind(path).unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(_) => return,
+                };
+
+                tokio::spawn(serve(stream, handler.clone(), recorded.clone()));
+            }
+        });
+
+        Self {
+            url: "http://localhost/".parse().unwrap(),
+            requests,
+        }
+    }
+
+    /// Starts a new server that responds to requests in the order provided.
+    ///
+    /// Once all responses have been used, the last response is repeated.
+    pub async fn scripted(responses: Vec<Response>) -> Self {
+        let counter = Mutex::new(0usize);
+        Self::start(move |_| {
+            let mut count = counter.lock().unwrap();
+            let response = responses[(*count).min(responses.len() - 1)].clone();
+            *count += 1;
+            response
+        })
+        .await
+    }
+
+    /// Gets the base URL of the server.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// Gets the requests received so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Serves requests on a single connection.
+async fn serve(
+    mut stream: impl AsyncRead + AsyncWrite + Unpin,
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<Request>>>,
+) {
+    let mut buffer = Vec::new();
+
+    loop {
+        let request = match read_request(&mut stream, &mut buffer).await {
+            Some(request) => request,
+            None => return,
+        };
+
+        let response = handler(&request);
+        requests.lock().unwrap().push(request);
+
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let mut head = format!(
+            "HTTP/1.1 {} Status\r\ncontent-length: {}\r\n",
+            response.status,
+            response.body.len()
+        );
+
+        for (key, value) in &response.headers {
+            head.push_str(&format!("{key}: {value}\r\n"));
+        }
+
+        head.push_str("\r\n");
+
+        if stream.write_all(head.as_bytes()).await.is_err()
+            || stream.write_all(&response.body).await.is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Reads a single request from the connection.
+async fn read_request(
+    stream: &mut (impl AsyncRead + Unpin),
+    buffer: &mut Vec<u8>,
+) -> Option<Request> {
+    let end = loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+
+        let mut chunk = [0u8; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+
+        if read == 0 {
+            return None;
+        }
+
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..end]).to_string();
+    buffer.drain(..end + 4);
+
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    let length = headers
+        .iter()
+        .find(|(key, _)| key == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or_default();
+
+    while buffer.len() < length {
+        let mut chunk = [0u8; 4096];
+        let read = stream.read(&mut chunk).await.ok()?;
+
+        if read == 0 {
+            return None;
+        }
+
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let body = buffer.drain(..length).collect();
+
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,587 @@
+//! Waiting for tasks submitted through a [`Client`](super::Client) to finish.
+//!
+//! A task is waited on by polling its minimal view until it reaches a state
+//! in which it is no longer executing (see
+//! [`State::is_executing()`](crate::v1::types::task::State::is_executing)),
+//! after which the task is retrieved once more with the requested view. How
+//! often a task is polled is decided by a [`PollPolicy`].
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::v1::client::tasks::View;
+use crate::v1::types::task::State;
+use crate::v1::types::task::executor;
+
+/// The interval between polls by default.
+///
+/// This is the interval for states without an interval of their own within a
+/// [`PollPolicy`].
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The maximum interval between polls by default.
+pub const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The factor by which the interval between polls grows by default while the
+/// state of a task does not change.
+pub const DEFAULT_POLL_GROWTH: f64 = 1.5;
+
+/// How long to wait for a task to be canceled after its deadline by default.
+pub const DEFAULT_CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// The maximum number of requests in flight when waiting for multiple tasks by
+/// default.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// A policy for how often to poll a task based on its state.
+///
+/// Each state of a task may have its own interval between polls (e.g., a task
+/// may sit in the [`State::Queued`] state for hours, but should be polled more
+/// often once it is [`State::Running`]). While the state of a task does not
+/// change, the interval grows exponentially (by the
+/// [growth factor](Self::growth())) up to the
+/// [maximum interval](Self::max_interval()).
+///
+/// By default, a task is polled every 30 seconds while it is queued or paused,
+/// every 10 seconds while it is initializing, and every
+/// [`DEFAULT_POLL_INTERVAL`] otherwise, growing by [`DEFAULT_POLL_GROWTH`] up
+/// to [`DEFAULT_MAX_POLL_INTERVAL`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PollPolicy {
+    /// The intervals between polls of particular states.
+    intervals: BTreeMap<State, Duration>,
+
+    /// The interval between polls of the remaining states.
+    default: Duration,
+
+    /// The factor by which the interval grows while the state is unchanged.
+    growth: f64,
+
+    /// The maximum interval between polls.
+    max: Duration,
+}
+
+impl PollPolicy {
+    /// Creates a policy that polls every `interval` (regardless of the state
+    /// of the task).
+    pub fn fixed(interval: Duration) -> Self {
+        Self {
+            intervals: BTreeMap::new(),
+            default: interval,
+            growth: 1.0,
+            max: interval,
+        }
+    }
+
+    /// Sets the interval between polls while a task is in `state`.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set interval for `state`.
+    pub fn state_interval(mut self, state: State, interval: Duration) -> Self {
+        self.intervals.insert(state, interval);
+        self
+    }
+
+    /// Sets the interval between polls while a task is in a state without an
+    /// interval of its own.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set default interval.
+    pub fn default_interval(mut self, interval: Duration) -> Self {
+        self.default = interval;
+        self
+    }
+
+    /// Sets the factor by which the interval grows with each poll that
+    /// observes the same state.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set growth factor. Factors
+    /// below one (which would shrink the interval) are treated as one.
+    pub fn growth(mut self, factor: f64) -> Self {
+        self.growth = if factor >= 1.0 { factor } else { 1.0 };
+        self
+    }
+
+    /// Sets the maximum interval between polls.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set maximum interval. The
+    /// maximum also caps the intervals of particular states.
+    pub fn max_interval(mut self, interval: Duration) -> Self {
+        self.max = interval;
+        self
+    }
+
+    /// Gets the interval until the next poll of a task in `state` that was
+    /// observed in the same state by the `unchanged` previous polls.
+    pub fn interval(&self, state: State, unchanged: u32) -> Duration {
+        let base = self.intervals.get(&state).copied().unwrap_or(self.default);
+        let factor = self
+            .growth
+            .powi(i32::try_from(unchanged).unwrap_or(i32::MAX));
+
+        // NOTE: an interval that overflows is capped like any large interval.
+        Duration::try_from_secs_f64(base.as_secs_f64() * factor)
+            .unwrap_or(self.max)
+            .min(self.max)
+    }
+}
+
+impl Default for PollPolicy {
+    fn default() -> Self {
+        Self::fixed(DEFAULT_POLL_INTERVAL)
+            .state_interval(State::Queued, Duration::from_secs(30))
+            .state_interval(State::Initializing, Duration::from_secs(10))
+            .state_interval(State::Paused, Duration::from_secs(30))
+            .growth(DEFAULT_POLL_GROWTH)
+            .max_interval(DEFAULT_MAX_POLL_INTERVAL)
+    }
+}
+
+impl From<Duration> for PollPolicy {
+    fn from(interval: Duration) -> Self {
+        Self::fixed(interval)
+    }
+}
+
+/// A function that is called with the progress of a wait.
+type Progress = dyn Fn(&WaitProgress) + Send + Sync;
+
+/// Options for waiting for a task to finish.
+#[derive(Clone)]
+pub struct WaitOptions {
+    /// The policy for how often to poll.
+    pub(crate) poll: PollPolicy,
+
+    /// How long to wait before giving up (if ever).
+    pub(crate) timeout: Option<Duration>,
+
+    /// The view with which the finished task is retrieved.
+    pub(crate) view: View,
+
+    /// Whether to cancel the task when the timeout elapses.
+    pub(crate) cancel_on_timeout: bool,
+
+    /// The maximum number of requests in flight when waiting for multiple
+    /// tasks.
+    pub(crate) concurrency: usize,
+
+    /// How long to wait for a task to be canceled after its deadline.
+    pub(crate) cancel_grace_period: Duration,
+
+    /// The function that is called after every poll.
+    pub(crate) progress: Option<Arc<Progress>>,
+}
+
+impl WaitOptions {
+    /// Sets a fixed interval between polls.
+    ///
+    /// This is a shorthand for setting a [`PollPolicy::fixed()`] policy.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set interval or poll policy.
+    pub fn interval(self, value: Duration) -> Self {
+        self.poll_policy(PollPolicy::fixed(value))
+    }
+
+    /// Sets the policy for how often to poll.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set interval or poll policy. By
+    /// default, the [default](PollPolicy::default()) policy is used.
+    pub fn poll_policy(mut self, value: PollPolicy) -> Self {
+        self.poll = value;
+        self
+    }
+
+    /// Sets how long to wait for the task to finish before giving up.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set timeout. By default, there
+    /// is no timeout.
+    pub fn timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Sets the view with which the finished task is retrieved.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set view. By default, the
+    /// finished task is retrieved with the [`View::Full`] view (which includes
+    /// its logs). As the minimal view does not include a task, the
+    /// [`View::Minimal`] view retrieves the task with the [`View::Basic`] view.
+    pub fn view(mut self, value: View) -> Self {
+        self.view = match value {
+            View::Minimal => View::Basic,
+            view => view,
+        };
+        self
+    }
+
+    /// Sets whether to cancel the task when the timeout elapses.
+    ///
+    /// Canceling a task that has not finished in time ensures that it does not
+    /// keep using the resources of the service after the client has given up
+    /// on it. A failure to cancel the task is logged and the
+    /// [`Error::WaitTimeout`](super::Error::WaitTimeout) is still returned.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, the task is
+    /// not canceled. This has no effect without a
+    /// [timeout](Self::timeout()).
+    pub fn cancel_on_timeout(mut self, value: bool) -> Self {
+        self.cancel_on_timeout = value;
+        self
+    }
+
+    /// Sets the maximum number of requests in flight when waiting for
+    /// multiple tasks.
+    ///
+    /// See [`Client::wait_for_tasks()`](super::Client::wait_for_tasks).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set concurrency. By default, the
+    /// concurrency is [`DEFAULT_CONCURRENCY`]. A concurrency of zero is
+    /// treated as a concurrency of one.
+    pub fn concurrency(mut self, value: usize) -> Self {
+        self.concurrency = value.max(1);
+        self
+    }
+
+    /// Sets how long to wait for a task to be canceled after its deadline
+    /// passed.
+    ///
+    /// See [`Client::run_with_deadline()`](super::Client::run_with_deadline).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set grace period. By default,
+    /// the grace period is [`DEFAULT_CANCEL_GRACE_PERIOD`].
+    pub fn cancel_grace_period(mut self, value: Duration) -> Self {
+        self.cancel_grace_period = value;
+        self
+    }
+
+    /// Sets a function that is called with the progress of the wait after
+    /// every poll of a task.
+    ///
+    /// The function is called for every successful poll (not only when the
+    /// state of the task changes), so it can be used to keep a spinner or a
+    /// heartbeat alive:
+    ///
+    /// ```
+    /// use tes::v1::client::wait::WaitOptions;
+    ///
+    /// const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+    ///
+    /// let options = WaitOptions::default().on_progress(|progress| {
+    ///     let spinner = SPINNER[progress.polls as usize % SPINNER.len()];
+    ///
+    ///     // NOTE: with a progress bar crate, this would instead update the
+    ///     // message of a spinner.
+    ///     eprint!(
+    ///         "\r{spinner} task `{id}` is {state} ({elapsed}s elapsed, {since}s in this state)",
+    ///         id = progress.id,
+    ///         state = progress.state,
+    ///         elapsed = progress.elapsed.as_secs(),
+    ///         since = (progress.elapsed - progress.last_change).as_secs(),
+    ///     );
+    /// });
+    /// ```
+    ///
+    /// The function is called from within the wait, so it should return
+    /// quickly.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set function. By default, no
+    /// function is called.
+    pub fn on_progress<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&WaitProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(f));
+        self
+    }
+}
+
+impl std::fmt::Debug for WaitOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitOptions")
+            .field("poll", &self.poll)
+            .field("timeout", &self.timeout)
+            .field("view", &self.view)
+            .field("cancel_on_timeout", &self.cancel_on_timeout)
+            .field("concurrency", &self.concurrency)
+            .field("cancel_grace_period", &self.cancel_grace_period)
+            .field("progress", &self.progress.as_ref().map(|_| "<function>"))
+            .finish()
+    }
+}
+
+/// The progress of waiting for a task.
+///
+/// See [`WaitOptions::on_progress()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WaitProgress {
+    /// The ID of the task.
+    pub id: String,
+
+    /// The state of the task observed by the latest poll.
+    pub state: State,
+
+    /// The time elapsed since the wait started.
+    pub elapsed: Duration,
+
+    /// The number of times the task was polled (including the latest poll).
+    pub polls: u32,
+
+    /// The time elapsed since the wait started when the state of the task
+    /// was last observed to change (or when it was first observed).
+    pub last_change: Duration,
+}
+
+/// A change to the state of a watched task.
+///
+/// See [`Client::watch_task()`](super::Client::watch_task).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateChange {
+    /// The previous state of the task (or `None` if this is the first state
+    /// that was observed).
+    pub from: Option<State>,
+
+    /// The new state of the task.
+    pub to: State,
+
+    /// When the new state was observed by the client.
+    pub observed_at: DateTime<Utc>,
+}
+
+/// A standard stream of an executor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputStream {
+    /// The standard output stream.
+    Stdout,
+
+    /// The standard error stream.
+    Stderr,
+}
+
+/// A chunk of the logs of a tailed task.
+///
+/// See [`Client::tail_logs()`](super::Client::tail_logs).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LogChunk {
+    /// Output that was appended to a stream of an executor since the previous
+    /// poll.
+    Output {
+        /// The index of the executor.
+        executor: usize,
+
+        /// The stream of the executor.
+        stream: OutputStream,
+
+        /// The appended output.
+        text: String,
+    },
+
+    /// The service truncated or replaced the output of a stream of an
+    /// executor, so the output no longer starts with the previously yielded
+    /// output.
+    ///
+    /// Any output that the stream now contains follows as an
+    /// [`LogChunk::Output`].
+    Reset {
+        /// The index of the executor.
+        executor: usize,
+
+        /// The stream of the executor.
+        stream: OutputStream,
+    },
+}
+
+/// Tracks the output of the executors of a tailed task.
+#[derive(Debug, Default)]
+pub(crate) struct LogTail {
+    /// The output observed so far (the standard output and standard error
+    /// streams of each executor).
+    seen: Vec<[String; 2]>,
+}
+
+impl LogTail {
+    /// Updates the observed output with the logs of the executors, returning
+    /// the chunks of output that are new.
+    pub(crate) fn update(&mut self, logs: &[executor::Log]) -> Vec<LogChunk> {
+        let mut chunks = Vec::new();
+
+        // NOTE: the logs of executors that disappeared are reset.
+        for (executor, seen) in self.seen.iter().enumerate().skip(logs.len()) {
+            for (stream, seen) in [OutputStream::Stdout, OutputStream::Stderr]
+                .into_iter()
+                .zip(seen)
+            {
+                if !seen.is_empty() {
+                    chunks.push(LogChunk::Reset { executor, stream });
+                }
+            }
+        }
+
+        self.seen.resize_with(logs.len(), Default::default);
+
+        for (executor, (log, seen)) in logs.iter().zip(&mut self.seen).enumerate() {
+            let [stdout, stderr] = seen;
+
+            for (stream, text, seen) in [
+                (OutputStream::Stdout, &log.stdout, stdout),
+                (OutputStream::Stderr, &log.stderr, stderr),
+            ] {
+                let text = text.as_deref().unwrap_or_default();
+
+                let appended = match text.strip_prefix(seen.as_str()) {
+                    Some(appended) => appended,
+                    None => {
+                        chunks.push(LogChunk::Reset { executor, stream });
+                        text
+                    }
+                };
+
+                if !appended.is_empty() {
+                    chunks.push(LogChunk::Output {
+                        executor,
+                        stream,
+                        text: appended.to_string(),
+                    });
+                }
+
+                text.clone_into(seen);
+            }
+        }
+
+        chunks
+    }
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            poll: PollPolicy::default(),
+            timeout: None,
+            view: View::Full,
+            cancel_on_timeout: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            cancel_grace_period: DEFAULT_CANCEL_GRACE_PERIOD,
+            progress: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates the logs of an executor.
+    fn log(stdout: &str, stderr: Option<&str>) -> executor::Log {
+        executor::Log {
+            stdout: Some(stdout.to_string()),
+            stderr: stderr.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an output chunk.
+    fn output(executor: usize, stream: OutputStream, text: &str) -> LogChunk {
+        LogChunk::Output {
+            executor,
+            stream,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn poll_policies() {
+        let policy = PollPolicy::default();
+        assert_eq!(policy.interval(State::Queued, 0), Duration::from_secs(30));
+        assert_eq!(policy.interval(State::Queued, 1), Duration::from_secs(45));
+        assert_eq!(policy.interval(State::Queued, 2), DEFAULT_MAX_POLL_INTERVAL);
+        assert_eq!(policy.interval(State::Running, 0), DEFAULT_POLL_INTERVAL);
+        assert_eq!(
+            policy.interval(State::Running, 2),
+            Duration::from_millis(11_250)
+        );
+        assert_eq!(
+            policy.interval(State::Running, u32::MAX),
+            DEFAULT_MAX_POLL_INTERVAL
+        );
+
+        let policy = PollPolicy::fixed(Duration::from_secs(2)).growth(0.5);
+        assert_eq!(policy.interval(State::Queued, 0), Duration::from_secs(2));
+        assert_eq!(policy.interval(State::Running, 10), Duration::from_secs(2));
+
+        let policy = PollPolicy::from(Duration::from_secs(1))
+            .state_interval(State::Queued, Duration::from_secs(120))
+            .growth(f64::INFINITY)
+            .max_interval(Duration::from_secs(90));
+        assert_eq!(policy.interval(State::Queued, 0), Duration::from_secs(90));
+        assert_eq!(policy.interval(State::Running, 0), Duration::from_secs(1));
+        assert_eq!(policy.interval(State::Running, 1), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn log_tails() {
+        use OutputStream::*;
+
+        let mut tail = LogTail::default();
+        assert_eq!(tail.update(&[]), []);
+        assert_eq!(
+            tail.update(&[log("hello", None)]),
+            [output(0, Stdout, "hello")]
+        );
+        assert_eq!(tail.update(&[log("hello", None)]), []);
+        assert_eq!(
+            tail.update(&[log("hello, world", Some("oops")), log("", None)]),
+            [output(0, Stdout, ", world"), output(0, Stderr, "oops")]
+        );
+
+        // NOTE: truncated output is reset and replaced.
+        assert_eq!(
+            tail.update(&[log("world", Some("oops")), log("next", None)]),
+            [
+                LogChunk::Reset {
+                    executor: 0,
+                    stream: Stdout
+                },
+                output(0, Stdout, "world"),
+                output(1, Stdout, "next"),
+            ]
+        );
+        assert_eq!(
+            tail.update(&[log("world", Some(""))]),
+            [
+                LogChunk::Reset {
+                    executor: 1,
+                    stream: Stdout
+                },
+                LogChunk::Reset {
+                    executor: 0,
+                    stream: Stderr
+                },
+            ]
+        );
+    }
+}
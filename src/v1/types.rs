@@ -1,6 +1,9 @@
 //! Types within v1.x of the specification.
 
+pub mod requests;
 pub mod responses;
+#[cfg(all(feature = "serde", not(feature = "ord")))]
+mod sorted;
 pub mod task;
 
 pub use task::Task;
@@ -0,0 +1,679 @@
+//! Parameters of requests to a service.
+
+use std::borrow::Borrow;
+
+use url::form_urlencoded;
+
+use crate::v1::types::Task;
+use crate::v1::types::task::State;
+
+pub mod builder;
+
+/// The maximum (inclusive) number of tasks within a page of results.
+///
+/// The specification requires the page size to be less than `2048`.
+pub const MAX_PAGE_SIZE: u16 = 2047;
+
+/// The number of tasks within a page of results when no page size is
+/// requested (as defined by the specification).
+pub const DEFAULT_PAGE_SIZE: u16 = 256;
+
+/// An error related to the parameters of a request.
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The page size is not within `1..=MAX_PAGE_SIZE`.
+    InvalidPageSize(u16),
+
+    /// A page token is empty.
+    EmptyPageToken,
+
+    /// A parameter within a query string has an invalid value.
+    InvalidParameter {
+        /// The name of the parameter.
+        name: &'static str,
+
+        /// The value of the parameter.
+        value: String,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidPageSize(size) => write!(
+                f,
+                "invalid page size of {size}: the page size must be between 1 and {MAX_PAGE_SIZE} \
+                 (inclusive)"
+            ),
+            Error::EmptyPageToken => write!(f, "page tokens cannot be empty"),
+            Error::InvalidParameter { name, value } => {
+                write!(f, "invalid value `{value}` for the `{name}` parameter")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The number of tasks within a page of results of `GET /tasks`.
+///
+/// A page size is always within `1..=`[`MAX_PAGE_SIZE`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "u16", into = "u16"))]
+#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
+pub struct PageSize(u16);
+
+impl PageSize {
+    /// Creates a new page size.
+    ///
+    /// # Errors
+    ///
+    /// The page size must be within `1..=`[`MAX_PAGE_SIZE`].
+    pub fn new(size: u16) -> Result<Self, Error> {
+        if !(1..=MAX_PAGE_SIZE).contains(&size) {
+            return Err(Error::InvalidPageSize(size));
+        }
+
+        Ok(Self(size))
+    }
+
+    /// Gets the page size.
+    pub fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+impl Default for PageSize {
+    fn default() -> Self {
+        Self(DEFAULT_PAGE_SIZE)
+    }
+}
+
+impl TryFrom<u16> for PageSize {
+    type Error = Error;
+
+    fn try_from(size: u16) -> Result<Self, Self::Error> {
+        Self::new(size)
+    }
+}
+
+impl From<PageSize> for u16 {
+    fn from(size: PageSize) -> Self {
+        size.0
+    }
+}
+
+impl std::fmt::Display for PageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An argument that affects which fields are returned on certain task-related
+/// endpoints.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
+pub enum View {
+    /// Only includes the `id` and `state` fields in the returned task.
+    #[default]
+    Minimal,
+
+    /// Includes all available fields except:
+    ///
+    /// * Logs for stdout (`tesTask.ExecutorLog.stdout`).
+    /// * Logs for stderr (`tesTask.ExecutorLog.stderr`).
+    /// * The content of inputs (`tesInput.content`).
+    /// * The system logs (`tesTaskLog.system_logs`).
+    Basic,
+
+    /// Includes all fields.
+    Full,
+}
+
+impl std::fmt::Display for View {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            View::Minimal => write!(f, "MINIMAL"),
+            View::Basic => write!(f, "BASIC"),
+            View::Full => write!(f, "FULL"),
+        }
+    }
+}
+
+/// An opaque token for a page of results of `GET /tasks`.
+///
+/// Page tokens are never empty: services that respond with an empty next page
+/// token (instead of none) have no more pages. A page token can be stored
+/// (e.g., as its [`Display`](std::fmt::Display) form) and later parsed with
+/// [`FromStr`](std::str::FromStr) to resume listing.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
+pub struct PageToken(String);
+
+impl PageToken {
+    /// Creates a new page token.
+    ///
+    /// This is `None` if the token is empty.
+    pub fn new(token: impl Into<String>) -> Option<Self> {
+        let token = token.into();
+        (!token.is_empty()).then_some(Self(token))
+    }
+
+    /// Gets the page token as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for PageToken {
+    type Error = Error;
+
+    fn try_from(token: String) -> Result<Self, Self::Error> {
+        Self::new(token).ok_or(Error::EmptyPageToken)
+    }
+}
+
+impl From<PageToken> for String {
+    fn from(token: PageToken) -> Self {
+        token.0
+    }
+}
+
+impl std::str::FromStr for PageToken {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_string())
+    }
+}
+
+impl std::fmt::Display for PageToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// The parameters of `GET /tasks`.
+///
+/// Tags are filtered by the tag key and the tag value at the same position:
+/// a task matches if it has a tag with the key and the value (or any value, if
+/// the tag value is empty). [`ListTasksParams::builder()`] keeps the tag keys
+/// and the tag values paired.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
+pub struct ListTasksParams {
+    /// Only lists tasks with names that start with this prefix.
+    pub name_prefix: Option<String>,
+
+    /// Only lists tasks in this state.
+    pub state: Option<State>,
+
+    /// The keys of the tags that listed tasks must have.
+    pub tag_keys: Vec<String>,
+
+    /// The values of the tags that listed tasks must have.
+    ///
+    /// There must not be more tag values than tag keys.
+    pub tag_values: Vec<String>,
+
+    /// The number of tasks within a page of results.
+    pub page_size: Option<PageSize>,
+
+    /// The token of the page of results to list.
+    pub page_token: Option<PageToken>,
+
+    /// The fields to include in the listed tasks.
+    pub view: Option<View>,
+}
+
+impl ListTasksParams {
+    /// Gets an empty builder for [`ListTasksParams`].
+    pub fn builder() -> builder::Builder {
+        builder::Builder::default()
+    }
+
+    /// Returns whether a task matches the filters of these parameters.
+    ///
+    /// As in the specification, a task matches if:
+    ///
+    /// * its name starts with the name prefix (if there is one),
+    /// * it is in the state (if there is one), and
+    /// * it has a tag with every tag key and the tag value at the same position
+    ///   (or any value, if that tag value is empty or missing).
+    ///
+    /// The page size, the page token, and the view do not affect which tasks
+    /// match.
+    pub fn matches(&self, task: &Task) -> bool {
+        if let Some(prefix) = &self.name_prefix {
+            if !task
+                .name
+                .as_deref()
+                .unwrap_or_default()
+                .starts_with(prefix.as_str())
+            {
+                return false;
+            }
+        }
+
+        if self.state.is_some() && task.state != self.state {
+            return false;
+        }
+
+        self.tag_keys.iter().enumerate().all(|(i, key)| {
+            let value = task.tags.as_ref().and_then(|tags| tags.get(key));
+
+            match self.tag_values.get(i).filter(|value| !value.is_empty()) {
+                Some(expected) => value == Some(expected),
+                None => value.is_some(),
+            }
+        })
+    }
+
+    /// Filters tasks (owned or borrowed) to those that match these
+    /// parameters.
+    ///
+    /// See [`Self::matches()`].
+    pub fn filter_tasks<T>(&self, tasks: impl IntoIterator<Item = T>) -> Vec<T>
+    where
+        T: Borrow<Task>,
+    {
+        tasks
+            .into_iter()
+            .filter(|task| self.matches(task.borrow()))
+            .collect()
+    }
+
+    /// Parses the parameters from the query string of a request to `GET
+    /// /tasks` (with or without the leading `?`).
+    ///
+    /// The tag keys and the tag values are collected from repeated
+    /// `tag_key` and `tag_value` parameters (in order), views and states are
+    /// matched case-insensitively, and unknown parameters are ignored. If a
+    /// parameter other than the tags is repeated, the last value is used.
+    ///
+    /// # Errors
+    ///
+    /// The page size must be a valid [`PageSize`], and the state and the view
+    /// must be known.
+    pub fn from_query(query: &str) -> Result<Self, Error> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let mut params = Self::default();
+
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            match name.as_ref() {
+                "name_prefix" => params.name_prefix = Some(value.into_owned()),
+                "state" => params.state = Some(parse_state(&value)?),
+                "tag_key" => params.tag_keys.push(value.into_owned()),
+                "tag_value" => params.tag_values.push(value.into_owned()),
+                "page_size" => {
+                    let size = value.parse::<u16>().map_err(|_| Error::InvalidParameter {
+                        name: "page_size",
+                        value: value.to_string(),
+                    })?;
+
+                    params.page_size = Some(PageSize::new(size)?);
+                }
+                "page_token" => params.page_token = PageToken::new(value),
+                "view" => params.view = Some(parse_view(&value)?),
+                _ => {}
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Gets the query string of a request to `GET /tasks` with these
+    /// parameters (without the leading `?`).
+    ///
+    /// The tags are sent as repeated pairs of `tag_key` and `tag_value`
+    /// parameters, with an empty tag value for every tag key without one.
+    pub fn to_query(&self) -> String {
+        self.query_with_view(self.view.as_ref())
+    }
+
+    /// Gets the query string of a request to `GET /tasks` with these
+    /// parameters and the provided view (instead of [`Self::view`]).
+    pub(crate) fn query_with_view(&self, view: Option<&View>) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+
+        if let Some(prefix) = &self.name_prefix {
+            query.append_pair("name_prefix", prefix);
+        }
+
+        if let Some(state) = &self.state {
+            query.append_pair("state", &state.to_string());
+        }
+
+        // NOTE: some services require a tag value for every tag key, so
+        // missing tag values are sent as empty values (which match any value).
+        for (i, key) in self.tag_keys.iter().enumerate() {
+            query.append_pair("tag_key", key);
+            query.append_pair(
+                "tag_value",
+                self.tag_values
+                    .get(i)
+                    .map(String::as_str)
+                    .unwrap_or_default(),
+            );
+        }
+
+        if let Some(size) = &self.page_size {
+            query.append_pair("page_size", &size.to_string());
+        }
+
+        if let Some(token) = &self.page_token {
+            query.append_pair("page_token", token.as_str());
+        }
+
+        if let Some(view) = view {
+            query.append_pair("view", &view.to_string());
+        }
+
+        query.finish()
+    }
+}
+
+/// Parses a state (case-insensitively).
+fn parse_state(value: &str) -> Result<State, Error> {
+    const STATES: &[State] = &[
+        State::Unknown,
+        State::Queued,
+        State::Initializing,
+        State::Running,
+        State::Paused,
+        State::Canceling,
+        State::Complete,
+        State::ExecutorError,
+        State::SystemError,
+        State::Canceled,
+        State::Preempted,
+    ];
+
+    STATES
+        .iter()
+        .find(|state| state.to_string().eq_ignore_ascii_case(value))
+        .copied()
+        .ok_or_else(|| Error::InvalidParameter {
+            name: "state",
+            value: value.to_string(),
+        })
+}
+
+/// Parses a view (case-insensitively).
+fn parse_view(value: &str) -> Result<View, Error> {
+    [View::Minimal, View::Basic, View::Full]
+        .into_iter()
+        .find(|view| view.to_string().eq_ignore_ascii_case(value))
+        .ok_or_else(|| Error::InvalidParameter {
+            name: "view",
+            value: value.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_sizes() {
+        assert_eq!(PageSize::new(0), Err(Error::InvalidPageSize(0)));
+        assert_eq!(PageSize::new(1).unwrap().get(), 1);
+        assert_eq!(PageSize::new(2047).unwrap().get(), 2047);
+        assert_eq!(PageSize::new(2048), Err(Error::InvalidPageSize(2048)));
+        assert_eq!(PageSize::default().get(), 256);
+
+        assert_eq!(
+            PageSize::new(2048).unwrap_err().to_string(),
+            "invalid page size of 2048: the page size must be between 1 and 2047 (inclusive)"
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let task = |name: Option<&str>, state: State, tags: &[(&str, &str)]| Task {
+            name: name.map(String::from),
+            state: Some(state),
+            tags: Some(
+                tags.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+
+        let tasks = [
+            task(Some("align-1"), State::Running, &[("project", "tes")]),
+            task(Some("align-2"), State::Complete, &[("project", "")]),
+            task(Some("call-1"), State::Running, &[("owner", "a")]),
+            task(None, State::Queued, &[]),
+        ];
+
+        let cases: &[(&str, &[usize])] = &[
+            ("", &[0, 1, 2, 3]),
+            ("name_prefix=align", &[0, 1]),
+            ("name_prefix=", &[0, 1, 2, 3]),
+            ("name_prefix=align-1x", &[]),
+            ("state=RUNNING", &[0, 2]),
+            ("state=CANCELED", &[]),
+            ("tag_key=project", &[0, 1]),
+            ("tag_key=project&tag_value=", &[0, 1]),
+            ("tag_key=project&tag_value=tes", &[0]),
+            ("tag_key=project&tag_value=TES", &[]),
+            ("tag_key=project&tag_value=tes&tag_key=owner", &[]),
+            ("tag_key=missing", &[]),
+            ("name_prefix=align&state=RUNNING&tag_key=project", &[0]),
+            ("name_prefix=a&state=RUNNING&page_size=1&view=FULL", &[0]),
+        ];
+
+        for (query, expected) in cases {
+            let params = ListTasksParams::from_query(query).unwrap();
+            let matched = params
+                .filter_tasks(&tasks)
+                .into_iter()
+                .map(|task| tasks.iter().position(|t| std::ptr::eq(t, task)).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(matched, *expected, "{query}");
+        }
+
+        let params = ListTasksParams::from_query("state=QUEUED").unwrap();
+        assert_eq!(params.filter_tasks(tasks.clone()), [tasks[3].clone()]);
+    }
+
+    #[test]
+    fn states_and_views_in_queries() {
+        let states = [
+            (State::Unknown, "UNKNOWN"),
+            (State::Queued, "QUEUED"),
+            (State::Initializing, "INITIALIZING"),
+            (State::Running, "RUNNING"),
+            (State::Paused, "PAUSED"),
+            (State::Canceling, "CANCELING"),
+            (State::Complete, "COMPLETE"),
+            (State::ExecutorError, "EXECUTOR_ERROR"),
+            (State::SystemError, "SYSTEM_ERROR"),
+            (State::Canceled, "CANCELED"),
+            (State::Preempted, "PREEMPTED"),
+        ];
+
+        for (state, expected) in states {
+            let params = ListTasksParams::builder().state(state).build();
+            assert_eq!(params.to_query(), format!("state={expected}"));
+            assert_eq!(parse_state(&expected.to_lowercase()).unwrap(), state);
+
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                serde_json::to_string(&state).unwrap(),
+                format!("\"{expected}\"")
+            );
+        }
+
+        let views = [
+            (View::Minimal, "MINIMAL"),
+            (View::Basic, "BASIC"),
+            (View::Full, "FULL"),
+        ];
+
+        for (view, expected) in views {
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                serde_json::to_string(&view).unwrap(),
+                format!("\"{expected}\"")
+            );
+
+            let params = ListTasksParams::builder().view(view).build();
+            assert_eq!(params.to_query(), format!("view={expected}"));
+        }
+    }
+
+    #[test]
+    fn page_tokens() {
+        assert_eq!(PageToken::new(""), None);
+        assert_eq!("".parse::<PageToken>(), Err(Error::EmptyPageToken));
+
+        let token = PageToken::new("CgwI/+eZBhDAtoHgAQ==").unwrap();
+        assert_eq!(token.to_string().parse::<PageToken>(), Ok(token.clone()));
+        assert_eq!(String::from(token), "CgwI/+eZBhDAtoHgAQ==");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn page_tokens_are_serialized_as_strings() {
+        let token = PageToken::new("abc").unwrap();
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(json, r#""abc""#);
+        assert_eq!(serde_json::from_str::<PageToken>(&json).unwrap(), token);
+
+        let err = serde_json::from_str::<PageToken>("\"\"").unwrap_err();
+        assert_eq!(err.to_string(), "page tokens cannot be empty");
+    }
+
+    #[test]
+    fn queries_are_parsed() {
+        let params = ListTasksParams::from_query(
+            "?name_prefix=my+task&state=executor_error&tag_key=project&tag_key=owner&\
+             tag_value=tes&tag_value=&page_size=100&page_token=abc%2Fdef&view=Basic&unknown=1",
+        )
+        .unwrap();
+
+        assert_eq!(
+            params,
+            ListTasksParams::builder()
+                .name_prefix("my task")
+                .state(State::ExecutorError)
+                .tag("project", "tes")
+                .tag_exists("owner")
+                .page_size(PageSize::new(100).unwrap())
+                .page_token(PageToken::new("abc/def").unwrap())
+                .view(View::Basic)
+                .build()
+        );
+
+        // NOTE: an empty page token is the same as no page token.
+        assert_eq!(
+            ListTasksParams::from_query("page_token="),
+            Ok(Default::default())
+        );
+
+        assert_eq!(ListTasksParams::from_query(""), Ok(Default::default()));
+        assert_eq!(
+            ListTasksParams::from_query("page_size=0"),
+            Err(Error::InvalidPageSize(0))
+        );
+        assert_eq!(
+            ListTasksParams::from_query("page_size=many"),
+            Err(Error::InvalidParameter {
+                name: "page_size",
+                value: String::from("many"),
+            })
+        );
+        assert_eq!(
+            ListTasksParams::from_query("view=EVERYTHING")
+                .unwrap_err()
+                .to_string(),
+            "invalid value `EVERYTHING` for the `view` parameter"
+        );
+        assert!(ListTasksParams::from_query("state=DONE").is_err());
+    }
+
+    #[test]
+    fn queries_round_trip() {
+        let prefixes = [None, Some("a"), Some("a b&c=d/é")];
+        let states = [None, Some(State::Unknown), Some(State::SystemError)];
+        let tags: &[&[(&str, &str)]] = &[&[], &[("a", "")], &[("a", "1"), ("a", "2"), ("+", "%")]];
+        let page_sizes = [None, Some(1), Some(2047)];
+        let tokens = [None, Some("abc=="), Some("?page_token=x")];
+        let views = [None, Some(View::Minimal), Some(View::Full)];
+
+        for prefix in prefixes {
+            for state in states {
+                for tags in tags {
+                    for page_size in page_sizes {
+                        for token in tokens {
+                            for view in &views {
+                                let mut builder = ListTasksParams::builder();
+
+                                if let Some(prefix) = prefix {
+                                    builder = builder.name_prefix(prefix);
+                                }
+
+                                if let Some(state) = state {
+                                    builder = builder.state(state);
+                                }
+
+                                for (key, value) in tags.iter() {
+                                    builder = builder.tag(*key, *value);
+                                }
+
+                                if let Some(size) = page_size {
+                                    builder = builder.page_size(PageSize::new(size).unwrap());
+                                }
+
+                                if let Some(token) = token {
+                                    builder = builder.page_token(PageToken::new(token).unwrap());
+                                }
+
+                                if let Some(view) = view {
+                                    builder = builder.view(view.clone());
+                                }
+
+                                let params = builder.build();
+                                let query = params.to_query();
+                                assert_eq!(
+                                    ListTasksParams::from_query(&query).as_ref(),
+                                    Ok(&params),
+                                    "{query}"
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn page_sizes_are_validated_when_deserialized() {
+        let size: PageSize = serde_json::from_str("2047").unwrap();
+        assert_eq!(size.get(), 2047);
+        assert_eq!(serde_json::to_string(&size).unwrap(), "2047");
+
+        let err = serde_json::from_str::<PageSize>("0").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid page size of 0: the page size must be between 1 and 2047 (inclusive)"
+        );
+        assert!(serde_json::from_str::<PageSize>("2048").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Builders for the parameters of requests.
+
+use crate::v1::types::requests::ListTasksParams;
+use crate::v1::types::requests::PageSize;
+use crate::v1::types::requests::PageToken;
+use crate::v1::types::requests::View;
+use crate::v1::types::task::State;
+
+/// A builder for [`ListTasksParams`].
+///
+/// Tag filters are added as pairs of a tag key and a tag value, so there are
+/// never more tag values than tag keys (which the specification forbids).
+#[derive(Debug, Default)]
+pub struct Builder {
+    /// The prefix of the names of listed tasks.
+    name_prefix: Option<String>,
+
+    /// The state of listed tasks.
+    state: Option<State>,
+
+    /// The tag filters (in the order they were added).
+    ///
+    /// An empty value matches any value of the tag.
+    tags: Vec<(String, String)>,
+
+    /// The number of tasks within a page of results.
+    page_size: Option<PageSize>,
+
+    /// The token of the page of results to list.
+    page_token: Option<PageToken>,
+
+    /// The fields to include in the listed tasks.
+    view: Option<View>,
+}
+
+impl Builder {
+    /// Sets the prefix of the names of listed tasks.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set name prefix.
+    pub fn name_prefix(mut self, value: impl Into<String>) -> Self {
+        self.name_prefix = Some(value.into());
+        self
+    }
+
+    /// Sets the state of listed tasks.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set state.
+    pub fn state(mut self, value: State) -> Self {
+        self.state = Some(value);
+        self
+    }
+
+    /// Only lists tasks with a tag that has the provided key and value.
+    ///
+    /// # Notes
+    ///
+    /// Tag filters accumulate: listed tasks must match every tag filter. An
+    /// empty value matches any value (see [`Self::tag_exists()`]).
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+
+    /// Only lists tasks with a tag that has the provided key (with any value).
+    ///
+    /// # Notes
+    ///
+    /// Tag filters accumulate: listed tasks must match every tag filter.
+    pub fn tag_exists(self, key: impl Into<String>) -> Self {
+        self.tag(key, String::new())
+    }
+
+    /// Sets the number of tasks within a page of results.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set page size.
+    pub fn page_size(mut self, value: PageSize) -> Self {
+        self.page_size = Some(value);
+        self
+    }
+
+    /// Sets the token of the page of results to list.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set page token.
+    pub fn page_token(mut self, value: PageToken) -> Self {
+        self.page_token = Some(value);
+        self
+    }
+
+    /// Sets the fields to include in the listed tasks.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set view.
+    pub fn view(mut self, value: View) -> Self {
+        self.view = Some(value);
+        self
+    }
+
+    /// Consumes `self` to build the [`ListTasksParams`].
+    ///
+    /// Every tag key is paired with a tag value (an empty value for
+    /// [`Self::tag_exists()`]), so the tag keys and the tag values always have
+    /// the same length.
+    pub fn build(self) -> ListTasksParams {
+        let (tag_keys, tag_values) = self.tags.into_iter().unzip();
+
+        ListTasksParams {
+            name_prefix: self.name_prefix,
+            state: self.state,
+            tag_keys,
+            tag_values,
+            page_size: self.page_size,
+            page_token: self.page_token,
+            view: self.view,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries() {
+        assert_eq!(ListTasksParams::builder().build().to_query(), "");
+
+        let params = ListTasksParams::builder()
+            .name_prefix("my task")
+            .state(State::ExecutorError)
+            .tag("project", "tes")
+            .tag_exists("owner")
+            .tag("env", "a&b=c")
+            .page_size(PageSize::new(100).unwrap())
+            .page_token(PageToken::new("abc/def").unwrap())
+            .view(View::Basic)
+            .build();
+
+        assert_eq!(params.tag_keys, ["project", "owner", "env"]);
+        assert_eq!(params.tag_values, ["tes", "", "a&b=c"]);
+        assert_eq!(
+            params.to_query(),
+            "name_prefix=my+task&state=EXECUTOR_ERROR&tag_key=project&tag_value=tes&tag_key=owner&\
+             tag_value=&tag_key=env&tag_value=a%26b%3Dc&page_size=100&page_token=abc%2Fdef&\
+             view=BASIC"
+        );
+    }
+}
@@ -5,6 +5,8 @@ pub mod task;
 
 pub use service_info::ServiceInfo;
 
+use crate::v1::types::requests::PageToken;
+
 /// A response from `POST /tasks`.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -17,11 +19,133 @@ pub struct CreateTask {
 /// The response from `GET /tasks`.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "Task: serde::Deserialize<'de>"))
+)]
 #[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
 pub struct ListTasks<Task> {
     /// The tasks in this page of results.
+    ///
+    /// Some services omit the tasks (or respond with `null`) when there are
+    /// no tasks, so both are deserialized as no tasks.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "null_as_default")
+    )]
     pub tasks: Vec<Task>,
 
     /// The token for the next page of results.
-    pub next_page_token: Option<String>,
+    ///
+    /// An empty token (which some services respond with when there are no
+    /// more pages) is deserialized as no token.
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "empty_as_none"))]
+    pub next_page_token: Option<PageToken>,
+}
+
+impl<Task> ListTasks<Task> {
+    /// Gets the number of tasks in this page of results.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns whether there are no tasks in this page of results.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Converts the tasks in this page of results (keeping the token for the
+    /// next page of results).
+    pub fn map_tasks<U>(self, f: impl FnMut(Task) -> U) -> ListTasks<U> {
+        ListTasks {
+            tasks: self.tasks.into_iter().map(f).collect(),
+            next_page_token: self.next_page_token,
+        }
+    }
+}
+
+impl<Task> IntoIterator for ListTasks<Task> {
+    type IntoIter = std::vec::IntoIter<Task>;
+    type Item = Task;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.into_iter()
+    }
+}
+
+impl<'a, Task> IntoIterator for &'a ListTasks<Task> {
+    type IntoIter = std::slice::Iter<'a, Task>;
+    type Item = &'a Task;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.iter()
+    }
+}
+
+/// Deserializes a `null` value as the default value.
+#[cfg(feature = "serde")]
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de> + Default,
+{
+    use serde::Deserialize as _;
+
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Deserializes an (optional) page token, treating an empty token as no
+/// token.
+#[cfg(feature = "serde")]
+fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<PageToken>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize as _;
+
+    Ok(Option::<String>::deserialize(deserializer)?.and_then(PageToken::new))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use crate::v1::types::responses::task::MinimalTask;
+
+    #[test]
+    fn mapping_tasks() {
+        let results = ListTasks {
+            tasks: vec![1, 2, 3],
+            next_page_token: PageToken::new("abc"),
+        };
+
+        assert_eq!(results.len(), 3);
+        assert!(!results.is_empty());
+        assert_eq!((&results).into_iter().sum::<i32>(), 6);
+
+        let results = results.map_tasks(|task| task.to_string());
+        assert_eq!(results.tasks, ["1", "2", "3"]);
+        assert_eq!(results.next_page_token, PageToken::new("abc"));
+        assert_eq!(results.into_iter().collect::<String>(), "123");
+
+        assert!(ListTasks::<i32>::default().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn missing_or_null_tasks() {
+        for fixture in [
+            include_str!("../../../tests/fixtures/responses/list-tasks-missing.json"),
+            include_str!("../../../tests/fixtures/responses/list-tasks-null.json"),
+        ] {
+            let results: ListTasks<MinimalTask> = serde_json::from_str(fixture).unwrap();
+            assert!(results.tasks.is_empty());
+        }
+
+        let results = ListTasks::<MinimalTask>::default();
+        assert_eq!(
+            serde_json::to_string(&results).unwrap(),
+            r#"{"tasks":[],"next_page_token":null}"#
+        );
+    }
 }
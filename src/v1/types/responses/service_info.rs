@@ -157,6 +157,30 @@ impl ServiceInfo {
     pub fn storage(&self) -> Option<&Vec<String>> {
         self.storage.as_ref()
     }
+
+    /// Serializes the service information as compact JSON.
+    ///
+    /// This is the canonical textual form of the service information: the
+    /// fields are written in the order they are declared, so equal service
+    /// information always serializes to the same string.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes the service information as pretty-printed JSON.
+    ///
+    /// See [`ServiceInfo::to_json()`].
+    #[cfg(feature = "serde")]
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes service information from JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
 }
 
 #[cfg(test)]
@@ -220,10 +244,57 @@ mod tests {
         );
         assert_eq!(result.environment.unwrap(), "test");
         assert_eq!(result.version, "1.0.0");
-        assert_eq!(result.storage.unwrap(), vec![
-            "file:///path/to/local/funnel-storage",
-            "s3://ohsu-compbio-funnel/storage"
-        ]);
+        assert_eq!(
+            result.storage.unwrap(),
+            vec![
+                "file:///path/to/local/funnel-storage",
+                "s3://ohsu-compbio-funnel/storage"
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn canonical_json() {
+        let info = ServiceInfo::from_json(
+            r#"{"version":"1.0.0","id":"org.ga4gh.myservice","name":"My project","type":{"group":"org.ga4gh","artifact":"tes","version":"1.0.0"},"organization":{"name":"My organization","url":"https://example.com"},"storage":["s3://bucket/storage"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            info.to_json().unwrap(),
+            r#"{"id":"org.ga4gh.myservice","name":"My project","type":{"group":"org.ga4gh","artifact":"tes","version":"1.0.0"},"description":null,"organization":{"name":"My organization","url":"https://example.com/"},"contactUrl":null,"documentationUrl":null,"createdAt":null,"updatedAt":null,"environment":null,"version":"1.0.0","storage":["s3://bucket/storage"]}"#
+        );
+        assert_eq!(
+            info.to_json_pretty().unwrap(),
+            r#"{
+  "id": "org.ga4gh.myservice",
+  "name": "My project",
+  "type": {
+    "group": "org.ga4gh",
+    "artifact": "tes",
+    "version": "1.0.0"
+  },
+  "description": null,
+  "organization": {
+    "name": "My organization",
+    "url": "https://example.com/"
+  },
+  "contactUrl": null,
+  "documentationUrl": null,
+  "createdAt": null,
+  "updatedAt": null,
+  "environment": null,
+  "version": "1.0.0",
+  "storage": [
+    "s3://bucket/storage"
+  ]
+}"#
+        );
+        assert_eq!(
+            ServiceInfo::from_json(&info.to_json().unwrap()).unwrap(),
+            info
+        );
     }
 
     #[cfg(feature = "serde")]
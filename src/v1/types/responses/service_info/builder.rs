@@ -14,10 +14,20 @@ use crate::v1::types::responses::service_info::TES_VERSION;
 pub const DEFAULT_GROUP: &str = "org.ga4gh";
 
 /// An error related to a [`Builder`].
+///
+/// More validations may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// A required value was missing for a builder field.
     Missing(&'static str),
+
+    /// The TES version is not a version string (e.g., `1.1.0`).
+    InvalidTesVersion(String),
+
+    /// The contact URL is neither a `mailto:` nor an HTTP(S) URL.
+    InvalidContactUrl(String),
 }
 
 impl std::fmt::Display for Error {
@@ -27,6 +37,17 @@ impl std::fmt::Display for Error {
                 f,
                 "missing required value for '{field}' in a service information builder"
             ),
+            Error::InvalidTesVersion(version) => {
+                write!(
+                    f,
+                    "invalid TES version `{version}` in a service information builder"
+                )
+            }
+            Error::InvalidContactUrl(url) => write!(
+                f,
+                "invalid contact URL `{url}` in a service information builder (expected a \
+                 `mailto:` or HTTP(S) URL)"
+            ),
         }
     }
 }
@@ -228,17 +249,36 @@ impl Builder {
     }
 
     /// Consumes `self` and attempts to builde a [`ServiceInfo`].
+    ///
+    /// # Errors
+    ///
+    /// The identifier, name, organization, and version of the service are
+    /// required. The TES version (if set) must be a version string (e.g.,
+    /// `1.1.0`) and the contact URL (if set) must be a `mailto:` or HTTP(S)
+    /// URL.
     pub fn try_build(self) -> Result<ServiceInfo> {
         let id = self.id.ok_or(Error::Missing("id"))?;
         let name = self.name.ok_or(Error::Missing("name"))?;
 
+        let tes_version = match self.tes_version {
+            Some(version) if !is_version(&version) => {
+                return Err(Error::InvalidTesVersion(version));
+            }
+            Some(version) => version,
+            None => String::from(TES_VERSION),
+        };
+
+        if let Some(url) = &self.contact_url {
+            if !is_contact_url(url) {
+                return Err(Error::InvalidContactUrl(url.clone()));
+            }
+        }
+
         let r#type = ServiceType {
             // NOTE: this value is dictated by the specification.
             group: String::from(DEFAULT_GROUP),
             artifact: Artifact::TaskExecutionService,
-            version: self
-                .tes_version
-                .unwrap_or_else(|| String::from(TES_VERSION)),
+            version: tes_version,
         };
 
         let organization = Organization {
@@ -264,3 +304,105 @@ impl Builder {
         })
     }
 }
+
+/// Returns whether a value is a version string.
+///
+/// A version string is made up of two or three numeric components separated
+/// by periods, optionally followed by a pre-release suffix (e.g., `1.1`,
+/// `1.1.0`, or `1.2.0-rc.1`).
+fn is_version(value: &str) -> bool {
+    let core = match value.split_once('-') {
+        Some((core, suffix)) if !suffix.is_empty() => core,
+        Some(_) => return false,
+        None => value,
+    };
+
+    let components = core.split('.').collect::<Vec<_>>();
+    (2..=3).contains(&components.len())
+        && components
+            .iter()
+            .all(|c| !c.is_empty() && c.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Returns whether a value is a `mailto:` or HTTP(S) URL.
+fn is_contact_url(value: &str) -> bool {
+    match Url::parse(value) {
+        Ok(url) => match url.scheme() {
+            "mailto" => !url.path().is_empty(),
+            "http" | "https" => url.has_host(),
+            _ => false,
+        },
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets a builder with every required field set.
+    fn builder() -> Builder {
+        Builder::default()
+            .id("org.ga4gh.myservice")
+            .name("My project")
+            .org_name("My organization")
+            .org_url("https://example.com".parse::<Url>().unwrap())
+            .version("1.0.0")
+    }
+
+    #[test]
+    fn missing_fields() {
+        let err = Builder::default().try_build().unwrap_err();
+        assert!(matches!(err, Error::Missing("id")));
+
+        let err = Builder::default()
+            .id("org.ga4gh.myservice")
+            .name("My project")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, Error::Missing("organization name")));
+        assert_eq!(
+            err.to_string(),
+            "missing required value for 'organization name' in a service information builder"
+        );
+    }
+
+    #[test]
+    fn tes_versions() {
+        let info = builder().try_build().unwrap();
+        assert_eq!(info.r#type.version, TES_VERSION);
+
+        for version in ["1.0", "1.1.0", "1.2.0-rc.1"] {
+            let info = builder().tes_version(version).try_build().unwrap();
+            assert_eq!(info.r#type.version, version);
+        }
+
+        for version in ["", "1", "v1.1.0", "1.1.0.0", "1..0", "1.1.0-"] {
+            let err = builder().tes_version(version).try_build().unwrap_err();
+            assert!(matches!(&err, Error::InvalidTesVersion(v) if v == version));
+        }
+    }
+
+    #[test]
+    fn contact_urls() {
+        for url in ["mailto:support@example.com", "https://example.com/support"] {
+            let info = builder().contact_url(url).try_build().unwrap();
+            assert_eq!(info.contact_url.as_deref(), Some(url));
+        }
+
+        for url in ["support@example.com", "ftp://example.com", "mailto:"] {
+            let err = builder().contact_url(url).try_build().unwrap_err();
+            assert!(matches!(&err, Error::InvalidContactUrl(u) if u == url));
+        }
+
+        assert_eq!(
+            builder()
+                .contact_url("ftp://example.com")
+                .try_build()
+                .unwrap_err()
+                .to_string(),
+            "invalid contact URL `ftp://example.com` in a service information builder (expected a \
+             `mailto:` or HTTP(S) URL)"
+        );
+    }
+}
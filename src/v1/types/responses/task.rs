@@ -3,24 +3,77 @@
 use crate::v1::types::Task;
 use crate::v1::types::task::State;
 
-/// A requested view of tasks.
-// TODO(clay): this is duplicated with some functionality of [`Response`]
-// below—can it be deduplicated?
-#[derive(Clone, Debug, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
-#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
-pub enum View {
-    /// The `MINIMAL` view.
-    Minimal,
+/// An error related to converting a [`Response`] into a submittable [`Task`]
+/// (or a [`Task`] into a [`Response`]).
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The response was a [`Response::Minimal`], which does not include
+    /// enough information to reconstruct the task.
+    MinimalView,
+
+    /// The task did not contain any executors.
+    MissingExecutors,
 
-    /// The `BASIC` view.
-    Basic,
+    /// The task did not have an ID, which the [`View::Minimal`] view requires.
+    MissingId,
+}
 
-    /// The `FULL` view.
-    Full,
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MinimalView => write!(
+                f,
+                "a task cannot be reconstructed from a `MINIMAL` view response"
+            ),
+            Error::MissingExecutors => write!(f, "the task does not contain any executors"),
+            Error::MissingId => write!(
+                f,
+                "a task without an ID cannot be represented with the `MINIMAL` view"
+            ),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
+/// Options for converting a [`Response`] into a submittable [`Task`].
+///
+/// See [`Response::into_submittable()`].
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Whether blank input content is stripped from `BASIC` view responses.
+    strip_blank_content: bool,
+}
+
+impl Options {
+    /// Sets whether the content of inputs is stripped (set to `None`) if it
+    /// is blank in a [`Response::Basic`].
+    ///
+    /// The `BASIC` view omits the content of inputs, which some services
+    /// return as an empty string. Resubmitting such a task would create empty
+    /// files in place of the original inputs, whereas a stripped input has
+    /// neither a URL nor content (which [`Task::validate()`] reports) until
+    /// its content is restored.
+    ///
+    /// Content is never stripped from a [`Response::Full`], where it is
+    /// genuine.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, content is
+    /// kept as it is.
+    pub fn strip_blank_content(mut self, value: bool) -> Self {
+        self.strip_blank_content = value;
+        self
+    }
+}
+
+pub use crate::v1::types::requests::View;
+
 /// A response for when `?view=MINIMAL` in a task endpoint.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -89,4 +142,415 @@ impl Response {
             _ => None,
         }
     }
+
+    /// Gets the view of the response.
+    pub fn view(&self) -> View {
+        match self {
+            Response::Minimal(_) => View::Minimal,
+            Response::Basic(_) => View::Basic,
+            Response::Full(_) => View::Full,
+        }
+    }
+
+    /// Gets the response in the shape of the `MINIMAL` view.
+    ///
+    /// Only the ID and the state are kept, so this drops every other field of
+    /// a [`Response::Basic`] or a [`Response::Full`]. Returns `None` if the
+    /// task has no ID (which is required by the `MINIMAL` view).
+    pub fn to_minimal(&self) -> Option<MinimalTask> {
+        Some(MinimalTask {
+            id: self.id()?.to_string(),
+            state: self.state(),
+        })
+    }
+
+    /// Gets the ID of the task.
+    ///
+    /// The ID is always present in a [`Response::Minimal`], whereas it is
+    /// optional in the other views.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Response::Minimal(task) => Some(&task.id),
+            Response::Basic(task) | Response::Full(task) => task.id.as_deref(),
+        }
+    }
+
+    /// Gets the state of the task (if the service reported it).
+    pub fn state(&self) -> Option<State> {
+        match self {
+            Response::Minimal(task) => task.state,
+            Response::Basic(task) | Response::Full(task) => task.state,
+        }
+    }
+
+    /// Gets the name of the task.
+    ///
+    /// This is always `None` for a [`Response::Minimal`], which does not
+    /// include the name.
+    pub fn name(&self) -> Option<&str> {
+        self.as_task()?.name.as_deref()
+    }
+
+    /// Consumes `self` to attempt to convert it into a [`Task`] that can be
+    /// resubmitted to a service with options.
+    ///
+    /// See the [`TryFrom`] implementation for [`Task`] (which uses the
+    /// default options) for details.
+    pub fn into_submittable(self, options: &Options) -> Result<Task, Error> {
+        let basic = matches!(self, Response::Basic(_));
+        let mut task = self.into_task().ok_or(Error::MinimalView)?;
+
+        if task.executors.is_empty() {
+            return Err(Error::MissingExecutors);
+        }
+
+        if basic && options.strip_blank_content {
+            for input in task.inputs.iter_mut().flatten() {
+                if input.content.as_deref() == Some("") {
+                    input.content = None;
+                }
+            }
+        }
+
+        Ok(Task {
+            id: None,
+            state: None,
+            logs: None,
+            creation_time: None,
+            ..task
+        })
+    }
+}
+
+impl Task {
+    /// Gets the task in the representation of a view.
+    ///
+    /// This follows the specification of the views (see [`View`]):
+    ///
+    /// * a [`View::Minimal`] only keeps the ID and the state of the task (so a
+    ///   task without an ID fails with [`Error::MissingId`]);
+    /// * a [`View::Basic`] drops the fields omitted by
+    ///   [`Task::strip_to_basic()`];
+    /// * a [`View::Full`] keeps every field.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::requests::View;
+    ///
+    /// let task = Task {
+    ///     id: Some(String::from("task-1")),
+    ///     name: Some(String::from("my-task")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let minimal = task.to_view(View::Minimal)?;
+    /// assert_eq!(minimal.id(), Some("task-1"));
+    /// assert_eq!(minimal.name(), None);
+    /// assert_eq!(task.to_view(View::Full)?.as_task(), Some(&task));
+    ///
+    /// assert!(Task::default().to_view(View::Minimal).is_err());
+    /// # Ok::<(), tes::v1::types::responses::task::Error>(())
+    /// ```
+    pub fn to_view(&self, view: View) -> Result<Response, Error> {
+        Ok(match view {
+            View::Minimal => Response::Minimal(MinimalTask {
+                id: self.id.clone().ok_or(Error::MissingId)?,
+                state: self.state,
+            }),
+            View::Basic => {
+                let mut task = self.clone();
+                task.strip_to_basic();
+                Response::Basic(task)
+            }
+            View::Full => Response::Full(self.clone()),
+        })
+    }
+
+    /// Drops the fields that are omitted from the `BASIC` view.
+    ///
+    /// These are the standard output and standard error of the executors,
+    /// the content of the inputs, and the system logs of the attempts.
+    pub fn strip_to_basic(&mut self) {
+        for input in self.inputs.iter_mut().flatten() {
+            input.content = None;
+        }
+
+        for log in self.logs.iter_mut().flatten() {
+            log.system_logs = None;
+
+            for log in &mut log.logs {
+                log.stdout = None;
+                log.stderr = None;
+            }
+        }
+    }
+}
+
+impl From<MinimalTask> for Response {
+    fn from(task: MinimalTask) -> Self {
+        Response::Minimal(task)
+    }
+}
+
+impl TryFrom<Response> for Task {
+    type Error = Error;
+
+    /// Attempts to convert a task [`Response`] into a [`Task`] that can be
+    /// resubmitted to a service.
+    ///
+    /// All fields that are assigned by the service (`id`, `state`, `logs`, and
+    /// `creation_time`) are dropped. Only [`Response::Basic`] and
+    /// [`Response::Full`] responses can be converted, and the task must
+    /// contain at least one executor.
+    ///
+    /// See [`Response::into_submittable()`] to strip the blank content of
+    /// inputs from a [`Response::Basic`].
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        response.into_submittable(&Options::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::v1::types::task::Executor;
+    use crate::v1::types::task::TaskLog;
+
+    #[test]
+    fn resubmittable_task_from_response() {
+        let task = Task {
+            id: Some(String::from("123")),
+            state: Some(State::SystemError),
+            name: Some(String::from("my-task")),
+            executors: vec![Executor {
+                image: String::from("ubuntu:latest"),
+                command: vec![String::from("true")],
+                ..Default::default()
+            }],
+            volumes: Some(vec![String::from("/data")]),
+            logs: Some(vec![TaskLog::default()]),
+            creation_time: Some(Utc::now()),
+            ..Default::default()
+        };
+
+        let converted = Task::try_from(Response::Full(task.clone())).unwrap();
+        assert_eq!(converted.id, None);
+        assert_eq!(converted.state, None);
+        assert_eq!(converted.logs, None);
+        assert_eq!(converted.creation_time, None);
+        assert_eq!(converted.name, task.name);
+        assert_eq!(converted.executors, task.executors);
+        assert_eq!(converted.volumes, task.volumes);
+    }
+
+    /// Parses a task fixture.
+    #[cfg(feature = "serde")]
+    fn fixture(json: &str) -> Task {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_tripping_full_views() {
+        let fetched = fixture(include_str!(
+            "../../../../tests/fixtures/responses/task-full.json"
+        ));
+        assert_eq!(fetched.id.as_deref(), Some("task-1"));
+        assert_eq!(fetched.logs.as_ref().unwrap().len(), 1);
+
+        let task = Task::try_from(Response::Full(fetched.clone())).unwrap();
+        assert!(task.semantically_eq(&fetched));
+        assert_eq!(
+            Task {
+                id: fetched.id.clone(),
+                state: fetched.state,
+                logs: fetched.logs.clone(),
+                creation_time: fetched.creation_time,
+                ..task.clone()
+            },
+            fetched
+        );
+
+        // NOTE: the converted task survives serialization unchanged, and
+        // stripping only applies to `BASIC` views.
+        let json = serde_json::to_string(&task).unwrap();
+        assert_eq!(serde_json::from_str::<Task>(&json).unwrap(), task);
+        assert!(!json.contains("task-1"));
+
+        let options = Options::default().strip_blank_content(true);
+        let mut blank = fetched;
+        blank.inputs.as_mut().unwrap()[1].content = Some(String::new());
+        let stripped = Response::Full(blank.clone())
+            .into_submittable(&options)
+            .unwrap();
+        assert_eq!(stripped.inputs, blank.inputs);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn stripping_blank_content() {
+        let fetched = fixture(include_str!(
+            "../../../../tests/fixtures/responses/task-basic.json"
+        ));
+
+        let task = Response::Basic(fetched.clone())
+            .into_submittable(&Options::default())
+            .unwrap();
+        assert_eq!(task.inputs, fetched.inputs);
+        assert_eq!(task.validate(), Ok(()));
+
+        let task = Response::Basic(fetched.clone())
+            .into_submittable(&Options::default().strip_blank_content(true))
+            .unwrap();
+        let inputs = task.inputs.as_ref().unwrap();
+        assert_eq!(inputs[0], fetched.inputs.as_ref().unwrap()[0]);
+        assert_eq!(inputs[1].content, None);
+        assert_eq!(task.semantic_diff(&fetched), ["inputs"]);
+        assert!(task.validate().is_err());
+    }
+
+    #[test]
+    fn accessors() {
+        let minimal = Response::Minimal(MinimalTask {
+            id: String::from("123"),
+            state: Some(State::Running),
+        });
+        assert_eq!(minimal.id(), Some("123"));
+        assert_eq!(minimal.state(), Some(State::Running));
+        assert_eq!(minimal.name(), None);
+
+        let minimal = Response::Minimal(MinimalTask {
+            id: String::from("123"),
+            state: None,
+        });
+        assert_eq!(minimal.state(), None);
+
+        let task = Task {
+            id: Some(String::from("456")),
+            state: Some(State::Complete),
+            name: Some(String::from("my-task")),
+            ..Default::default()
+        };
+        for response in [Response::Basic(task.clone()), Response::Full(task)] {
+            assert_eq!(response.id(), Some("456"));
+            assert_eq!(response.state(), Some(State::Complete));
+            assert_eq!(response.name(), Some("my-task"));
+        }
+
+        for response in [
+            Response::Basic(Task::default()),
+            Response::Full(Task::default()),
+        ] {
+            assert_eq!(response.id(), None);
+            assert_eq!(response.state(), None);
+            assert_eq!(response.name(), None);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn converting_between_views() {
+        let task = fixture(include_str!(
+            "../../../../tests/fixtures/responses/task-full.json"
+        ));
+
+        let full = Response::Full(task.clone());
+        assert_eq!(full.view(), View::Full);
+        assert_eq!(Response::Basic(task).view(), View::Basic);
+
+        let minimal = full.to_minimal().unwrap();
+        assert_eq!(
+            minimal,
+            MinimalTask {
+                id: String::from("task-1"),
+                state: Some(State::Complete),
+            }
+        );
+
+        let response = Response::from(minimal.clone());
+        assert_eq!(response.view(), View::Minimal);
+        assert_eq!(response.id(), full.id());
+        assert_eq!(response.state(), full.state());
+
+        // NOTE: converting a minimal response to the minimal shape is
+        // lossless.
+        assert_eq!(response.to_minimal(), Some(minimal));
+        assert_eq!(response.into_minimal(), full.to_minimal());
+
+        // NOTE: tasks without an ID have no minimal shape.
+        assert_eq!(Response::Full(Task::default()).to_minimal(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn projecting_views() {
+        let task = fixture(include_str!(
+            "../../../../tests/fixtures/responses/task-full.json"
+        ));
+
+        assert_eq!(
+            task.to_view(View::Full).unwrap(),
+            Response::Full(task.clone())
+        );
+
+        let minimal = task.to_view(View::Minimal).unwrap();
+        assert_eq!(
+            minimal,
+            Response::Minimal(MinimalTask {
+                id: String::from("task-1"),
+                state: Some(State::Complete),
+            })
+        );
+
+        let basic = task.to_view(View::Basic).unwrap().into_task().unwrap();
+        let inputs = basic.inputs.as_ref().unwrap();
+        assert_eq!(inputs[0], task.inputs.as_ref().unwrap()[0]);
+        assert_eq!(inputs[1].content, None);
+
+        let log = &basic.logs.as_ref().unwrap()[0];
+        assert_eq!(log.system_logs, None);
+        assert_eq!(log.logs[0].stdout, None);
+        assert_eq!(log.logs[0].stderr, None);
+        assert_eq!(log.logs[0].exit_code, Some(0));
+        assert_eq!(log.outputs, task.logs.as_ref().unwrap()[0].outputs);
+
+        // NOTE: every other field is kept.
+        let mut stripped = task.clone();
+        stripped.strip_to_basic();
+        assert_eq!(stripped, basic);
+        assert_eq!(basic.executors, task.executors);
+        assert_eq!(basic.resources, task.resources);
+        assert_eq!(basic.tags, task.tags);
+        assert_eq!(basic.creation_time, task.creation_time);
+        assert_eq!(basic.semantic_diff(&task), ["inputs"]);
+
+        // NOTE: a task without an ID is not given a made up ID.
+        let err = Task::default().to_view(View::Minimal).unwrap_err();
+        assert_eq!(err, Error::MissingId);
+        assert_eq!(
+            err.to_string(),
+            "a task without an ID cannot be represented with the `MINIMAL` view"
+        );
+    }
+
+    #[test]
+    fn minimal_views_cannot_be_converted() {
+        let response = Response::Minimal(MinimalTask {
+            id: String::from("123"),
+            state: Some(State::Complete),
+        });
+
+        assert_eq!(Task::try_from(response).unwrap_err(), Error::MinimalView);
+    }
+
+    #[test]
+    fn tasks_without_executors_cannot_be_converted() {
+        let response = Response::Basic(Task::default());
+        assert_eq!(
+            Task::try_from(response).unwrap_err(),
+            Error::MissingExecutors
+        );
+    }
 }
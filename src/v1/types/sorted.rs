@@ -0,0 +1,23 @@
+//! Serializing maps sorted by their keys.
+//!
+//! The iteration order of a [`HashMap`] changes between runs, so maps within
+//! the types are serialized through a sorted view to make the serialization
+//! of the types deterministic.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// Serializes an optional map sorted by its keys.
+pub(crate) fn serialize<S>(
+    map: &Option<HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serde::Serialize::serialize(
+        &map.as_ref()
+            .map(|map| map.iter().collect::<BTreeMap<_, _>>()),
+        serializer,
+    )
+}
@@ -4,21 +4,47 @@
 use std::collections::BTreeMap;
 #[cfg(not(feature = "ord"))]
 use std::collections::HashMap;
+use std::path::Path;
 
 use chrono::DateTime;
 use chrono::Utc;
 use ordered_float::OrderedFloat;
 
+pub mod builder;
+pub mod comparison;
+#[cfg(feature = "digest")]
+pub mod digest;
 pub mod executor;
 pub mod file;
+pub mod input;
+pub mod log;
+pub mod output;
+pub mod redaction;
+pub mod resources;
+pub mod tags;
+pub mod validation;
+
+/// The maximum number of characters of a log included in an error summary.
+///
+/// See [`Task::error_summary()`].
+pub const MAX_SUMMARY_LOG_CHARS: usize = 200;
+
+/// The map of the tags of a task.
+#[cfg(not(feature = "ord"))]
+type Tags = HashMap<String, String>;
+/// The map of the tags of a task.
+#[cfg(feature = "ord")]
+type Tags = BTreeMap<String, String>;
 
 pub use executor::Executor;
 
 /// State of TES task.
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+///
+/// States are ordered by the lifecycle of a task: from [`State::Unknown`] and
+/// [`State::Queued`] through [`State::Running`] to the terminal states.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
-#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
 pub enum State {
     /// An unknown state.
     #[default]
@@ -36,6 +62,11 @@ pub enum State {
     /// A task that is paused.
     Paused,
 
+    /// A task that is being canceled.
+    ///
+    /// This state was added in version 1.1 of the specification.
+    Canceling,
+
     /// A task that has completed.
     Complete,
 
@@ -49,14 +80,44 @@ pub enum State {
 
     /// A task that has been cancelled.
     Canceled,
+
+    /// A task that was stopped because the instance running it was preempted.
+    ///
+    /// This state was added in version 1.1 of the specification.
+    Preempted,
+}
+
+impl std::fmt::Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::Unknown => write!(f, "UNKNOWN"),
+            State::Queued => write!(f, "QUEUED"),
+            State::Initializing => write!(f, "INITIALIZING"),
+            State::Running => write!(f, "RUNNING"),
+            State::Paused => write!(f, "PAUSED"),
+            State::Canceling => write!(f, "CANCELING"),
+            State::Complete => write!(f, "COMPLETE"),
+            State::ExecutorError => write!(f, "EXECUTOR_ERROR"),
+            State::SystemError => write!(f, "SYSTEM_ERROR"),
+            State::Canceled => write!(f, "CANCELED"),
+            State::Preempted => write!(f, "PREEMPTED"),
+        }
+    }
 }
 
 impl State {
     /// Returns whether a task is still executing or not.
+    ///
+    /// A task that is [being canceled](State::Canceling) is still executing.
     pub fn is_executing(&self) -> bool {
         matches!(
             self,
-            Self::Unknown | Self::Queued | Self::Initializing | Self::Running | Self::Paused
+            Self::Unknown
+                | Self::Queued
+                | Self::Initializing
+                | Self::Running
+                | Self::Paused
+                | Self::Canceling
         )
     }
 }
@@ -86,6 +147,143 @@ pub struct Input {
     pub content: Option<String>,
 }
 
+impl Input {
+    /// Creates a file input that is fetched from `url` and mounted at `path`
+    /// within the container.
+    ///
+    /// The path must be absolute:
+    ///
+    /// ```
+    /// use tes::v1::types::task::Input;
+    ///
+    /// let input = Input::from_url("s3://bucket/reads.fq", "/inputs/reads.fq")?.name("reads");
+    /// assert_eq!(input.url.as_deref(), Some("s3://bucket/reads.fq"));
+    ///
+    /// assert!(Input::from_url("s3://bucket/reads.fq", "reads.fq").is_err());
+    /// # Ok::<(), tes::v1::types::task::input::Error>(())
+    /// ```
+    pub fn from_url(url: impl Into<String>, path: impl Into<String>) -> Result<Self, input::Error> {
+        Ok(Self {
+            url: Some(url.into()),
+            path: input::check_path(path.into())?,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a directory input that is fetched from `url` and mounted at
+    /// `path` within the container.
+    ///
+    /// See [`Self::from_url()`].
+    pub fn directory_from_url(
+        url: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Result<Self, input::Error> {
+        Ok(Self {
+            r#type: file::Type::Directory,
+            ..Self::from_url(url, path)?
+        })
+    }
+
+    /// Creates a file input with literal content that is mounted at `path`
+    /// within the container.
+    ///
+    /// The path must be absolute. Directories cannot have content, so there is
+    /// no directory counterpart.
+    pub fn from_content(
+        content: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Result<Self, input::Error> {
+        Ok(Self {
+            path: input::check_path(path.into())?,
+            content: Some(content.into()),
+            ..Default::default()
+        })
+    }
+
+    /// Creates an input mounted at `container_path` whose content is that of
+    /// a local file.
+    ///
+    /// See [`Self::inline_local_file()`]. The file may be at most
+    /// [`input::DEFAULT_CONTENT_LIMIT`] bytes, and `container_path` must be
+    /// absolute.
+    pub fn from_local_file(
+        path: impl AsRef<Path>,
+        container_path: impl Into<String>,
+    ) -> Result<Self, input::Error> {
+        let mut input = Self {
+            path: input::check_path(container_path.into())?,
+            ..Default::default()
+        };
+
+        input.inline_local_file(path, input::DEFAULT_CONTENT_LIMIT)?;
+        Ok(input)
+    }
+
+    /// Sets the content of the input to that of a local file.
+    ///
+    /// This is useful for attaching small scripts and configuration files
+    /// without staging them to storage. The file must be valid UTF-8 (as the
+    /// content of an input is a string literal) and at most `limit` bytes.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set content, but fails if the
+    /// input has a URL or is a directory.
+    pub fn inline_local_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        limit: u64,
+    ) -> Result<(), input::Error> {
+        if self.url.is_some() {
+            return Err(input::Error::UrlAndContent {
+                path: self.path.clone(),
+            });
+        }
+
+        if self.r#type == file::Type::Directory {
+            return Err(input::Error::DirectoryContent {
+                path: self.path.clone(),
+            });
+        }
+
+        self.content = Some(input::read(path.as_ref(), limit)?);
+        Ok(())
+    }
+
+    /// Gets where the content of the input comes from.
+    ///
+    /// As in the specification, the URL is ignored when the input has
+    /// content (though [`Task::validate()`] rejects inputs with both). An
+    /// input with neither has no source.
+    pub fn source(&self) -> Option<input::InputSource<'_>> {
+        match (&self.content, &self.url) {
+            (Some(content), _) => Some(input::InputSource::Content(content)),
+            (None, Some(url)) => Some(input::InputSource::Url(url)),
+            (None, None) => None,
+        }
+    }
+
+    /// Sets the name of the input.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set name.
+    pub fn name(mut self, value: impl Into<String>) -> Self {
+        self.name = Some(value.into());
+        self
+    }
+
+    /// Sets the description of the input.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set description.
+    pub fn description(mut self, value: impl Into<String>) -> Self {
+        self.description = Some(value.into());
+        self
+    }
+}
+
 /// An output for a TES task.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -101,13 +299,120 @@ pub struct Output {
     pub url: String,
 
     /// The path to the output within the container.
+    ///
+    /// This may contain wildcards, in which case [`Self::path_prefix`] is
+    /// required.
     pub path: String,
 
+    /// The prefix removed from the paths matching a wildcard [`Self::path`]
+    /// to get the URLs of the matching files (relative to [`Self::url`]).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub path_prefix: Option<String>,
+
     /// The type.
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub r#type: file::Type,
 }
 
+impl Output {
+    /// Creates a file output at `path` within the container that is stored at
+    /// `url`.
+    ///
+    /// The path must be absolute and must not contain wildcards (see
+    /// [`Self::glob()`]):
+    ///
+    /// ```
+    /// use tes::v1::types::task::Output;
+    ///
+    /// let output = Output::file("s3://bucket/report.html", "/outputs/report.html")?;
+    /// assert_eq!(output.path, "/outputs/report.html");
+    ///
+    /// assert!(Output::file("s3://bucket/", "/outputs/*.html").is_err());
+    /// # Ok::<(), tes::v1::types::task::output::Error>(())
+    /// ```
+    pub fn file(url: impl Into<String>, path: impl Into<String>) -> Result<Self, output::Error> {
+        Ok(Self {
+            url: url.into(),
+            path: output::check_path(path.into(), false)?,
+            ..Default::default()
+        })
+    }
+
+    /// Creates a directory output at `path` within the container that is
+    /// stored at `url`.
+    ///
+    /// See [`Self::file()`].
+    pub fn directory(
+        url: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Result<Self, output::Error> {
+        Ok(Self {
+            r#type: file::Type::Directory,
+            ..Self::file(url, path)?
+        })
+    }
+
+    /// Creates an output of the files matching the wildcard `pattern` within
+    /// the container that are stored under `url`.
+    ///
+    /// Each matching file is stored at `url` joined with its path after
+    /// removing `path_prefix`, which must be a prefix of the pattern (without
+    /// wildcards):
+    ///
+    /// ```
+    /// use tes::v1::types::task::Output;
+    ///
+    /// // NOTE: `/outputs/logs/a.log` is stored at `s3://bucket/logs/a.log`.
+    /// let output = Output::glob("s3://bucket/logs/", "/outputs/logs/*.log", "/outputs/logs/")?;
+    /// assert_eq!(output.path_prefix.as_deref(), Some("/outputs/logs/"));
+    ///
+    /// assert!(Output::glob("s3://bucket/logs/", "/outputs/logs/*.log", "/inputs/").is_err());
+    /// # Ok::<(), tes::v1::types::task::output::Error>(())
+    /// ```
+    pub fn glob(
+        url: impl Into<String>,
+        pattern: impl Into<String>,
+        path_prefix: impl Into<String>,
+    ) -> Result<Self, output::Error> {
+        let pattern = output::check_path(pattern.into(), true)?;
+        let prefix = path_prefix.into();
+
+        if !pattern.starts_with(&prefix) || output::has_wildcards(&prefix) {
+            return Err(output::Error::InvalidPathPrefix { pattern, prefix });
+        }
+
+        Ok(Self {
+            url: url.into(),
+            path: pattern,
+            path_prefix: Some(prefix),
+            ..Default::default()
+        })
+    }
+
+    /// Sets the name of the output.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set name.
+    pub fn name(mut self, value: impl Into<String>) -> Self {
+        self.name = Some(value.into());
+        self
+    }
+
+    /// Sets the description of the output.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set description.
+    pub fn description(mut self, value: impl Into<String>) -> Self {
+        self.description = Some(value.into());
+        self
+    }
+}
+
 /// Requested resources for a TES task.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -127,6 +432,79 @@ pub struct Resources {
 
     /// The zones.
     pub zones: Option<Vec<String>>,
+
+    /// The parameters passed to the backend of the service.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub backend_parameters: Option<resources::BackendParameters>,
+
+    /// Whether the service must fail the task if it does not support one of
+    /// the backend parameters.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub backend_parameters_strict: Option<bool>,
+}
+
+impl Resources {
+    /// Gets an empty builder for [`Resources`].
+    ///
+    /// See [`resources::Builder`].
+    pub fn builder() -> resources::Builder {
+        resources::Builder::default()
+    }
+
+    /// Adds a zone in which the task may run.
+    ///
+    /// Returns `false` (leaving the zones unchanged) if the zone was already
+    /// present.
+    ///
+    /// ```
+    /// use tes::v1::types::task::Resources;
+    ///
+    /// let mut resources = Resources::default();
+    /// assert!(resources.add_zone("us-central1-b")?);
+    /// assert!(resources.add_zone("us-central1-a")?);
+    /// assert!(!resources.add_zone("us-central1-b")?);
+    /// assert!(resources.add_zone("").is_err());
+    ///
+    /// assert_eq!(resources.zones(), ["us-central1-b", "us-central1-a"]);
+    /// # Ok::<(), tes::v1::types::task::resources::Error>(())
+    /// ```
+    pub fn add_zone(&mut self, zone: impl Into<String>) -> resources::Result<bool> {
+        let zone = zone.into();
+
+        if zone.is_empty() {
+            return Err(resources::Error::EmptyZone);
+        }
+
+        let zones = self.zones.get_or_insert_with(Vec::new);
+        if zones.contains(&zone) {
+            return Ok(false);
+        }
+
+        zones.push(zone);
+        Ok(true)
+    }
+
+    /// Gets the zones in which the task may run without duplicates (in the
+    /// order they were first added).
+    ///
+    /// Use [`zones`](Self::zones) directly to access the zones as they are.
+    pub fn zones(&self) -> Vec<&str> {
+        let mut zones: Vec<&str> = Vec::new();
+
+        for zone in self.zones.iter().flatten() {
+            if !zones.contains(&zone.as_str()) {
+                zones.push(zone);
+            }
+        }
+
+        zones
+    }
 }
 
 /// An output file log.
@@ -141,9 +519,75 @@ pub struct OutputFileLog {
     pub path: String,
 
     /// The size in bytes.
+    ///
+    /// The specification encodes the size as a string, but some services
+    /// (e.g., Funnel) send a number, so either is accepted when deserializing.
+    /// The size is always serialized as a string. See
+    /// [`OutputFileLog::size()`] for the size as a number.
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_size_bytes"))]
     pub size_bytes: String,
 }
 
+impl OutputFileLog {
+    /// Parses the size in bytes.
+    ///
+    /// ```
+    /// use tes::v1::types::task::OutputFileLog;
+    ///
+    /// let log = OutputFileLog {
+    ///     size_bytes: String::from("1024"),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(log.size(), Ok(1024));
+    ///
+    /// let log = OutputFileLog {
+    ///     size_bytes: String::from("-1"),
+    ///     ..Default::default()
+    /// };
+    /// assert!(log.size().is_err());
+    /// ```
+    pub fn size(&self) -> Result<u64, std::num::ParseIntError> {
+        self.size_bytes.parse()
+    }
+}
+
+/// Deserializes the size of an output file from either a string or an
+/// integer.
+#[cfg(feature = "serde")]
+fn deserialize_size_bytes<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    /// A visitor of the size of an output file.
+    struct Visitor;
+
+    impl serde::de::Visitor<'_> for Visitor {
+        type Value = String;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "a string or an integer")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_string<E: serde::de::Error>(self, value: String) -> Result<String, E> {
+            Ok(value)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<String, E> {
+            Ok(value.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(Visitor)
+}
+
 /// A task log.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -165,6 +609,74 @@ pub struct TaskLog {
     pub system_logs: Option<Vec<String>>,
 }
 
+/// Gets the time elapsed between two times, clamping negative durations (from
+/// clock skew) to zero.
+fn elapsed(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<chrono::Duration> {
+    Some((end - start).max(chrono::Duration::zero()))
+}
+
+impl TaskLog {
+    /// Gets an empty builder for a [`TaskLog`].
+    ///
+    /// See [`log::Builder`].
+    pub fn builder() -> log::Builder {
+        log::Builder::default()
+    }
+
+    /// Gets how long the attempt ran (if both its start time and its end time
+    /// are known).
+    ///
+    /// An end time before the start time (e.g., because of clock skew between
+    /// workers) results in a duration of zero.
+    pub fn runtime(&self) -> Option<chrono::Duration> {
+        elapsed(self.start_time?, self.end_time?)
+    }
+
+    /// Gets how long each executor ran within the attempt (in the order of the
+    /// executors).
+    ///
+    /// See [`executor::Log::duration()`].
+    pub fn executor_durations(&self) -> Vec<Option<chrono::Duration>> {
+        self.logs.iter().map(executor::Log::duration).collect()
+    }
+
+    /// Gets the index and the exit code of the first executor within the
+    /// attempt that exited with a non-zero exit code.
+    pub fn first_nonzero_exit(&self) -> Option<(usize, u32)> {
+        self.logs
+            .iter()
+            .enumerate()
+            .find_map(|(index, log)| match log.exit_code {
+                Some(code) if code != 0 => Some((index, code)),
+                _ => None,
+            })
+    }
+
+    /// Gets the indices and the logs of the executors within the attempt that
+    /// exited with a non-zero exit code.
+    ///
+    /// Executors without an exit code (e.g., because they never ran) are not
+    /// included.
+    pub fn failed_executors(&self) -> Vec<(usize, &executor::Log)> {
+        self.logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log.exit_code.is_some_and(|code| code != 0))
+            .collect()
+    }
+}
+
+/// Gets the last non-empty line of a log (truncated to
+/// [`MAX_SUMMARY_LOG_CHARS`]).
+fn last_line(log: &str) -> Option<String> {
+    let line = log.lines().map(str::trim).rfind(|line| !line.is_empty())?;
+
+    match line.char_indices().nth(MAX_SUMMARY_LOG_CHARS) {
+        Some((end, _)) => Some(format!("{}...", &line[..end])),
+        None => Some(line.to_string()),
+    }
+}
+
 /// A task.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -198,11 +710,11 @@ pub struct Task {
     pub volumes: Option<Vec<String>>,
 
     /// The tags.
-    #[cfg(not(feature = "ord"))]
-    pub tags: Option<HashMap<String, String>>,
-    /// The tags.
-    #[cfg(feature = "ord")]
-    pub tags: Option<BTreeMap<String, String>>,
+    #[cfg_attr(
+        all(feature = "serde", not(feature = "ord")),
+        serde(serialize_with = "crate::v1::types::sorted::serialize")
+    )]
+    pub tags: Option<Tags>,
 
     /// The logs.
     pub logs: Option<Vec<TaskLog>>,
@@ -210,3 +722,803 @@ pub struct Task {
     /// The time of creation.
     pub creation_time: Option<DateTime<Utc>>,
 }
+
+impl Task {
+    /// Gets an empty builder for a [`Task`].
+    ///
+    /// See [`builder::Builder`].
+    pub fn builder() -> builder::Builder {
+        builder::Builder::default()
+    }
+
+    /// Sets a tag of the task.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::tags;
+    ///
+    /// let mut task = Task::default()
+    ///     .tag(tags::RUN_ID, "run-1")
+    ///     .tag(tags::SAMPLE, "NA12878");
+    /// task.tags_mut().remove(tags::SAMPLE);
+    ///
+    /// assert_eq!(task.get_tag(tags::RUN_ID), Some("run-1"));
+    /// assert_eq!(task.get_tag(tags::SAMPLE), None);
+    /// ```
+    ///
+    /// Keys are checked when the task is [validated](Self::validate) (see
+    /// [`tags::is_valid_key()`]).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set value for the tag.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags_mut().insert(key.into(), value.into());
+        self
+    }
+
+    /// Gets a mutable reference to the tags of the task (adding an empty map
+    /// of tags if the task has none).
+    pub fn tags_mut(&mut self) -> &mut Tags {
+        self.tags.get_or_insert_with(Default::default)
+    }
+
+    /// Gets the value of a tag of the task.
+    pub fn get_tag(&self, key: &str) -> Option<&str> {
+        self.tags.as_ref()?.get(key).map(String::as_str)
+    }
+
+    /// Gets the total size (in bytes) of the content of the inputs of the
+    /// task.
+    ///
+    /// The content is sent within the body of the request that creates the
+    /// task, so this is a lower bound on the size of the body. See
+    /// [`validation::Options::content_limit()`] to limit the content of each
+    /// input.
+    pub fn inline_content_size(&self) -> usize {
+        self.inputs
+            .iter()
+            .flatten()
+            .filter_map(|input| input.content.as_ref())
+            .map(String::len)
+            .sum()
+    }
+
+    /// Serializes the task as compact JSON.
+    ///
+    /// This is the canonical textual form of a task: the fields are written
+    /// in the order they are declared and maps (e.g., the tags) are sorted by
+    /// their keys, so equal tasks always serialize to the same string.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let task = Task {
+    ///     executors: vec![Executor::new("ubuntu", ["true"])?],
+    ///     ..Default::default()
+    /// }
+    /// .tag("b", "2")
+    /// .tag("a", "1");
+    ///
+    /// let json = task.to_json()?;
+    /// assert!(json.contains(r#""tags":{"a":"1","b":"2"}"#));
+    /// assert_eq!(Task::from_json(&json)?, task);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Serializes the task as pretty-printed JSON.
+    ///
+    /// See [`Task::to_json()`].
+    #[cfg(feature = "serde")]
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a task from JSON.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Gets the logs of the latest attempt of the task (if any).
+    ///
+    /// Services append the logs of each attempt, so this is the last of the
+    /// logs.
+    pub fn latest_log(&self) -> Option<&TaskLog> {
+        self.logs.as_ref()?.last()
+    }
+
+    /// Gets how long the latest attempt of the task ran.
+    ///
+    /// See [`TaskLog::runtime()`].
+    pub fn total_runtime(&self) -> Option<chrono::Duration> {
+        self.latest_log()?.runtime()
+    }
+
+    /// Gets how long the task waited from its creation until its first
+    /// attempt started (if both times are known).
+    ///
+    /// The time is measured by the clocks of the service and its workers, so
+    /// a start time before the time of creation (from clock skew) results in
+    /// a duration of zero.
+    pub fn queue_to_start(&self) -> Option<chrono::Duration> {
+        let first = self.logs.as_ref()?.first()?;
+        elapsed(self.creation_time?, first.start_time?)
+    }
+
+    /// Gets how long the task took from its creation until its latest attempt
+    /// ended (if both times are known).
+    ///
+    /// Unlike [`Task::total_runtime()`], this includes the time spent queued
+    /// and in earlier attempts. An end time before the time of creation (from
+    /// clock skew) results in a duration of zero.
+    pub fn wall_clock(&self) -> Option<chrono::Duration> {
+        elapsed(self.creation_time?, self.latest_log()?.end_time?)
+    }
+
+    /// Gets how long each executor ran within the latest attempt of the task.
+    ///
+    /// See [`TaskLog::executor_durations()`]. This is empty if the task has no
+    /// logs.
+    pub fn per_executor_durations(&self) -> Vec<Option<chrono::Duration>> {
+        self.latest_log()
+            .map(TaskLog::executor_durations)
+            .unwrap_or_default()
+    }
+
+    /// Gets the index and the exit code of the first executor that exited
+    /// with a non-zero exit code within the latest attempt of the task.
+    ///
+    /// See [`TaskLog::first_nonzero_exit()`].
+    pub fn first_nonzero_exit(&self) -> Option<(usize, u32)> {
+        self.latest_log()?.first_nonzero_exit()
+    }
+
+    /// Gets the indices and the logs of the executors that exited with a
+    /// non-zero exit code within the latest attempt of the task.
+    ///
+    /// See [`TaskLog::failed_executors()`]. This is empty if the task has no
+    /// logs.
+    pub fn failed_executors(&self) -> Vec<(usize, &executor::Log)> {
+        self.latest_log()
+            .map(TaskLog::failed_executors)
+            .unwrap_or_default()
+    }
+
+    /// Gets the index and the logs of the first executor that exited with a
+    /// non-zero exit code within the latest attempt of the task.
+    ///
+    /// Later executors are usually not run after a failure, so this is the
+    /// executor that failed the task.
+    pub fn first_failure(&self) -> Option<(usize, &executor::Log)> {
+        self.failed_executors().into_iter().next()
+    }
+
+    /// Gets a one-line summary of why the task failed.
+    ///
+    /// If an executor failed within the latest attempt (see
+    /// [`Task::first_failure()`]), the summary names the executor and its
+    /// exit code, followed by the last line of its standard error (which is
+    /// only present in the `FULL` view). Otherwise, if the task is in the
+    /// [`State::ExecutorError`] or [`State::SystemError`] state, the summary
+    /// names the state, followed by the last line of the system logs of the
+    /// latest attempt (if any). Lines are truncated to
+    /// [`MAX_SUMMARY_LOG_CHARS`] characters.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::State;
+    /// use tes::v1::types::task::TaskLog;
+    /// use tes::v1::types::task::executor;
+    ///
+    /// let task = Task {
+    ///     state: Some(State::ExecutorError),
+    ///     logs: Some(vec![TaskLog {
+    ///         logs: vec![
+    ///             executor::Log {
+    ///                 exit_code: Some(0),
+    ///                 ..Default::default()
+    ///             },
+    ///             executor::Log {
+    ///                 exit_code: Some(127),
+    ///                 stderr: Some(String::from("sh: 1: samtools: not found\n")),
+    ///                 ..Default::default()
+    ///             },
+    ///         ],
+    ///         ..Default::default()
+    ///     }]),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     task.error_summary().as_deref(),
+    ///     Some("executors[1] exited with code 127: sh: 1: samtools: not found")
+    /// );
+    /// ```
+    ///
+    /// Returns `None` if no executor failed and the task is not in either of
+    /// the error states.
+    pub fn error_summary(&self) -> Option<String> {
+        if let Some((index, log)) = self.first_failure() {
+            // SAFETY: only executors with an exit code are failures.
+            let code = log.exit_code.unwrap();
+            let mut summary = format!("executors[{index}] exited with code {code}");
+
+            if let Some(line) = log.stderr.as_deref().and_then(last_line) {
+                summary.push_str(": ");
+                summary.push_str(&line);
+            }
+
+            return Some(summary);
+        }
+
+        let mut summary = match self.state? {
+            State::ExecutorError => String::from("the task failed with an executor error"),
+            State::SystemError => String::from("the task failed with a system error"),
+            _ => return None,
+        };
+
+        let line = self
+            .latest_log()
+            .and_then(|log| log.system_logs.as_ref())
+            .and_then(|logs| logs.iter().rev().find_map(|log| last_line(log)));
+        if let Some(line) = line {
+            summary.push_str(": ");
+            summary.push_str(&line);
+        }
+
+        Some(summary)
+    }
+
+    /// Gets the lines of the system logs of every attempt of the task (from
+    /// the first attempt to the latest).
+    ///
+    /// Each system log may contain multiple lines.
+    pub fn system_log_lines(&self) -> impl Iterator<Item = &str> {
+        self.logs
+            .iter()
+            .flatten()
+            .flat_map(|log| log.system_logs.iter().flatten())
+            .flat_map(|log| log.lines())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone as _;
+
+    use super::*;
+
+    /// Gets the time at a number of seconds past midnight.
+    fn at(seconds: u32) -> Option<DateTime<Utc>> {
+        Some(
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+                + chrono::Duration::seconds(seconds.into()),
+        )
+    }
+
+    /// Creates the logs of an executor.
+    fn executor(start: Option<u32>, end: Option<u32>, exit_code: Option<u32>) -> executor::Log {
+        executor::Log {
+            start_time: start.and_then(at),
+            end_time: end.and_then(at),
+            exit_code,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a task with the logs of multiple attempts.
+    fn task() -> Task {
+        Task {
+            logs: Some(vec![
+                TaskLog {
+                    logs: vec![
+                        executor(Some(0), Some(5), Some(0)),
+                        executor(Some(5), None, None),
+                    ],
+                    start_time: at(0),
+                    end_time: None,
+                    system_logs: Some(vec![String::from("preempted\nrescheduling")]),
+                    ..Default::default()
+                },
+                TaskLog {
+                    logs: vec![
+                        executor(Some(60), Some(90), Some(0)),
+                        executor(Some(90), Some(150), Some(137)),
+                        executor(None, Some(160), Some(1)),
+                    ],
+                    start_time: at(60),
+                    end_time: at(160),
+                    system_logs: Some(vec![String::from("out of memory")]),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn states() {
+        assert!(State::Canceling.is_executing());
+        assert!(!State::Preempted.is_executing());
+
+        // NOTE: a task that is being canceled has not yet reached a terminal
+        // state.
+        assert!(State::Paused < State::Canceling);
+        assert!(State::Canceling < State::Complete);
+        assert!(State::Canceled < State::Preempted);
+
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            serde_json::from_str::<State>(r#""PREEMPTED""#).unwrap(),
+            State::Preempted
+        );
+    }
+
+    #[test]
+    fn runtimes() {
+        let task = task();
+        assert_eq!(task.total_runtime(), Some(chrono::Duration::seconds(100)));
+        assert_eq!(
+            task.per_executor_durations(),
+            [
+                Some(chrono::Duration::seconds(30)),
+                Some(chrono::Duration::seconds(60)),
+                None
+            ]
+        );
+
+        // NOTE: the first attempt never ended.
+        let first = &task.logs.as_ref().unwrap()[0];
+        assert_eq!(first.runtime(), None);
+        assert_eq!(
+            first.executor_durations(),
+            [Some(chrono::Duration::seconds(5)), None]
+        );
+
+        assert_eq!(Task::default().total_runtime(), None);
+        assert!(Task::default().per_executor_durations().is_empty());
+        assert_eq!(TaskLog::default().runtime(), None);
+    }
+
+    #[test]
+    fn wall_clocks() {
+        let mut task = task();
+        assert_eq!(task.queue_to_start(), None);
+        assert_eq!(task.wall_clock(), None);
+
+        task.creation_time = at(0).map(|time| time - chrono::Duration::seconds(30));
+        assert_eq!(task.queue_to_start(), Some(chrono::Duration::seconds(30)));
+        assert_eq!(task.wall_clock(), Some(chrono::Duration::seconds(190)));
+
+        // NOTE: the latest attempt has not ended yet.
+        task.logs.as_mut().unwrap()[1].end_time = None;
+        assert_eq!(task.wall_clock(), None);
+        assert_eq!(Task::default().queue_to_start(), None);
+        assert_eq!(Task::default().wall_clock(), None);
+    }
+
+    #[test]
+    fn skewed_clocks() {
+        // NOTE: each time is earlier than the time it follows.
+        let task = Task {
+            creation_time: at(100),
+            logs: Some(vec![TaskLog {
+                logs: vec![executor(Some(50), Some(40), Some(0))],
+                start_time: at(90),
+                end_time: at(80),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+
+        let zero = Some(chrono::Duration::zero());
+        assert_eq!(task.queue_to_start(), zero);
+        assert_eq!(task.wall_clock(), zero);
+        assert_eq!(task.total_runtime(), zero);
+        assert_eq!(task.per_executor_durations(), [zero]);
+    }
+
+    #[test]
+    fn nonzero_exits() {
+        let task = task();
+        assert_eq!(task.first_nonzero_exit(), Some((1, 137)));
+        assert_eq!(task.logs.as_ref().unwrap()[0].first_nonzero_exit(), None);
+        assert_eq!(Task::default().first_nonzero_exit(), None);
+    }
+
+    #[test]
+    fn failed_executors() {
+        let task = task();
+        assert_eq!(
+            task.failed_executors()
+                .into_iter()
+                .map(|(index, log)| (index, log.exit_code))
+                .collect::<Vec<_>>(),
+            [(1, Some(137)), (2, Some(1))]
+        );
+        assert_eq!(task.first_failure().map(|(index, _)| index), Some(1));
+
+        // NOTE: only the latest attempt is inspected.
+        assert!(task.logs.as_ref().unwrap()[0].failed_executors().is_empty());
+        assert!(Task::default().failed_executors().is_empty());
+        assert!(Task::default().first_failure().is_none());
+    }
+
+    #[test]
+    fn error_summaries() {
+        let mut task = task();
+        assert_eq!(
+            task.error_summary().as_deref(),
+            Some("executors[1] exited with code 137")
+        );
+
+        let logs = &mut task.logs.as_mut().unwrap()[1].logs;
+        logs[1].stderr = Some(String::from("loading index\n  Killed  \n\n"));
+        assert_eq!(
+            task.error_summary().as_deref(),
+            Some("executors[1] exited with code 137: Killed")
+        );
+
+        let logs = &mut task.logs.as_mut().unwrap()[1].logs;
+        logs[1].stderr = Some("x".repeat(MAX_SUMMARY_LOG_CHARS + 1));
+        assert_eq!(
+            task.error_summary().unwrap(),
+            format!(
+                "executors[1] exited with code 137: {}...",
+                "x".repeat(MAX_SUMMARY_LOG_CHARS)
+            )
+        );
+
+        // NOTE: without a failed executor, the state and system logs are used.
+        let mut task = Task {
+            state: Some(State::SystemError),
+            logs: Some(vec![TaskLog {
+                logs: vec![executor(Some(0), None, None)],
+                system_logs: Some(vec![
+                    String::from("node lost"),
+                    String::from("pod evicted\n"),
+                ]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(
+            task.error_summary().as_deref(),
+            Some("the task failed with a system error: pod evicted")
+        );
+
+        task.logs = None;
+        task.state = Some(State::ExecutorError);
+        assert_eq!(
+            task.error_summary().as_deref(),
+            Some("the task failed with an executor error")
+        );
+
+        task.state = Some(State::Complete);
+        assert_eq!(task.error_summary(), None);
+        assert_eq!(Task::default().error_summary(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn output_file_sizes() {
+        let log: TaskLog = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/responses/task-log-output-sizes.json"
+        ))
+        .unwrap();
+        let outputs = log.outputs.unwrap();
+
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|output| output.size_bytes.as_str())
+                .collect::<Vec<_>>(),
+            ["1024", "2048", "-1", "about 3 KB"]
+        );
+        assert_eq!(outputs[0].size(), Ok(1024));
+        assert_eq!(outputs[1].size(), Ok(2048));
+        assert!(outputs[2].size().is_err());
+        assert!(outputs[3].size().is_err());
+
+        // NOTE: sizes sent as numbers are serialized as strings.
+        assert_eq!(
+            serde_json::to_string(&outputs[1]).unwrap(),
+            r#"{"url":"s3://bucket/number.txt","path":"/outputs/number.txt","size_bytes":"2048"}"#
+        );
+
+        for json in [
+            r#"{"url":"s3://bucket/a","path":"/a","size_bytes":1.5}"#,
+            r#"{"url":"s3://bucket/a","path":"/a","size_bytes":null}"#,
+        ] {
+            let err = serde_json::from_str::<OutputFileLog>(json).unwrap_err();
+            assert!(
+                err.to_string().contains("expected a string or an integer"),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn system_log_lines() {
+        assert_eq!(
+            task().system_log_lines().collect::<Vec<_>>(),
+            ["preempted", "rescheduling", "out of memory"]
+        );
+        assert_eq!(Task::default().system_log_lines().count(), 0);
+    }
+
+    #[test]
+    fn inputs() {
+        let input = Input::from_url("s3://bucket/a.txt", "/inputs/a.txt")
+            .unwrap()
+            .name("a")
+            .description("The first input.");
+        assert_eq!(input.url.as_deref(), Some("s3://bucket/a.txt"));
+        assert_eq!(input.path, "/inputs/a.txt");
+        assert_eq!(input.r#type, file::Type::File);
+        assert_eq!(input.name.as_deref(), Some("a"));
+        assert_eq!(input.description.as_deref(), Some("The first input."));
+        assert_eq!(input.content, None);
+
+        let input = Input::directory_from_url("s3://bucket/b", "/inputs/b").unwrap();
+        assert_eq!(input.r#type, file::Type::Directory);
+
+        let input = Input::from_content("hello", "/inputs/c.txt").unwrap();
+        assert_eq!(input.content.as_deref(), Some("hello"));
+        assert_eq!(input.url, None);
+        assert_eq!(input.r#type, file::Type::File);
+
+        for result in [
+            Input::from_url("s3://bucket/a.txt", "a.txt"),
+            Input::directory_from_url("s3://bucket/b", "./b"),
+            Input::from_content("hello", ""),
+            Input::from_local_file("script.sh", "script.sh"),
+        ] {
+            assert!(matches!(result, Err(input::Error::RelativePath(_))));
+        }
+    }
+
+    #[test]
+    fn outputs() {
+        let output = Output::file("s3://bucket/a.txt", "/outputs/a.txt")
+            .unwrap()
+            .name("a");
+        assert_eq!(output.r#type, file::Type::File);
+        assert_eq!(output.path_prefix, None);
+        assert_eq!(output.name.as_deref(), Some("a"));
+
+        let output = Output::directory("s3://bucket/b", "/outputs/b").unwrap();
+        assert_eq!(output.r#type, file::Type::Directory);
+
+        assert_eq!(
+            Output::file("s3://bucket/a.txt", "a.txt"),
+            Err(output::Error::RelativePath(String::from("a.txt")))
+        );
+        for path in ["/outputs/*.txt", "/outputs/?.txt", "/outputs/[ab].txt"] {
+            assert_eq!(
+                Output::directory("s3://bucket/", path),
+                Err(output::Error::MissingPathPrefix(String::from(path)))
+            );
+        }
+    }
+
+    #[test]
+    fn glob_outputs() {
+        let output = Output::glob("s3://bucket/", "/outputs/*.txt", "/outputs/").unwrap();
+        assert_eq!(output.path, "/outputs/*.txt");
+        assert_eq!(output.path_prefix.as_deref(), Some("/outputs/"));
+        assert_eq!(output.r#type, file::Type::File);
+
+        assert!(matches!(
+            Output::glob("s3://bucket/", "outputs/*.txt", "outputs/"),
+            Err(output::Error::RelativePath(_))
+        ));
+        for prefix in ["/inputs/", "/outputs/*", "/outputs/a"] {
+            assert_eq!(
+                Output::glob("s3://bucket/", "/outputs/*.txt", prefix),
+                Err(output::Error::InvalidPathPrefix {
+                    pattern: String::from("/outputs/*.txt"),
+                    prefix: String::from(prefix),
+                })
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializing_outputs() {
+        let output = Output::glob("s3://bucket/", "/outputs/*.txt", "/outputs/").unwrap();
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"name":null,"description":null,"url":"s3://bucket/","path":"/outputs/*.txt","path_prefix":"/outputs/","type":"FILE"}"#
+        );
+
+        let output: Output = serde_json::from_str(
+            r#"{"url":"s3://bucket/b","path":"/outputs/b","type":"DIRECTORY"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            Output::directory("s3://bucket/b", "/outputs/b").unwrap()
+        );
+
+        // NOTE: outputs without a path prefix serialize as they do for
+        // services implementing version 1.0 of the specification.
+        let output = Output::file("s3://bucket/a", "/outputs/a").unwrap();
+        let json = serde_json::to_string(&output).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":null,"description":null,"url":"s3://bucket/a","path":"/outputs/a","type":"FILE"}"#
+        );
+        assert_eq!(serde_json::from_str::<Output>(&json).unwrap(), output);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn canonical_json() {
+        let task = Task::builder()
+            .name("align")
+            .input(Input::from_content("ACGT", "/inputs/reads.fa").unwrap())
+            .resources(Resources::builder().cpu_cores(2).try_build().unwrap())
+            .executor(
+                Executor::from_shell("ubuntu", "wc -c /inputs/reads.fa")
+                    .env("THREADS", "2")
+                    .env("LC_ALL", "C")
+                    .env("HOME", "/root")
+                    .try_build()
+                    .unwrap(),
+            )
+            .tag("sample", "NA12878")
+            .tag("run_id", "run-1")
+            .tag("owner", "me")
+            .try_build()
+            .unwrap();
+
+        assert_eq!(
+            task.to_json().unwrap(),
+            r#"{"id":null,"state":null,"name":"align","description":null,"inputs":[{"name":null,"description":null,"url":null,"path":"/inputs/reads.fa","type":"FILE","content":"ACGT"}],"outputs":null,"resources":{"cpu_cores":2,"preemptible":null,"ram_gb":null,"disk_gb":null,"zones":null},"executors":[{"image":"ubuntu","command":["/bin/bash","-c","wc -c /inputs/reads.fa"],"workdir":null,"stdin":null,"stdout":null,"stderr":null,"env":{"HOME":"/root","LC_ALL":"C","THREADS":"2"}}],"volumes":null,"tags":{"owner":"me","run_id":"run-1","sample":"NA12878"},"logs":null,"creation_time":null}"#
+        );
+        assert_eq!(
+            task.to_json_pretty().unwrap(),
+            r#"{
+  "id": null,
+  "state": null,
+  "name": "align",
+  "description": null,
+  "inputs": [
+    {
+      "name": null,
+      "description": null,
+      "url": null,
+      "path": "/inputs/reads.fa",
+      "type": "FILE",
+      "content": "ACGT"
+    }
+  ],
+  "outputs": null,
+  "resources": {
+    "cpu_cores": 2,
+    "preemptible": null,
+    "ram_gb": null,
+    "disk_gb": null,
+    "zones": null
+  },
+  "executors": [
+    {
+      "image": "ubuntu",
+      "command": [
+        "/bin/bash",
+        "-c",
+        "wc -c /inputs/reads.fa"
+      ],
+      "workdir": null,
+      "stdin": null,
+      "stdout": null,
+      "stderr": null,
+      "env": {
+        "HOME": "/root",
+        "LC_ALL": "C",
+        "THREADS": "2"
+      }
+    }
+  ],
+  "volumes": null,
+  "tags": {
+    "owner": "me",
+    "run_id": "run-1",
+    "sample": "NA12878"
+  },
+  "logs": null,
+  "creation_time": null
+}"#
+        );
+        assert_eq!(Task::from_json(&task.to_json().unwrap()).unwrap(), task);
+    }
+
+    #[test]
+    fn input_sources() {
+        use input::InputSource;
+
+        let input = |url: Option<&str>, content: Option<&str>| Input {
+            url: url.map(String::from),
+            path: String::from("/inputs/a.txt"),
+            content: content.map(String::from),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            input(Some("s3://bucket/a.txt"), None).source(),
+            Some(InputSource::Url("s3://bucket/a.txt"))
+        );
+        assert_eq!(
+            input(None, Some("hello")).source(),
+            Some(InputSource::Content("hello"))
+        );
+        assert_eq!(
+            input(Some("s3://bucket/a.txt"), Some("hello")).source(),
+            Some(InputSource::Content("hello"))
+        );
+        assert_eq!(input(None, None).source(), None);
+    }
+
+    #[test]
+    fn inlining_local_files() {
+        let dir = std::env::temp_dir().join(format!("tes-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let script = dir.join("script.sh");
+        std::fs::write(&script, "echo hello\n").unwrap();
+        let input = Input::from_local_file(&script, "/inputs/script.sh").unwrap();
+        assert_eq!(input.path, "/inputs/script.sh");
+        assert_eq!(input.content.as_deref(), Some("echo hello\n"));
+        assert_eq!(input.url, None);
+
+        let mut input = Input::default();
+        assert!(matches!(
+            input.inline_local_file(&script, 4),
+            Err(input::Error::TooLarge { limit: 4, .. })
+        ));
+        input.inline_local_file(&script, 11).unwrap();
+
+        let binary = dir.join("binary");
+        std::fs::write(&binary, [0xFF, 0xFE, 0x00]).unwrap();
+        assert!(matches!(
+            Input::from_local_file(&binary, "/inputs/binary"),
+            Err(input::Error::NotUtf8(_))
+        ));
+
+        let mut input = Input {
+            url: Some(String::from("s3://bucket/script.sh")),
+            path: String::from("/inputs/script.sh"),
+            ..Default::default()
+        };
+        assert!(matches!(
+            input.inline_local_file(&script, input::DEFAULT_CONTENT_LIMIT),
+            Err(input::Error::UrlAndContent { .. })
+        ));
+        assert_eq!(input.content, None);
+
+        assert!(matches!(
+            Input::from_local_file(dir.join("missing"), "/inputs/missing"),
+            Err(input::Error::Io { .. })
+        ));
+
+        let mut input = Input {
+            path: String::from("/inputs/scripts"),
+            r#type: file::Type::Directory,
+            ..Default::default()
+        };
+        assert!(matches!(
+            input.inline_local_file(&script, input::DEFAULT_CONTENT_LIMIT),
+            Err(input::Error::DirectoryContent { .. })
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+//! Builders for tasks.
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::v1::types::Task;
+use crate::v1::types::task::Executor;
+use crate::v1::types::task::Input;
+use crate::v1::types::task::Output;
+use crate::v1::types::task::Resources;
+use crate::v1::types::task::State;
+use crate::v1::types::task::TaskLog;
+
+/// An error related to a [`Builder`].
+///
+/// More validations may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// No executors were added (the specification requires at least one).
+    NoExecutors,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NoExecutors => write!(f, "a task builder requires at least one executor"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A [`Result`](std::result::Result) with an [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A builder for a [`Task`].
+///
+/// Unlike the [default](Task::default()) task, a built task always has at
+/// least one executor:
+///
+/// ```
+/// use tes::v1::types::Task;
+/// use tes::v1::types::task::Executor;
+///
+/// let task = Task::builder()
+///     .name("my-task")
+///     .executor(Executor {
+///         image: String::from("ubuntu:latest"),
+///         command: vec![String::from("echo"), String::from("hello")],
+///         ..Default::default()
+///     })
+///     .tag("project", "tes")
+///     .try_build()?;
+///
+/// assert_eq!(task.name.as_deref(), Some("my-task"));
+/// assert_eq!(task.executors.len(), 1);
+///
+/// assert!(Task::builder().name("my-task").try_build().is_err());
+/// # Ok::<(), tes::v1::types::task::builder::Error>(())
+/// ```
+///
+/// The fields assigned by a service (the ID, state, logs, and time of
+/// creation) can also be set, which is useful for building the tasks returned
+/// by a service.
+#[derive(Debug, Default)]
+pub struct Builder {
+    /// The task being built.
+    task: Task,
+}
+
+impl Builder {
+    /// Sets the ID of the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set ID.
+    pub fn id(mut self, value: impl Into<String>) -> Self {
+        self.task.id = Some(value.into());
+        self
+    }
+
+    /// Sets the state of the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set state.
+    pub fn state(mut self, value: State) -> Self {
+        self.task.state = Some(value);
+        self
+    }
+
+    /// Sets the name of the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set name.
+    pub fn name(mut self, value: impl Into<String>) -> Self {
+        self.task.name = Some(value.into());
+        self
+    }
+
+    /// Sets the description of the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set description.
+    pub fn description(mut self, value: impl Into<String>) -> Self {
+        self.task.description = Some(value.into());
+        self
+    }
+
+    /// Adds an input to the task.
+    pub fn input(mut self, value: Input) -> Self {
+        self.task.inputs.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    /// Adds an output to the task.
+    pub fn output(mut self, value: Output) -> Self {
+        self.task.outputs.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    /// Sets the requested resources of the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set resources.
+    pub fn resources(mut self, value: Resources) -> Self {
+        self.task.resources = Some(value);
+        self
+    }
+
+    /// Adds an executor to the task.
+    ///
+    /// Executors are run in the order they are added.
+    pub fn executor(mut self, value: Executor) -> Self {
+        self.task.executors.push(value);
+        self
+    }
+
+    /// Adds a volume to the task.
+    pub fn volume(mut self, value: impl Into<String>) -> Self {
+        self.task
+            .volumes
+            .get_or_insert_with(Vec::new)
+            .push(value.into());
+        self
+    }
+
+    /// Adds a tag to the task.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set value for the tag.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.task = self.task.tag(key, value);
+        self
+    }
+
+    /// Adds the log of an attempt of the task.
+    ///
+    /// Logs are in the order of the attempts (see [`TaskLog::builder()`]).
+    pub fn log(mut self, value: TaskLog) -> Self {
+        self.task.logs.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    /// Sets the time the task was created.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set time of creation.
+    pub fn creation_time(mut self, value: DateTime<Utc>) -> Self {
+        self.task.creation_time = Some(value);
+        self
+    }
+
+    /// Consumes `self` to attempt to build a [`Task`].
+    pub fn try_build(self) -> Result<Task> {
+        if self.task.executors.is_empty() {
+            return Err(Error::NoExecutors);
+        }
+
+        Ok(self.task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets an executor that runs `command`.
+    fn executor(command: &str) -> Executor {
+        Executor {
+            image: String::from("ubuntu"),
+            command: vec![String::from(command)],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn missing_executors() {
+        let err = Builder::default()
+            .name("my-task")
+            .volume("/data")
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(err, Error::NoExecutors));
+        assert_eq!(
+            err.to_string(),
+            "a task builder requires at least one executor"
+        );
+    }
+
+    #[test]
+    fn tasks() {
+        let task = Task::builder()
+            .name("my-task")
+            .description("A description.")
+            .resources(Resources {
+                cpu_cores: Some(4),
+                ..Default::default()
+            })
+            .executor(executor("first"))
+            .executor(executor("second"))
+            .input(Input {
+                path: String::from("/inputs/a.txt"),
+                content: Some(String::from("hello")),
+                ..Default::default()
+            })
+            .output(Output {
+                url: String::from("s3://bucket/b.txt"),
+                path: String::from("/outputs/b.txt"),
+                ..Default::default()
+            })
+            .volume("/scratch")
+            .tag("project", "tes")
+            .tag("project", "other")
+            .try_build()
+            .unwrap();
+
+        assert_eq!(task.name.as_deref(), Some("my-task"));
+        assert_eq!(task.description.as_deref(), Some("A description."));
+        assert_eq!(task.resources.unwrap().cpu_cores, Some(4));
+        assert_eq!(
+            task.executors
+                .iter()
+                .map(|executor| executor.command[0].as_str())
+                .collect::<Vec<_>>(),
+            ["first", "second"]
+        );
+        assert_eq!(task.inputs.unwrap().len(), 1);
+        assert_eq!(task.outputs.unwrap().len(), 1);
+        assert_eq!(task.volumes.unwrap(), ["/scratch"]);
+
+        let tags = task.tags.unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags["project"], "other");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn full_view_tasks() {
+        use crate::v1::types::task::OutputFileLog;
+        use crate::v1::types::task::executor;
+        use crate::v1::types::task::resources::Size;
+
+        let time = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+
+        let task = Task::builder()
+            .id("task-1")
+            .state(State::Complete)
+            .name("align")
+            .description("Aligns a sample.")
+            .input(
+                Input::from_url("s3://bucket/ref.fa", "/inputs/ref.fa")
+                    .unwrap()
+                    .name("reference"),
+            )
+            .input(
+                Input::from_content(
+                    "bwa mem /inputs/ref.fa /inputs/reads.fq > /outputs/aligned.sam\n",
+                    "/inputs/align.sh",
+                )
+                .unwrap()
+                .name("script"),
+            )
+            .output(Output::file("s3://bucket/aligned.sam", "/outputs/aligned.sam").unwrap())
+            .resources(
+                Resources::builder()
+                    .cpu_cores(4)
+                    .preemptible(true)
+                    .ram(Size::gb(8))
+                    .disk(Size::gb(40))
+                    .zone("us-east1-b")
+                    .zone("us-east1-c")
+                    .try_build()
+                    .unwrap(),
+            )
+            .executor(
+                Executor::from_args("biocontainers/bwa:v0.7.17", ["sh", "/inputs/align.sh"])
+                    .workdir("/outputs")
+                    .env("THREADS", "4")
+                    .try_build()
+                    .unwrap(),
+            )
+            .volume("/scratch")
+            .tag("run_id", "run-1")
+            .tag("sample", "NA12878")
+            .log(
+                TaskLog::builder()
+                    .executor_log(
+                        executor::Log::builder()
+                            .start_time(time("2024-09-01T12:00:05Z"))
+                            .end_time(time("2024-09-01T12:10:00Z"))
+                            .stdout("")
+                            .stderr("[main] Real time: 595.000 sec\n")
+                            .exit_code(0)
+                            .build(),
+                    )
+                    .start_time(time("2024-09-01T12:00:00Z"))
+                    .end_time(time("2024-09-01T12:10:05Z"))
+                    .output(OutputFileLog {
+                        url: String::from("s3://bucket/aligned.sam"),
+                        path: String::from("/outputs/aligned.sam"),
+                        size_bytes: String::from("1048576"),
+                    })
+                    .system_log("pulled image biocontainers/bwa:v0.7.17")
+                    .build(),
+            )
+            .creation_time(time("2024-09-01T11:59:58Z"))
+            .try_build()
+            .unwrap();
+
+        let fixture: Task = serde_json::from_str(include_str!(
+            "../../../../tests/fixtures/responses/task-full.json"
+        ))
+        .unwrap();
+        assert_eq!(task, fixture);
+        assert_eq!(
+            task.to_json_pretty().unwrap(),
+            include_str!("../../../../tests/fixtures/responses/task-full-golden.json").trim_end()
+        );
+    }
+}
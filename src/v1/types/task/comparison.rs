@@ -0,0 +1,225 @@
+//! Semantic comparison of tasks.
+//!
+//! See [`Task::semantically_eq()`].
+
+use crate::v1::types::Task;
+use crate::v1::types::task::Resources;
+
+/// Returns whether two optional lists are equal, treating a missing list as
+/// an empty list.
+fn lists_eq<T: PartialEq>(a: &Option<Vec<T>>, b: &Option<Vec<T>>) -> bool {
+    a.as_deref().unwrap_or_default() == b.as_deref().unwrap_or_default()
+}
+
+/// Returns whether two resources have the same zones
+/// (regardless of their order and of duplicates).
+fn zones_eq(a: &Resources, b: &Resources) -> bool {
+    let mut a = a.zones();
+    let mut b = b.zones();
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
+
+impl Task {
+    /// Returns whether the task has the same definition as another task.
+    ///
+    /// This is intended for comparing a submitted task with the task returned
+    /// by a service, so it ignores the fields assigned by a service (the ID,
+    /// state, logs, and time of creation) and the differences services
+    /// commonly introduce:
+    ///
+    /// * missing lists and maps are the same as empty ones (e.g., a task
+    ///   without volumes and a task with `Some(vec![])`), as are missing
+    ///   resources and default resources;
+    /// * zones are compared regardless of their order and of duplicates;
+    /// * tags are compared regardless of their order.
+    ///
+    /// Everything else (including the order of executors, inputs, and
+    /// outputs) must be equal. See [`Task::semantic_diff()`] for the fields
+    /// that differ.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    /// use tes::v1::types::task::State;
+    ///
+    /// let submitted = Task {
+    ///     executors: vec![Executor::new("ubuntu", ["echo", "hello"])?],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut returned = submitted.clone();
+    /// returned.id = Some(String::from("task-1"));
+    /// returned.state = Some(State::Running);
+    /// returned.volumes = Some(Vec::new());
+    /// assert!(submitted.semantically_eq(&returned));
+    ///
+    /// returned.name = Some(String::from("renamed"));
+    /// assert_eq!(submitted.semantic_diff(&returned), ["name"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn semantically_eq(&self, other: &Task) -> bool {
+        self.semantic_diff(other).is_empty()
+    }
+
+    /// Gets the fields in which the definition of the task differs from that
+    /// of another task (e.g., `executors` or `resources.zones`).
+    ///
+    /// The fields are compared as described for [`Task::semantically_eq()`],
+    /// and they are returned in the order they are declared.
+    pub fn semantic_diff(&self, other: &Task) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        let mut compare = |field: &'static str, eq: bool| {
+            if !eq {
+                fields.push(field);
+            }
+        };
+
+        compare("name", self.name == other.name);
+        compare("description", self.description == other.description);
+        compare("inputs", lists_eq(&self.inputs, &other.inputs));
+        compare("outputs", lists_eq(&self.outputs, &other.outputs));
+
+        let default = Resources::default();
+        let a = self.resources.as_ref().unwrap_or(&default);
+        let b = other.resources.as_ref().unwrap_or(&default);
+        compare("resources.cpu_cores", a.cpu_cores == b.cpu_cores);
+        compare("resources.preemptible", a.preemptible == b.preemptible);
+        compare("resources.ram_gb", a.ram_gb == b.ram_gb);
+        compare("resources.disk_gb", a.disk_gb == b.disk_gb);
+        compare("resources.zones", zones_eq(a, b));
+        compare(
+            "resources.backend_parameters",
+            a.backend_parameters.as_ref().filter(|p| !p.is_empty())
+                == b.backend_parameters.as_ref().filter(|p| !p.is_empty()),
+        );
+        compare(
+            "resources.backend_parameters_strict",
+            a.backend_parameters_strict == b.backend_parameters_strict,
+        );
+
+        compare("executors", self.executors == other.executors);
+        compare("volumes", lists_eq(&self.volumes, &other.volumes));
+        compare(
+            "tags",
+            self.tags.as_ref().filter(|tags| !tags.is_empty())
+                == other.tags.as_ref().filter(|tags| !tags.is_empty()),
+        );
+
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::types::task::Executor;
+    use crate::v1::types::task::Input;
+    use crate::v1::types::task::State;
+    use crate::v1::types::task::TaskLog;
+
+    /// Gets a task as it was submitted.
+    fn submitted() -> Task {
+        Task {
+            name: Some(String::from("my-task")),
+            inputs: Some(vec![
+                Input::from_url("s3://bucket/a.txt", "/inputs/a.txt").unwrap(),
+            ]),
+            resources: Some(
+                Resources::builder()
+                    .cpu_cores(2)
+                    .zone("us-east1-b")
+                    .zone("us-east1-c")
+                    .try_build()
+                    .unwrap(),
+            ),
+            executors: vec![Executor::new("ubuntu", ["cat", "/inputs/a.txt"]).unwrap()],
+            ..Default::default()
+        }
+        .tag("run_id", "run-1")
+        .tag("sample", "NA12878")
+    }
+
+    #[test]
+    fn equal_tasks() {
+        let task = submitted();
+        assert!(task.semantically_eq(&task));
+        assert!(Task::default().semantically_eq(&Task::default()));
+
+        let mut returned = task.clone();
+        returned.id = Some(String::from("task-1"));
+        returned.state = Some(State::Complete);
+        returned.logs = Some(vec![TaskLog::default()]);
+        returned.creation_time = Some(chrono::Utc::now());
+        returned.outputs = Some(Vec::new());
+        returned.volumes = Some(Vec::new());
+        assert!(task.semantically_eq(&returned));
+        assert!(returned.semantically_eq(&task));
+
+        // NOTE: missing and default resources are the same.
+        let resources = Task {
+            resources: Some(Resources::default()),
+            ..Default::default()
+        };
+        assert!(Task::default().semantically_eq(&resources));
+    }
+
+    #[test]
+    fn reordered_tasks() {
+        let task = submitted();
+
+        let mut returned = Task {
+            tags: None,
+            ..task.clone()
+        }
+        .tag("sample", "NA12878")
+        .tag("run_id", "run-1");
+        returned.resources.as_mut().unwrap().zones = Some(
+            ["us-east1-c", "us-east1-b", "us-east1-c"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+
+        assert!(task.semantically_eq(&returned));
+    }
+
+    #[test]
+    fn different_tasks() {
+        let task = submitted();
+
+        let mut returned = task.clone();
+        returned.name = None;
+        returned.inputs.as_mut().unwrap()[0].path = String::from("/inputs/b.txt");
+        returned.resources.as_mut().unwrap().cpu_cores = Some(1);
+        returned.resources.as_mut().unwrap().zones = Some(vec![String::from("us-east1-b")]);
+        returned
+            .executors
+            .push(Executor::new("ubuntu", ["true"]).unwrap());
+        returned
+            .tags_mut()
+            .insert(String::from("run_id"), String::from("run-2"));
+
+        assert!(!task.semantically_eq(&returned));
+        assert_eq!(
+            task.semantic_diff(&returned),
+            [
+                "name",
+                "inputs",
+                "resources.cpu_cores",
+                "resources.zones",
+                "executors",
+                "tags"
+            ]
+        );
+
+        // NOTE: the order of executors matters.
+        let mut task = task;
+        task.executors
+            .push(Executor::new("ubuntu", ["true"]).unwrap());
+        let mut reordered = task.clone();
+        reordered.executors.swap(0, 1);
+        assert_eq!(task.semantic_diff(&reordered), ["executors"]);
+    }
+}
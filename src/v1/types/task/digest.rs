@@ -0,0 +1,324 @@
+//! Digests of tasks.
+//!
+//! See [`Task::digest()`].
+
+use serde_json::Value;
+use sha2::Digest as _;
+use sha2::Sha256;
+
+use crate::v1::types::Task;
+
+/// The fields of a task that are assigned by a service (and never covered by
+/// a digest).
+const ASSIGNED_FIELDS: &[&str] = &["id", "state", "logs", "creation_time"];
+
+/// A SHA-256 digest of a task.
+///
+/// Digests are displayed as lowercase hexadecimal strings.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Digest([u8; 32]);
+
+impl Digest {
+    /// Gets the bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for computing the digest of a task.
+///
+/// See [`Task::digest_with()`].
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Whether the tags are excluded from the digest.
+    exclude_tags: bool,
+}
+
+impl Options {
+    /// Sets whether the tags of the task are excluded from the digest.
+    ///
+    /// Excluding the tags allows tasks that only differ in their metadata
+    /// (e.g., the ID of the run that submitted them) to share a digest.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, the tags are
+    /// included.
+    pub fn exclude_tags(mut self, value: bool) -> Self {
+        self.exclude_tags = value;
+        self
+    }
+}
+
+/// Writes the canonical JSON serialization of a value.
+///
+/// The members of objects are written sorted by their keys, members
+/// that are `null` are skipped, and no whitespace is written.
+fn write(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Array(values) => {
+            out.push(b'[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                write(value, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(members) => {
+            // NOTE: the members are sorted here rather than relying on the
+            // order of `serde_json`'s map, which depends on its features.
+            let mut members = members
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .collect::<Vec<_>>();
+            members.sort_by_key(|(key, _)| *key);
+
+            out.push(b'{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+
+                write(&Value::String(key.clone()), out);
+                out.push(b':');
+                write(value, out);
+            }
+            out.push(b'}');
+        }
+        // NOTE: writing scalars to a vector cannot fail.
+        scalar => {
+            serde_json::to_writer(&mut *out, scalar).expect("scalars should serialize to a vector")
+        }
+    }
+}
+
+/// Gets the canonical JSON serialization of a task.
+fn canonicalize(task: &Task, options: &Options) -> Vec<u8> {
+    let mut value = serde_json::to_value(task).expect("tasks should serialize to a JSON value");
+
+    if let Value::Object(members) = &mut value {
+        for field in ASSIGNED_FIELDS {
+            members.remove(*field);
+        }
+
+        if options.exclude_tags {
+            members.remove("tags");
+        }
+    }
+
+    let mut out = Vec::new();
+    write(&value, &mut out);
+    out
+}
+
+impl Task {
+    /// Computes the digest of the definition of the task.
+    ///
+    /// Tasks with the same definition have the same digest, which makes the
+    /// digest suitable for caching and detecting duplicate submissions. The
+    /// digest is a SHA-256 hash of a canonical JSON serialization of the task
+    /// in which:
+    ///
+    /// * the members of every object are sorted by their keys, so the order of
+    ///   maps (e.g., the tags or the environment variables of executors) does
+    ///   not matter;
+    /// * members that are `null` are skipped, so a missing optional field and a
+    ///   field set to `None` are the same (whereas an empty list and a missing
+    ///   list are not);
+    /// * the fields assigned by a service (the ID, state, logs, and time of
+    ///   creation) are excluded.
+    ///
+    /// Everything else, including the order of lists (e.g., of executors) and
+    /// the tags, is covered. See [`Task::digest_with()`] to exclude the tags.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let task = Task {
+    ///     executors: vec![Executor::new("ubuntu", ["echo", "hello"])?],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut submitted = task.clone();
+    /// submitted.id = Some(String::from("task-1"));
+    /// assert_eq!(task.digest(), submitted.digest());
+    ///
+    /// let tagged = task.clone().tag("run_id", "run-1");
+    /// assert_ne!(task.digest(), tagged.digest());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn digest(&self) -> Digest {
+        self.digest_with(&Options::default())
+    }
+
+    /// Computes the digest of the definition of the task with options.
+    ///
+    /// See [`Task::digest()`].
+    pub fn digest_with(&self, options: &Options) -> Digest {
+        Digest(Sha256::digest(canonicalize(self, options)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::types::task::Executor;
+    use crate::v1::types::task::TaskLog;
+
+    /// Gets every permutation of `items`.
+    fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+        if items.len() <= 1 {
+            return vec![items.to_vec()];
+        }
+
+        let mut result = Vec::new();
+        for i in 0..items.len() {
+            let mut rest = items.to_vec();
+            let item = rest.remove(i);
+
+            for mut permutation in permutations(&rest) {
+                permutation.insert(0, item.clone());
+                result.push(permutation);
+            }
+        }
+
+        result
+    }
+
+    /// Gets a task with a single executor.
+    fn task() -> Task {
+        Task {
+            name: Some(String::from("my-task")),
+            executors: vec![Executor::new("ubuntu", ["echo", "hello"]).unwrap()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn canonical_serialization() {
+        let task = task().tag("b", "2").tag("a", "1");
+
+        assert_eq!(
+            String::from_utf8(canonicalize(&task, &Options::default())).unwrap(),
+            r#"{"executors":[{"command":["echo","hello"],"image":"ubuntu"}],"name":"my-task","tags":{"a":"1","b":"2"}}"#
+        );
+        assert_eq!(
+            String::from_utf8(canonicalize(&task, &Options::default().exclude_tags(true))).unwrap(),
+            r#"{"executors":[{"command":["echo","hello"],"image":"ubuntu"}],"name":"my-task"}"#
+        );
+
+        // NOTE: the SHA-256 hash of the canonical serialization above.
+        assert_eq!(
+            task.digest().to_string(),
+            "f2d06821d05b652bdf47d248f9e25c1095c53e05bb7f0bb43bf9ef4e1284122d"
+        );
+    }
+
+    #[test]
+    fn tag_order() {
+        let tags = [
+            ("run_id", "run-1"),
+            ("sample", "NA12878"),
+            ("owner", "me"),
+            ("project", "tes"),
+        ];
+        let expected = tags
+            .iter()
+            .fold(task(), |task, (key, value)| task.tag(*key, *value))
+            .digest();
+
+        for permutation in permutations(&tags) {
+            let task = permutation
+                .into_iter()
+                .fold(task(), |task, (key, value)| task.tag(key, value));
+            assert_eq!(task.digest(), expected);
+        }
+    }
+
+    #[test]
+    fn env_order() {
+        let env = [("A", "1"), ("B", "2"), ("PATH", "/bin"), ("HOME", "/root")];
+        let expected = Executor::builder("ubuntu", ["env"])
+            .env("A", "1")
+            .env("B", "2")
+            .env("PATH", "/bin")
+            .env("HOME", "/root")
+            .try_build()
+            .unwrap();
+        let expected = Task {
+            executors: vec![expected],
+            ..Default::default()
+        }
+        .digest();
+
+        for permutation in permutations(&env) {
+            let executor = permutation
+                .into_iter()
+                .fold(
+                    Executor::builder("ubuntu", ["env"]),
+                    |builder, (name, value)| builder.env(name, value),
+                )
+                .try_build()
+                .unwrap();
+            let task = Task {
+                executors: vec![executor],
+                ..Default::default()
+            };
+
+            assert_eq!(task.digest(), expected);
+        }
+    }
+
+    #[test]
+    fn covered_fields() {
+        let expected = task().digest();
+
+        // NOTE: `null` and missing fields are the same.
+        let task: Task = serde_json::from_str(
+            r#"{"name":"my-task","description":null,"executors":[{"image":"ubuntu","command":["echo","hello"],"env":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(task.digest(), expected);
+
+        let mut assigned = task.clone();
+        assigned.id = Some(String::from("task-1"));
+        assigned.state = Some(crate::v1::types::task::State::Complete);
+        assigned.logs = Some(vec![TaskLog::default()]);
+        assigned.creation_time = Some(chrono::Utc::now());
+        assert_eq!(assigned.digest(), expected);
+
+        let tagged = task.clone().tag("run_id", "run-1");
+        assert_ne!(tagged.digest(), expected);
+        assert_eq!(
+            tagged.digest_with(&Options::default().exclude_tags(true)),
+            expected
+        );
+
+        // NOTE: empty and missing lists are different.
+        let mut volumes = task.clone();
+        volumes.volumes = Some(Vec::new());
+        assert_ne!(volumes.digest(), expected);
+
+        let mut executors = task.clone();
+        executors.executors.push(executors.executors[0].clone());
+        assert_ne!(executors.digest(), expected);
+
+        let mut command = task;
+        command.executors[0].command.reverse();
+        assert_ne!(command.digest(), expected);
+    }
+}
@@ -8,6 +8,31 @@ use std::collections::HashMap;
 use chrono::DateTime;
 use chrono::Utc;
 
+pub mod log;
+
+/// A shell that runs the script of an executor.
+///
+/// See [`Executor::from_shell_with()`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Shell {
+    /// The Bourne shell (`/bin/sh`).
+    Sh,
+
+    /// Bash (`/bin/bash`).
+    #[default]
+    Bash,
+}
+
+impl Shell {
+    /// Gets the path of the shell within the container.
+    pub fn path(&self) -> &'static str {
+        match self {
+            Shell::Sh => "/bin/sh",
+            Shell::Bash => "/bin/bash",
+        }
+    }
+}
+
 /// An executor.
 ///
 /// In short, an executor is a single command that is run in a different
@@ -37,12 +62,335 @@ pub struct Executor {
 
     /// The environment variables.
     #[cfg(not(feature = "ord"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::v1::types::sorted::serialize")
+    )]
     pub env: Option<HashMap<String, String>>,
     /// The environment variables.
     #[cfg(feature = "ord")]
     pub env: Option<BTreeMap<String, String>>,
 }
 
+impl Executor {
+    /// Creates a new executor that runs `command` within `image`.
+    ///
+    /// Services reject executors without an image or a command, so both must
+    /// be non-empty:
+    ///
+    /// ```
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let executor = Executor::new("ubuntu:latest", ["echo", "hello"])?;
+    /// assert_eq!(executor.command, ["echo", "hello"]);
+    ///
+    /// assert!(Executor::new("ubuntu:latest", Vec::<String>::new()).is_err());
+    /// # Ok::<(), tes::v1::types::task::executor::Error>(())
+    /// ```
+    pub fn new<I>(image: impl Into<String>, command: I) -> Result<Self>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self::builder(image, command).try_build()
+    }
+
+    /// Gets a builder for an executor that runs `command` within `image`.
+    ///
+    /// The builder sets the optional fields of the executor:
+    ///
+    /// ```
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let executor = Executor::builder("ubuntu:latest", ["sh", "-c", "echo $GREETING"])
+    ///     .workdir("/work")
+    ///     .stdout("/outputs/stdout.txt")
+    ///     .env("GREETING", "hello")
+    ///     .try_build()?;
+    /// assert_eq!(executor.workdir.as_deref(), Some("/work"));
+    ///
+    /// assert!(
+    ///     Executor::builder("ubuntu:latest", ["true"])
+    ///         .stdout("stdout.txt")
+    ///         .try_build()
+    ///         .is_err()
+    /// );
+    /// # Ok::<(), tes::v1::types::task::executor::Error>(())
+    /// ```
+    pub fn builder<I>(image: impl Into<String>, command: I) -> Builder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Builder {
+            executor: Executor {
+                image: image.into(),
+                command: command.into_iter().map(Into::into).collect(),
+                ..Default::default()
+            },
+            any_env_names: false,
+        }
+    }
+
+    /// Gets a builder for an executor that runs `script` with Bash within
+    /// `image`.
+    ///
+    /// The command of the executor is `["/bin/bash", "-c", script]`:
+    ///
+    /// ```
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let executor = Executor::from_shell("ubuntu:latest", "echo $GREETING > greeting.txt")
+    ///     .workdir("/outputs")
+    ///     .env("GREETING", "hello")
+    ///     .try_build()?;
+    /// assert_eq!(
+    ///     executor.command,
+    ///     ["/bin/bash", "-c", "echo $GREETING > greeting.txt"]
+    /// );
+    /// # Ok::<(), tes::v1::types::task::executor::Error>(())
+    /// ```
+    ///
+    /// See [`Executor::from_shell_with()`] to use a different shell.
+    pub fn from_shell(image: impl Into<String>, script: impl Into<String>) -> Builder {
+        Self::from_shell_with(image, Shell::Bash, script)
+    }
+
+    /// Gets a builder for an executor that runs `script` with `shell` within
+    /// `image`.
+    ///
+    /// The command of the executor is `[shell, "-c", script]` (see
+    /// [`Shell::path()`]). Images without Bash (e.g., `alpine`) commonly
+    /// still have a Bourne shell.
+    pub fn from_shell_with(
+        image: impl Into<String>,
+        shell: Shell,
+        script: impl Into<String>,
+    ) -> Builder {
+        Self::builder(
+            image,
+            [shell.path().to_string(), String::from("-c"), script.into()],
+        )
+    }
+
+    /// Gets a builder for an executor that runs the program and arguments in
+    /// `args` directly (without a shell) within `image`.
+    ///
+    /// ```
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let executor = Executor::from_args("ubuntu:latest", ["md5sum", "/inputs/a.txt"])
+    ///     .stdout("/outputs/md5.txt")
+    ///     .try_build()?;
+    /// assert_eq!(executor.command, ["md5sum", "/inputs/a.txt"]);
+    /// # Ok::<(), tes::v1::types::task::executor::Error>(())
+    /// ```
+    ///
+    /// This is the same as [`Executor::builder()`].
+    pub fn from_args<I>(image: impl Into<String>, args: I) -> Builder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self::builder(image, args)
+    }
+}
+
+/// An error related to an executor [`Builder`].
+///
+/// More validations may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The image is empty.
+    EmptyImage,
+
+    /// The command is empty.
+    EmptyCommand,
+
+    /// A path within the container is not absolute.
+    RelativePath {
+        /// The field with the path (e.g., `workdir`).
+        field: &'static str,
+
+        /// The path.
+        path: String,
+    },
+
+    /// The name of an environment variable is empty or not portable (see
+    /// [`is_portable_env_name()`]).
+    InvalidEnvName(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::EmptyImage => write!(f, "an executor requires a non-empty image"),
+            Error::EmptyCommand => write!(f, "an executor requires a non-empty command"),
+            Error::RelativePath { field, path } => write!(
+                f,
+                "the {field} of an executor must be an absolute path, but `{path}` is relative"
+            ),
+            Error::InvalidEnvName(name) if name.is_empty() => {
+                write!(f, "the name of an environment variable must not be empty")
+            }
+            Error::InvalidEnvName(name) => write!(
+                f,
+                "invalid environment variable name `{name}` (expected letters, digits, and \
+                 underscores, not starting with a digit)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A [`Result`](std::result::Result) with an [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Returns whether the name of an environment variable is portable.
+///
+/// A portable name matches `[A-Za-z_][A-Za-z0-9_]*`, which every container
+/// runtime accepts.
+pub fn is_portable_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A builder for an [`Executor`].
+///
+/// See [`Executor::builder()`].
+#[derive(Debug)]
+pub struct Builder {
+    /// The executor being built.
+    executor: Executor,
+
+    /// Whether environment variables may have names that are not portable.
+    any_env_names: bool,
+}
+
+impl Builder {
+    /// Sets the working directory of the executor.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set working directory.
+    pub fn workdir(mut self, value: impl Into<String>) -> Self {
+        self.executor.workdir = Some(value.into());
+        self
+    }
+
+    /// Sets the path from which to pipe the standard input stream.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set path.
+    pub fn stdin(mut self, value: impl Into<String>) -> Self {
+        self.executor.stdin = Some(value.into());
+        self
+    }
+
+    /// Sets the path to pipe the standard output stream to.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set path.
+    pub fn stdout(mut self, value: impl Into<String>) -> Self {
+        self.executor.stdout = Some(value.into());
+        self
+    }
+
+    /// Sets the path to pipe the standard error stream to.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set path.
+    pub fn stderr(mut self, value: impl Into<String>) -> Self {
+        self.executor.stderr = Some(value.into());
+        self
+    }
+
+    /// Adds an environment variable.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set value for the variable.
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.executor
+            .env
+            .get_or_insert_with(Default::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets whether environment variables may have names that are not
+    /// portable (see [`is_portable_env_name()`]).
+    ///
+    /// This is an escape hatch for services whose backends accept such names.
+    /// Empty names are always rejected.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, only portable
+    /// names are accepted.
+    pub fn allow_any_env_names(mut self, value: bool) -> Self {
+        self.any_env_names = value;
+        self
+    }
+
+    /// Consumes `self` to attempt to build an [`Executor`].
+    ///
+    /// The image and the command must be non-empty, the working directory
+    /// and the paths of the streams must be absolute, and the names of the
+    /// environment variables must be portable (unless
+    /// [`Self::allow_any_env_names()`] is set).
+    pub fn try_build(self) -> Result<Executor> {
+        let executor = self.executor;
+
+        if executor.image.is_empty() {
+            return Err(Error::EmptyImage);
+        }
+
+        if executor.command.is_empty() {
+            return Err(Error::EmptyCommand);
+        }
+
+        for (field, path) in [
+            ("working directory", &executor.workdir),
+            ("standard input", &executor.stdin),
+            ("standard output", &executor.stdout),
+            ("standard error", &executor.stderr),
+        ] {
+            if let Some(path) = path.as_ref().filter(|path| !path.starts_with('/')) {
+                return Err(Error::RelativePath {
+                    field,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        // NOTE: the first invalid name in sorted order is reported so that
+        // the error is deterministic.
+        let name = executor
+            .env
+            .iter()
+            .flatten()
+            .map(|(name, _)| name)
+            .filter(|name| name.is_empty() || (!self.any_env_names && !is_portable_env_name(name)))
+            .min();
+        if let Some(name) = name {
+            return Err(Error::InvalidEnvName(name.clone()));
+        }
+
+        Ok(executor)
+    }
+}
+
 /// A log for an [`Executor`].
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -63,3 +411,186 @@ pub struct Log {
     /// The exit code.
     pub exit_code: Option<u32>,
 }
+
+impl Log {
+    /// Gets how long the executor ran (if both its start time and its end
+    /// time are known).
+    ///
+    /// An end time before the start time (e.g., because of clock skew between
+    /// workers) results in a duration of zero.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        super::elapsed(self.start_time?, self.end_time?)
+    }
+
+    /// Gets an empty builder for a [`Log`].
+    ///
+    /// See [`log::Builder`].
+    pub fn builder() -> log::Builder {
+        log::Builder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn executors() {
+        let executor = Executor::builder("ubuntu", ["cat"])
+            .workdir("/work")
+            .stdin("/inputs/stdin.txt")
+            .stdout("/outputs/stdout.txt")
+            .stderr("/outputs/stderr.txt")
+            .env("A", "1")
+            .env("A", "2")
+            .try_build()
+            .unwrap();
+
+        assert_eq!(executor.image, "ubuntu");
+        assert_eq!(executor.command, ["cat"]);
+        assert_eq!(executor.stdin.as_deref(), Some("/inputs/stdin.txt"));
+        assert_eq!(executor.stderr.as_deref(), Some("/outputs/stderr.txt"));
+        assert_eq!(executor.env.unwrap()["A"], "2");
+    }
+
+    #[test]
+    fn shell_commands() {
+        let executor = Executor::from_shell("ubuntu", "echo 'hello, world!'")
+            .try_build()
+            .unwrap();
+        assert_eq!(
+            executor.command,
+            ["/bin/bash", "-c", "echo 'hello, world!'"]
+        );
+
+        let executor = Executor::from_shell_with("alpine", Shell::Sh, "ls | wc -l")
+            .workdir("/data")
+            .env("LC_ALL", "C")
+            .try_build()
+            .unwrap();
+        assert_eq!(executor.image, "alpine");
+        assert_eq!(executor.command, ["/bin/sh", "-c", "ls | wc -l"]);
+        assert_eq!(executor.workdir.as_deref(), Some("/data"));
+        assert_eq!(executor.env.unwrap()["LC_ALL"], "C");
+
+        // NOTE: an empty script is still a (valid) command.
+        let executor = Executor::from_shell("ubuntu", "").try_build().unwrap();
+        assert_eq!(executor.command, ["/bin/bash", "-c", ""]);
+    }
+
+    #[test]
+    fn exec_commands() {
+        let executor = Executor::from_args("ubuntu", vec![String::from("ls"), String::from("-l")])
+            .try_build()
+            .unwrap();
+        assert_eq!(executor.command, ["ls", "-l"]);
+
+        let executor = Executor::from_args("ubuntu", ["echo", "$HOME"].iter().copied())
+            .try_build()
+            .unwrap();
+        assert_eq!(executor.command, ["echo", "$HOME"]);
+
+        assert!(matches!(
+            Executor::from_args("ubuntu", std::iter::empty::<&str>()).try_build(),
+            Err(Error::EmptyCommand)
+        ));
+    }
+
+    #[test]
+    fn empty_images_and_commands() {
+        assert!(matches!(
+            Executor::new("", ["true"]),
+            Err(Error::EmptyImage)
+        ));
+        assert!(matches!(
+            Executor::new("ubuntu", Vec::<String>::new()),
+            Err(Error::EmptyCommand)
+        ));
+        assert_eq!(
+            Error::EmptyCommand.to_string(),
+            "an executor requires a non-empty command"
+        );
+    }
+
+    #[test]
+    fn env_names() {
+        for name in ["HOME", "_private", "a1_B2"] {
+            assert!(is_portable_env_name(name), "{name}");
+            Executor::builder("ubuntu", ["env"])
+                .env(name, "= any value at all")
+                .try_build()
+                .unwrap();
+        }
+
+        for name in ["", "1ST", "A=B", "MY VAR", "my-var", "caf\u{e9}"] {
+            assert!(!is_portable_env_name(name), "{name}");
+            assert!(matches!(
+                Executor::builder("ubuntu", ["env"]).env(name, "").try_build(),
+                Err(Error::InvalidEnvName(n)) if n == name
+            ));
+        }
+
+        // NOTE: the first invalid name in sorted order is reported.
+        for _ in 0..8 {
+            assert!(matches!(
+                Executor::builder("ubuntu", ["env"])
+                    .env("my-var", "")
+                    .env("HOME", "")
+                    .env("1ST", "")
+                    .env("A=B", "")
+                    .try_build(),
+                Err(Error::InvalidEnvName(n)) if n == "1ST"
+            ));
+        }
+
+        // NOTE: the escape hatch allows any non-empty name.
+        let executor = Executor::builder("ubuntu", ["env"])
+            .env("my-var", "1")
+            .allow_any_env_names(true)
+            .try_build()
+            .unwrap();
+        assert_eq!(executor.env.unwrap()["my-var"], "1");
+        assert!(matches!(
+            Executor::builder("ubuntu", ["env"])
+                .env("", "1")
+                .allow_any_env_names(true)
+                .try_build(),
+            Err(Error::InvalidEnvName(_))
+        ));
+
+        assert_eq!(
+            Error::InvalidEnvName(String::from("A=B")).to_string(),
+            "invalid environment variable name `A=B` (expected letters, digits, and underscores, \
+             not starting with a digit)"
+        );
+    }
+
+    #[test]
+    fn relative_paths() {
+        let builder = || Executor::builder("ubuntu", ["true"]);
+
+        for (result, expected) in [
+            (builder().workdir("work").try_build(), "working directory"),
+            (builder().stdin("stdin.txt").try_build(), "standard input"),
+            (
+                builder().stdout("./stdout.txt").try_build(),
+                "standard output",
+            ),
+            (builder().stderr("stderr.txt").try_build(), "standard error"),
+        ] {
+            match result {
+                Err(Error::RelativePath { field, .. }) => assert_eq!(field, expected),
+                result => panic!("unexpected result: {result:?}"),
+            }
+        }
+
+        assert_eq!(
+            builder()
+                .workdir("work")
+                .try_build()
+                .unwrap_err()
+                .to_string(),
+            "the working directory of an executor must be an absolute path, but `work` is relative"
+        );
+    }
+}
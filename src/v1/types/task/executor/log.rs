@@ -0,0 +1,87 @@
+//! Building the logs of executors.
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::v1::types::task::executor::Log;
+
+/// A builder for an executor [`Log`].
+///
+/// The exit code is unknown (`None`) until it is set, as it is for an
+/// executor that has not finished:
+///
+/// ```
+/// use chrono::Utc;
+/// use tes::v1::types::task::executor;
+///
+/// let log = executor::Log::builder()
+///     .start_time(Utc::now())
+///     .stdout("hello\n")
+///     .build();
+/// assert_eq!(log.exit_code, None);
+///
+/// let log = executor::Log::builder().exit_code(1).stderr("oops\n").build();
+/// assert_eq!(log.exit_code, Some(1));
+/// ```
+#[derive(Debug, Default)]
+pub struct Builder {
+    /// The log being built.
+    log: Log,
+}
+
+impl Builder {
+    /// Sets the time the executor started.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set start time.
+    pub fn start_time(mut self, value: DateTime<Utc>) -> Self {
+        self.log.start_time = Some(value);
+        self
+    }
+
+    /// Sets the time the executor ended.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set end time.
+    pub fn end_time(mut self, value: DateTime<Utc>) -> Self {
+        self.log.end_time = Some(value);
+        self
+    }
+
+    /// Sets the value of the standard output stream.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set value.
+    pub fn stdout(mut self, value: impl Into<String>) -> Self {
+        self.log.stdout = Some(value.into());
+        self
+    }
+
+    /// Sets the value of the standard error stream.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set value.
+    pub fn stderr(mut self, value: impl Into<String>) -> Self {
+        self.log.stderr = Some(value.into());
+        self
+    }
+
+    /// Sets the exit code.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set exit code.
+    pub fn exit_code(mut self, value: u32) -> Self {
+        self.log.exit_code = Some(value);
+        self
+    }
+
+    /// Consumes `self` to build an executor [`Log`].
+    pub fn build(self) -> Log {
+        self.log
+    }
+}
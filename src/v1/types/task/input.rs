@@ -0,0 +1,150 @@
+//! Inputs declared within tasks.
+
+use std::io::Read as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The maximum size (in bytes) of a local file inlined as the content of an
+/// input by default.
+///
+/// The specification asks implementations to support content of at least 128
+/// KiB, and servers commonly cap the size of request bodies.
+pub const DEFAULT_CONTENT_LIMIT: u64 = 128 * 1024;
+
+/// Where the content of an input comes from.
+///
+/// See [`Input::source()`](super::Input::source).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputSource<'a> {
+    /// The content is fetched from a URL.
+    Url(&'a str),
+
+    /// The content is provided literally.
+    Content(&'a str),
+}
+
+/// An error constructing an input.
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The local file could not be read.
+    Io {
+        /// The path of the local file.
+        path: PathBuf,
+
+        /// The underlying error.
+        source: std::io::Error,
+    },
+
+    /// The local file is larger than the limit.
+    TooLarge {
+        /// The path of the local file.
+        path: PathBuf,
+
+        /// The limit (in bytes).
+        limit: u64,
+    },
+
+    /// The local file is not valid UTF-8.
+    ///
+    /// The content of an input is a string literal, so binary files must be
+    /// staged to storage and referenced by URL instead.
+    NotUtf8(PathBuf),
+
+    /// The input already has a URL (an input cannot have both a URL and
+    /// content).
+    UrlAndContent {
+        /// The path of the input within the container.
+        path: String,
+    },
+
+    /// The input is a directory (a directory cannot have content).
+    DirectoryContent {
+        /// The path of the input within the container.
+        path: String,
+    },
+
+    /// The path of the input within the container is not absolute.
+    RelativePath(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(
+                    f,
+                    "failed to read `{path}`: {source}",
+                    path = path.display()
+                )
+            }
+            Error::TooLarge { path, limit } => write!(
+                f,
+                "`{path}` is larger than the limit of {limit} bytes for input content",
+                path = path.display()
+            ),
+            Error::NotUtf8(path) => write!(
+                f,
+                "`{path}` is not valid UTF-8 and cannot be used as input content",
+                path = path.display()
+            ),
+            Error::UrlAndContent { path } => {
+                write!(f, "input `{path}` has a URL and cannot also have content")
+            }
+            Error::DirectoryContent { path } => {
+                write!(f, "input `{path}` is a directory and cannot have content")
+            }
+            Error::RelativePath(path) => write!(
+                f,
+                "the path of an input must be an absolute path, but `{path}` is relative"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that the path of an input within the container is absolute.
+pub(crate) fn check_path(path: String) -> Result<String, Error> {
+    if path.starts_with('/') {
+        Ok(path)
+    } else {
+        Err(Error::RelativePath(path))
+    }
+}
+
+/// Reads a local file as a string, failing if it exceeds `limit` bytes.
+pub(crate) fn read(path: &Path, limit: u64) -> Result<String, Error> {
+    let io = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    // NOTE: reading one byte past the limit detects larger files without
+    // reading them entirely (and regardless of their reported size).
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)
+        .map_err(io)?
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(io)?;
+
+    if bytes.len() as u64 > limit {
+        return Err(Error::TooLarge {
+            path: path.to_path_buf(),
+            limit,
+        });
+    }
+
+    String::from_utf8(bytes).map_err(|_| Error::NotUtf8(path.to_path_buf()))
+}
@@ -0,0 +1,87 @@
+//! Building the logs of the attempts of tasks.
+
+use chrono::DateTime;
+use chrono::Utc;
+
+use crate::v1::types::task::OutputFileLog;
+use crate::v1::types::task::TaskLog;
+use crate::v1::types::task::executor;
+
+/// A builder for a [`TaskLog`].
+///
+/// ```
+/// use chrono::Utc;
+/// use tes::v1::types::task::OutputFileLog;
+/// use tes::v1::types::task::TaskLog;
+/// use tes::v1::types::task::executor;
+///
+/// let log = TaskLog::builder()
+///     .start_time(Utc::now())
+///     .executor_log(executor::Log::builder().exit_code(0).build())
+///     .output(OutputFileLog {
+///         url: String::from("s3://bucket/a.txt"),
+///         path: String::from("/outputs/a.txt"),
+///         size_bytes: String::from("1024"),
+///     })
+///     .system_log("pulled image ubuntu")
+///     .end_time(Utc::now())
+///     .build();
+///
+/// assert_eq!(log.logs.len(), 1);
+/// assert_eq!(log.system_logs.unwrap(), ["pulled image ubuntu"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Builder {
+    /// The log being built.
+    log: TaskLog,
+}
+
+impl Builder {
+    /// Adds the log of an executor.
+    ///
+    /// The logs of executors are in the order of the executors.
+    pub fn executor_log(mut self, value: executor::Log) -> Self {
+        self.log.logs.push(value);
+        self
+    }
+
+    /// Sets the time the attempt started.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set start time.
+    pub fn start_time(mut self, value: DateTime<Utc>) -> Self {
+        self.log.start_time = Some(value);
+        self
+    }
+
+    /// Sets the time the attempt ended.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set end time.
+    pub fn end_time(mut self, value: DateTime<Utc>) -> Self {
+        self.log.end_time = Some(value);
+        self
+    }
+
+    /// Adds the log of an output file.
+    pub fn output(mut self, value: OutputFileLog) -> Self {
+        self.log.outputs.get_or_insert_with(Vec::new).push(value);
+        self
+    }
+
+    /// Adds a system log.
+    pub fn system_log(mut self, value: impl Into<String>) -> Self {
+        self.log
+            .system_logs
+            .get_or_insert_with(Vec::new)
+            .push(value.into());
+        self
+    }
+
+    /// Consumes `self` to build a [`TaskLog`].
+    pub fn build(self) -> TaskLog {
+        self.log
+    }
+}
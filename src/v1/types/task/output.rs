@@ -0,0 +1,72 @@
+//! Outputs declared within tasks.
+
+/// The characters that make a path a wildcard pattern.
+const WILDCARDS: &[char] = &['*', '?', '['];
+
+/// An error constructing an output.
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The path of the output within the container is not absolute.
+    RelativePath(String),
+
+    /// The path of the output contains wildcards, but there is no path prefix.
+    ///
+    /// See [`Output::glob()`](super::Output::glob).
+    MissingPathPrefix(String),
+
+    /// The path prefix of the output is not a prefix of its pattern (or
+    /// contains wildcards itself).
+    InvalidPathPrefix {
+        /// The pattern.
+        pattern: String,
+
+        /// The path prefix.
+        prefix: String,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::RelativePath(path) => write!(
+                f,
+                "the path of an output must be an absolute path, but `{path}` is relative"
+            ),
+            Error::MissingPathPrefix(path) => write!(
+                f,
+                "the path `{path}` of an output contains wildcards and requires a path prefix"
+            ),
+            Error::InvalidPathPrefix { pattern, prefix } => write!(
+                f,
+                "`{prefix}` is not a valid path prefix for the output pattern `{pattern}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Returns whether a path contains wildcards.
+pub(crate) fn has_wildcards(path: &str) -> bool {
+    path.contains(WILDCARDS)
+}
+
+/// Checks the path of an output within the container.
+///
+/// The path must be absolute and, unless it is a pattern, it must not contain
+/// wildcards.
+pub(crate) fn check_path(path: String, pattern: bool) -> Result<String, Error> {
+    if !path.starts_with('/') {
+        return Err(Error::RelativePath(path));
+    }
+
+    if !pattern && has_wildcards(&path) {
+        return Err(Error::MissingPathPrefix(path));
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,237 @@
+//! Redaction of sensitive values within tasks.
+//!
+//! See [`Task::redacted()`].
+
+use crate::v1::types::Task;
+
+/// The markers of the names of secret environment variables by default.
+pub const DEFAULT_SECRET_MARKERS: &[&str] = &["TOKEN", "SECRET", "PASSWORD", "KEY"];
+
+/// The value that replaces the values of secret environment variables.
+pub const REDACTED_ENV_VALUE: &str = "***";
+
+/// Options for redacting a task.
+///
+/// See [`Task::redacted_with()`].
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The (uppercase) markers of the names of secret environment variables.
+    markers: Vec<String>,
+}
+
+impl Options {
+    /// Sets the markers of the names of secret environment variables.
+    ///
+    /// An environment variable is secret if its name contains any of the
+    /// markers (ignoring case).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set markers. By default, the
+    /// markers are [`DEFAULT_SECRET_MARKERS`].
+    pub fn secret_markers<I>(mut self, markers: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.markers = markers
+            .into_iter()
+            .map(|marker| marker.as_ref().to_ascii_uppercase())
+            .collect();
+        self
+    }
+
+    /// Returns whether an environment variable is secret.
+    fn is_secret(&self, name: &str) -> bool {
+        let name = name.to_ascii_uppercase();
+        self.markers.iter().any(|marker| name.contains(marker))
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            markers: DEFAULT_SECRET_MARKERS
+                .iter()
+                .map(|marker| marker.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Gets the placeholder for content of `len` bytes.
+fn placeholder(len: usize) -> String {
+    format!("<redacted: {len} bytes>")
+}
+
+impl Task {
+    /// Gets a copy of the task that is safe to log.
+    ///
+    /// The content of every input is replaced with a placeholder noting its
+    /// original size, and the values of environment variables whose names
+    /// contain any of the [`DEFAULT_SECRET_MARKERS`] (ignoring case) are
+    /// replaced with [`REDACTED_ENV_VALUE`]. Everything else is kept, so the
+    /// redacted task still serializes as a task.
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    /// use tes::v1::types::task::Input;
+    ///
+    /// let task = Task {
+    ///     inputs: Some(vec![Input::from_content(
+    ///         "hunter2",
+    ///         "/inputs/password.txt",
+    ///     )?]),
+    ///     executors: vec![
+    ///         Executor::builder("ubuntu", ["env"])
+    ///             .env("API_TOKEN", "abc123")
+    ///             .env("THREADS", "4")
+    ///             .try_build()?,
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let redacted = task.redacted();
+    /// let inputs = redacted.inputs.as_ref().unwrap();
+    /// assert_eq!(inputs[0].content.as_deref(), Some("<redacted: 7 bytes>"));
+    ///
+    /// let env = redacted.executors[0].env.as_ref().unwrap();
+    /// assert_eq!(env["API_TOKEN"], "***");
+    /// assert_eq!(env["THREADS"], "4");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// See [`Task::redacted_with()`] to choose which environment variables
+    /// are secret.
+    pub fn redacted(&self) -> Task {
+        self.redacted_with(&Options::default())
+    }
+
+    /// Gets a copy of the task that is safe to log with options.
+    ///
+    /// See [`Task::redacted()`].
+    pub fn redacted_with(&self, options: &Options) -> Task {
+        let mut task = self.clone();
+
+        for input in task.inputs.iter_mut().flatten() {
+            if let Some(content) = &mut input.content {
+                *content = placeholder(content.len());
+            }
+        }
+
+        for executor in &mut task.executors {
+            for (name, value) in executor.env.iter_mut().flatten() {
+                if options.is_secret(name) {
+                    *value = String::from(REDACTED_ENV_VALUE);
+                }
+            }
+        }
+
+        task
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::v1::types::task::Executor;
+    use crate::v1::types::task::Input;
+
+    /// Gets a task with secrets.
+    fn task() -> Task {
+        Task {
+            name: Some(String::from("my-task")),
+            inputs: Some(vec![
+                Input::from_content("patient: Jane Doe", "/inputs/record.txt").unwrap(),
+                Input::from_url("s3://bucket/a.txt", "/inputs/a.txt").unwrap(),
+                Input::from_content("", "/inputs/empty.txt").unwrap(),
+            ]),
+            executors: vec![
+                Executor::builder("ubuntu", ["env"])
+                    .env("GITHUB_TOKEN", "ghp_abc")
+                    .env("aws_secret_access_key", "wJalr")
+                    .env("DB_PASSWORD", "hunter2")
+                    .env("HOME", "/root")
+                    .env("MONKEY", "banana")
+                    .try_build()
+                    .unwrap(),
+                Executor::new("ubuntu", ["true"]).unwrap(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_markers() {
+        let task = task();
+        let redacted = task.redacted();
+
+        let contents = redacted
+            .inputs
+            .iter()
+            .flatten()
+            .map(|input| input.content.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contents,
+            [
+                Some("<redacted: 17 bytes>"),
+                None,
+                Some("<redacted: 0 bytes>")
+            ]
+        );
+
+        let env = redacted.executors[0].env.as_ref().unwrap();
+        assert_eq!(env["GITHUB_TOKEN"], "***");
+        assert_eq!(env["aws_secret_access_key"], "***");
+        assert_eq!(env["DB_PASSWORD"], "***");
+        assert_eq!(env["HOME"], "/root");
+        // NOTE: markers match anywhere within the name.
+        assert_eq!(env["MONKEY"], "***");
+        assert_eq!(env.len(), 5);
+
+        // NOTE: the structure is otherwise unchanged.
+        assert_eq!(redacted.name, task.name);
+        assert_eq!(
+            redacted.inputs.as_ref().unwrap()[1],
+            task.inputs.unwrap()[1]
+        );
+        assert_eq!(redacted.executors[1], task.executors[1]);
+        assert_eq!(redacted.executors[0].command, task.executors[0].command);
+    }
+
+    #[test]
+    fn custom_markers() {
+        let options = Options::default().secret_markers(["password", "home"]);
+        let redacted = task().redacted_with(&options);
+
+        let env = redacted.executors[0].env.as_ref().unwrap();
+        assert_eq!(env["GITHUB_TOKEN"], "ghp_abc");
+        assert_eq!(env["aws_secret_access_key"], "wJalr");
+        assert_eq!(env["DB_PASSWORD"], "***");
+        assert_eq!(env["HOME"], "***");
+        assert_eq!(env["MONKEY"], "banana");
+
+        // NOTE: content is always redacted.
+        assert_eq!(
+            redacted.inputs.unwrap()[0].content.as_deref(),
+            Some("<redacted: 17 bytes>")
+        );
+
+        let options = Options::default().secret_markers(std::iter::empty::<&str>());
+        let redacted = task().redacted_with(&options);
+        assert_eq!(redacted.executors, task().executors);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializing_redacted_tasks() {
+        let redacted = task().redacted();
+        let json = serde_json::to_string(&redacted).unwrap();
+
+        assert!(!json.contains("Jane Doe"));
+        assert!(!json.contains("hunter2"));
+        assert_eq!(serde_json::from_str::<Task>(&json).unwrap(), redacted);
+    }
+}
@@ -0,0 +1,510 @@
+//! Resources requested by tasks.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ordered_float::OrderedFloat;
+
+use crate::v1::types::task::Resources;
+use crate::v1::types::task::resources::private::Integer;
+
+pub mod backend;
+
+/// The number of bytes within a (decimal) gigabyte.
+const BYTES_PER_GB: f64 = 1e9;
+
+/// An amount of memory or disk space.
+///
+/// The specification requests resources in decimal gigabytes (10^9 bytes), so
+/// amounts in binary units are converted to decimal gigabytes:
+///
+/// ```
+/// use tes::v1::types::task::resources::Size;
+///
+/// assert_eq!(Size::gb(8).as_gb(), 8.0);
+/// assert_eq!(Size::gib(8).as_gb(), 8.589934592);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Size(f64);
+
+impl Size {
+    /// Creates a size from a number of decimal gigabytes (10^9 bytes).
+    pub fn gb(value: u64) -> Self {
+        Self::bytes(value as f64 * 1e9)
+    }
+
+    /// Creates a size from a number of decimal megabytes (10^6 bytes).
+    pub fn mb(value: u64) -> Self {
+        Self::bytes(value as f64 * 1e6)
+    }
+
+    /// Creates a size from a number of gibibytes (2^30 bytes).
+    pub fn gib(value: u64) -> Self {
+        Self::bytes(value as f64 * (1u64 << 30) as f64)
+    }
+
+    /// Creates a size from a number of mebibytes (2^20 bytes).
+    pub fn mib(value: u64) -> Self {
+        Self::bytes(value as f64 * (1u64 << 20) as f64)
+    }
+
+    /// Creates a size from a number of bytes.
+    fn bytes(value: f64) -> Self {
+        Self(value / BYTES_PER_GB)
+    }
+
+    /// Gets the size in decimal gigabytes.
+    pub fn as_gb(&self) -> f64 {
+        self.0
+    }
+}
+
+/// Sealed traits.
+mod private {
+    /// An integer that can be inserted as a backend parameter.
+    ///
+    /// This trait is sealed so that only the primitive integer types implement
+    /// it.
+    pub trait Integer: std::fmt::Display {}
+
+    /// Implements [`Integer`] for primitive integer types.
+    macro_rules! integers {
+        ($($ty:ty),*) => {
+            $(impl Integer for $ty {})*
+        };
+    }
+
+    integers!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+    );
+}
+
+/// Parameters passed to the backend of a service.
+///
+/// The specification defines backend parameters as strings, so typed values
+/// are converted to and from their string forms:
+///
+/// ```
+/// use tes::v1::types::task::resources::BackendParameters;
+/// use tes::v1::types::task::resources::backend::microsoft;
+///
+/// let mut parameters = BackendParameters::default();
+/// parameters.insert_str(microsoft::VM_SIZE, "Standard_D2s_v3");
+/// parameters.insert_bool("docker_cleanup", true);
+/// parameters.insert_int("retries", 2);
+/// parameters.insert_number("ratio", 0.5);
+///
+/// assert_eq!(parameters.get(microsoft::VM_SIZE), Some("Standard_D2s_v3"));
+/// assert_eq!(parameters.get_as::<bool>("docker_cleanup"), Some(Ok(true)));
+/// assert_eq!(parameters.get_as::<u32>("retries"), Some(Ok(2)));
+/// assert_eq!(parameters.get_as::<f64>("ratio"), Some(Ok(0.5)));
+/// ```
+///
+/// The parameters are (de)serialized as a plain map.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[cfg_attr(feature = "ord", derive(Ord, PartialOrd))]
+pub struct BackendParameters(BTreeMap<String, String>);
+
+impl BackendParameters {
+    /// Inserts a string parameter, returning the previous value (if any).
+    pub fn insert_str(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Inserts a boolean parameter (as `true` or `false`), returning the
+    /// previous value (if any).
+    pub fn insert_bool(&mut self, key: impl Into<String>, value: bool) -> Option<String> {
+        self.insert_str(key, value.to_string())
+    }
+
+    /// Inserts an integer parameter, returning the previous value (if any).
+    ///
+    /// Any primitive integer type is accepted (e.g., a `u64` disk size or a
+    /// `usize` count), and the value is inserted exactly.
+    pub fn insert_int(&mut self, key: impl Into<String>, value: impl Integer) -> Option<String> {
+        self.insert_str(key, value.to_string())
+    }
+
+    /// Inserts a floating-point parameter, returning the previous value (if
+    /// any).
+    ///
+    /// Whole numbers are inserted without a fractional part (e.g., `2` rather
+    /// than `2.0`). Use [`Self::insert_int()`] for integers, which may lose
+    /// precision as a floating-point number.
+    pub fn insert_number(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<f64>,
+    ) -> Option<String> {
+        self.insert_str(key, value.into().to_string())
+    }
+
+    /// Gets the value of a parameter.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Gets the value of a parameter parsed as a `T`.
+    ///
+    /// Returns `None` if the parameter is not present.
+    pub fn get_as<T: FromStr>(&self, key: &str) -> Option<std::result::Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    /// Removes a parameter, returning its value (if it was present).
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    /// Gets an iterator over the parameters (ordered by key).
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Gets the number of parameters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<BTreeMap<String, String>> for BackendParameters {
+    fn from(value: BTreeMap<String, String>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<BackendParameters> for BTreeMap<String, String> {
+    fn from(value: BackendParameters) -> Self {
+        value.0
+    }
+}
+
+/// An error related to a resources [`Builder`].
+///
+/// More validations may be added in future releases, so matches on this error
+/// should include a wildcard (`_`) arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The number of CPU cores is not positive.
+    InvalidCpuCores(i64),
+
+    /// A zone is empty.
+    EmptyZone,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidCpuCores(cores) => write!(
+                f,
+                "invalid number of CPU cores ({cores}) in a resources builder: the number of CPU \
+                 cores must be positive"
+            ),
+            Error::EmptyZone => write!(f, "a zone must not be empty"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A [`Result`](std::result::Result) with an [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A builder for [`Resources`].
+///
+/// ```
+/// use tes::v1::types::task::Resources;
+/// use tes::v1::types::task::resources::Size;
+///
+/// let resources = Resources::builder()
+///     .cpu_cores(4)
+///     .ram(Size::gib(8))
+///     .disk(Size::mib(512_000))
+///     .preemptible(true)
+///     .zone("us-central1-a")
+///     .try_build()?;
+///
+/// assert_eq!(resources.ram_gb.unwrap().into_inner(), 8.589934592);
+/// assert_eq!(resources.disk_gb.unwrap().into_inner(), 536.870912);
+/// # Ok::<(), tes::v1::types::task::resources::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Builder {
+    /// The resources being built.
+    resources: Resources,
+}
+
+impl Builder {
+    /// Sets the number of CPU cores.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set number of CPU cores.
+    pub fn cpu_cores(mut self, value: i64) -> Self {
+        self.resources.cpu_cores = Some(value);
+        self
+    }
+
+    /// Sets whether the task prefers to be preemptible.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting.
+    pub fn preemptible(mut self, value: bool) -> Self {
+        self.resources.preemptible = Some(value);
+        self
+    }
+
+    /// Sets the amount of RAM.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set amount of RAM.
+    pub fn ram(mut self, value: Size) -> Self {
+        self.resources.ram_gb = Some(OrderedFloat(value.as_gb()));
+        self
+    }
+
+    /// Sets the amount of disk space.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set amount of disk space.
+    pub fn disk(mut self, value: Size) -> Self {
+        self.resources.disk_gb = Some(OrderedFloat(value.as_gb()));
+        self
+    }
+
+    /// Sets the parameters passed to the backend of the service.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set backend parameters.
+    pub fn backend_parameters(mut self, value: impl Into<BackendParameters>) -> Self {
+        self.resources.backend_parameters = Some(value.into());
+        self
+    }
+
+    /// Sets whether the service must fail the task if it does not support
+    /// one of the backend parameters (rather than ignoring it).
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting.
+    pub fn strict(mut self, value: bool) -> Self {
+        self.resources.backend_parameters_strict = Some(value);
+        self
+    }
+
+    /// Adds a zone in which the task may run.
+    ///
+    /// Zones that were already added are ignored.
+    pub fn zone(mut self, value: impl Into<String>) -> Self {
+        let zones = self.resources.zones.get_or_insert_with(Vec::new);
+        let value = value.into();
+
+        if !zones.contains(&value) {
+            zones.push(value);
+        }
+
+        self
+    }
+
+    /// Consumes `self` to attempt to build [`Resources`].
+    pub fn try_build(self) -> Result<Resources> {
+        if let Some(cores) = self.resources.cpu_cores.filter(|cores| *cores <= 0) {
+            return Err(Error::InvalidCpuCores(cores));
+        }
+
+        if self.resources.zones.iter().flatten().any(String::is_empty) {
+            return Err(Error::EmptyZone);
+        }
+
+        Ok(self.resources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(Size::gb(8).as_gb(), 8.0);
+        assert_eq!(Size::mb(512).as_gb(), 0.512);
+        assert_eq!(Size::mb(1500).as_gb(), 1.5);
+        assert_eq!(Size::gib(1).as_gb(), 1.073741824);
+        assert_eq!(Size::gib(8).as_gb(), 8.589934592);
+        assert_eq!(Size::mib(1).as_gb(), 0.001048576);
+        assert_eq!(Size::mib(1024).as_gb(), 1.073741824);
+        assert_eq!(Size::mib(512_000).as_gb(), 536.870912);
+        assert_eq!(Size::gb(0).as_gb(), 0.0);
+    }
+
+    #[test]
+    fn resources() {
+        let resources = Resources::builder()
+            .cpu_cores(2)
+            .ram(Size::mb(2000))
+            .disk(Size::gb(100))
+            .preemptible(false)
+            .zone("us-central1-a")
+            .zone("us-central1-b")
+            .zone("us-central1-a")
+            .try_build()
+            .unwrap();
+
+        assert_eq!(resources.cpu_cores, Some(2));
+        assert_eq!(resources.ram_gb, Some(OrderedFloat(2.0)));
+        assert_eq!(resources.disk_gb, Some(OrderedFloat(100.0)));
+        assert_eq!(resources.preemptible, Some(false));
+        assert_eq!(resources.zones.unwrap(), ["us-central1-a", "us-central1-b"]);
+
+        assert_eq!(
+            Resources::builder().try_build().unwrap(),
+            Resources::default()
+        );
+    }
+
+    #[test]
+    fn zones() {
+        let mut resources = Resources::default();
+        assert!(resources.zones().is_empty());
+
+        for zone in ["us-east1-c", "us-east1-a", "us-east1-c", "us-east1-b"] {
+            resources.add_zone(zone).unwrap();
+        }
+        assert!(!resources.add_zone("us-east1-a").unwrap());
+        assert!(matches!(resources.add_zone(""), Err(Error::EmptyZone)));
+
+        assert_eq!(
+            resources.zones(),
+            ["us-east1-c", "us-east1-a", "us-east1-b"]
+        );
+        assert_eq!(resources.zones.as_ref().unwrap().len(), 3);
+
+        // NOTE: zones set directly are deduplicated in the view only.
+        resources.zones = Some(
+            ["b", "a", "b", "c", "a"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        );
+        assert_eq!(resources.zones(), ["b", "a", "c"]);
+        assert_eq!(resources.zones.as_ref().unwrap().len(), 5);
+
+        let err = Resources::builder().zone("").try_build().unwrap_err();
+        assert!(matches!(err, Error::EmptyZone));
+        assert_eq!(err.to_string(), "a zone must not be empty");
+    }
+
+    #[test]
+    fn backend_parameters() {
+        let mut parameters = BackendParameters::default();
+        assert_eq!(parameters.insert_str("vm_size", "small"), None);
+        assert_eq!(
+            parameters.insert_str("vm_size", "large").as_deref(),
+            Some("small")
+        );
+        parameters.insert_bool("cleanup", false);
+        parameters.insert_number("ratio", 0.5);
+        parameters.insert_int("count", 3u32);
+
+        assert_eq!(
+            parameters.iter().collect::<Vec<_>>(),
+            [
+                ("cleanup", "false"),
+                ("count", "3"),
+                ("ratio", "0.5"),
+                ("vm_size", "large")
+            ]
+        );
+        assert_eq!(parameters.get_as::<bool>("cleanup"), Some(Ok(false)));
+        assert_eq!(parameters.get_as::<f64>("ratio"), Some(Ok(0.5)));
+        assert!(parameters.get_as::<u32>("vm_size").unwrap().is_err());
+        assert_eq!(parameters.get_as::<u32>("missing"), None);
+
+        assert_eq!(parameters.remove("ratio").as_deref(), Some("0.5"));
+        assert_eq!(parameters.len(), 3);
+
+        // NOTE: large integers are inserted exactly (they are not converted to
+        // floating-point numbers).
+        parameters.insert_int("disk", u64::MAX);
+        assert_eq!(parameters.get_as::<u64>("disk"), Some(Ok(u64::MAX)));
+        parameters.insert_int("offset", -1i64);
+        assert_eq!(parameters.get("offset"), Some("-1"));
+        parameters.insert_int("files", 12usize);
+        assert_eq!(parameters.get("files"), Some("12"));
+        parameters.remove("disk");
+        parameters.remove("offset");
+        parameters.remove("files");
+
+        let map = BTreeMap::from(parameters.clone());
+        assert_eq!(BackendParameters::from(map), parameters);
+
+        let resources = Resources::builder()
+            .backend_parameters(parameters)
+            .strict(true)
+            .try_build()
+            .unwrap();
+        assert_eq!(resources.backend_parameters.unwrap().len(), 3);
+        assert_eq!(resources.backend_parameters_strict, Some(true));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializing_backend_parameters() {
+        // NOTE: resources without backend parameters serialize as before.
+        assert_eq!(
+            serde_json::to_string(&Resources::default()).unwrap(),
+            r#"{"cpu_cores":null,"preemptible":null,"ram_gb":null,"disk_gb":null,"zones":null}"#
+        );
+
+        let mut parameters = BackendParameters::default();
+        parameters.insert_str(backend::microsoft::VM_SIZE, "Standard_D2s_v3");
+        let resources = Resources::builder()
+            .backend_parameters(parameters)
+            .strict(false)
+            .try_build()
+            .unwrap();
+
+        let json = serde_json::to_string(&resources).unwrap();
+        assert_eq!(
+            json,
+            r#"{"cpu_cores":null,"preemptible":null,"ram_gb":null,"disk_gb":null,"zones":null,"backend_parameters":{"vm_size":"Standard_D2s_v3"},"backend_parameters_strict":false}"#
+        );
+        assert_eq!(serde_json::from_str::<Resources>(&json).unwrap(), resources);
+    }
+
+    #[test]
+    fn invalid_cpu_cores() {
+        for cores in [0, -1] {
+            let err = Resources::builder()
+                .cpu_cores(cores)
+                .try_build()
+                .unwrap_err();
+            assert!(matches!(err, Error::InvalidCpuCores(c) if c == cores));
+        }
+
+        assert_eq!(
+            Error::InvalidCpuCores(0).to_string(),
+            "invalid number of CPU cores (0) in a resources builder: the number of CPU cores must \
+             be positive"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Keys of backend parameters understood by well-known implementations.
+//!
+//! Backend parameters are not standardized, so these keys only have an effect
+//! on the implementations they are listed under.
+
+/// Keys understood by Microsoft's TES implementation (TES on Azure).
+pub mod microsoft {
+    /// The size of the virtual machine that runs the task (e.g.,
+    /// `Standard_D2s_v3`).
+    pub const VM_SIZE: &str = "vm_size";
+}
@@ -0,0 +1,29 @@
+//! Tags of tasks.
+//!
+//! Tags are the only metadata of a task that services preserve without
+//! interpreting. The specification does not reserve any names, but using the
+//! conventional names below consistently makes tasks easier to query (e.g.,
+//! with [`ListTasksParams::tag_keys`](crate::v1::types::requests::ListTasksParams::tag_keys)).
+
+/// The maximum length (in bytes) of a tag key accepted by
+/// [`Task::validate()`](super::Task::validate).
+///
+/// The specification does not bound the length of keys, but services
+/// commonly store tags in columns or labels of a limited size.
+pub const MAX_KEY_LENGTH: usize = 255;
+
+/// The conventional tag for the ID of the run (e.g., of a workflow) a task
+/// belongs to.
+pub const RUN_ID: &str = "run_id";
+
+/// The conventional tag for the sample a task processes.
+pub const SAMPLE: &str = "sample";
+
+/// The conventional tag for the owner of a task.
+pub const OWNER: &str = "owner";
+
+/// Returns whether a tag key is valid (not empty and at most
+/// [`MAX_KEY_LENGTH`] bytes long).
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LENGTH
+}
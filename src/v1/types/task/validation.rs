@@ -0,0 +1,923 @@
+//! Structural validation of tasks.
+//!
+//! See [`Task::validate()`].
+
+use crate::v1::types::Task;
+use crate::v1::types::task::executor;
+use crate::v1::types::task::tags;
+
+/// A violation of the structure of a task required by the specification.
+///
+/// Each violation names the offending field (e.g., `executors[1].command`).
+///
+/// Matches on this error should include a wildcard (`_`) arm, as it is
+/// non-exhaustive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required field is empty.
+    Empty {
+        /// The field.
+        field: String,
+    },
+
+    /// A path within the container is not absolute.
+    RelativePath {
+        /// The field.
+        field: String,
+
+        /// The path.
+        path: String,
+    },
+
+    /// A numeric field is negative.
+    Negative {
+        /// The field.
+        field: String,
+    },
+
+    /// The name of an environment variable is empty or not portable (see
+    /// [`executor::is_portable_env_name()`]).
+    InvalidEnvName {
+        /// The field of the environment variables.
+        field: String,
+
+        /// The name.
+        name: String,
+    },
+
+    /// An input has both a URL and content.
+    UrlAndContent {
+        /// The field of the input.
+        field: String,
+    },
+
+    /// An input has neither a URL nor content.
+    MissingSource {
+        /// The field of the input.
+        field: String,
+    },
+
+    /// A volume is the same as, a parent of, or a child of the path of an
+    /// input or an output.
+    ///
+    /// Services handle such nested mounts differently (e.g., the volume may
+    /// shadow the input), so this is only a warning by default (see
+    /// [`Options::mount_conflicts()`]).
+    MountConflict {
+        /// The field of the volume.
+        field: String,
+
+        /// The volume.
+        volume: String,
+
+        /// The field of the path of the input or the output.
+        other: String,
+
+        /// The path of the input or the output.
+        path: String,
+    },
+
+    /// The key of a tag is empty or too long (see
+    /// [`tags::is_valid_key()`]).
+    InvalidTagKey {
+        /// The field of the tags.
+        field: String,
+
+        /// The key.
+        key: String,
+    },
+
+    /// The content of an input is larger than the limit (see
+    /// [`Options::content_limit()`]).
+    ContentTooLarge {
+        /// The field of the content.
+        field: String,
+
+        /// The name of the input (if any).
+        name: Option<String>,
+
+        /// The size of the content (in bytes).
+        size: usize,
+
+        /// The limit (in bytes).
+        limit: usize,
+    },
+
+    /// A zone is listed more than once.
+    DuplicateZone {
+        /// The field of the repeated zone.
+        field: String,
+
+        /// The zone.
+        zone: String,
+    },
+}
+
+impl Error {
+    /// Gets the offending field.
+    pub fn field(&self) -> &str {
+        match self {
+            Error::Empty { field }
+            | Error::RelativePath { field, .. }
+            | Error::Negative { field }
+            | Error::InvalidEnvName { field, .. }
+            | Error::UrlAndContent { field }
+            | Error::MissingSource { field }
+            | Error::MountConflict { field, .. }
+            | Error::InvalidTagKey { field, .. }
+            | Error::ContentTooLarge { field, .. }
+            | Error::DuplicateZone { field, .. } => field,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Empty { field } => write!(f, "`{field}` must not be empty"),
+            Error::RelativePath { field, path } => {
+                write!(
+                    f,
+                    "`{field}` must be an absolute path, but `{path}` is relative"
+                )
+            }
+            Error::Negative { field } => write!(f, "`{field}` must not be negative"),
+            Error::InvalidEnvName { field, name } if name.is_empty() => {
+                write!(f, "`{field}` must not contain an empty name")
+            }
+            Error::InvalidEnvName { field, name } => write!(
+                f,
+                "`{field}` contains the non-portable name `{name}` (expected letters, digits, and \
+                 underscores, not starting with a digit)"
+            ),
+            Error::UrlAndContent { field } => {
+                write!(f, "`{field}` must not have both a URL and content")
+            }
+            Error::MissingSource { field } => {
+                write!(f, "`{field}` must have either a URL or content")
+            }
+            Error::MountConflict {
+                field,
+                volume,
+                other,
+                path,
+            } => write!(
+                f,
+                "`{field}` (`{volume}`) conflicts with the mount path `{path}` of `{other}`"
+            ),
+            Error::InvalidTagKey { field, key } if key.is_empty() => {
+                write!(f, "`{field}` must not contain an empty key")
+            }
+            Error::InvalidTagKey { field, key } => write!(
+                f,
+                "`{field}` contains a key of {len} bytes, which is longer than the maximum of \
+                 {max} bytes",
+                len = key.len(),
+                max = tags::MAX_KEY_LENGTH
+            ),
+            Error::ContentTooLarge {
+                field,
+                name,
+                size,
+                limit,
+            } => {
+                write!(f, "`{field}` ")?;
+
+                if let Some(name) = name {
+                    write!(f, "(of input `{name}`) ")?;
+                }
+
+                write!(
+                    f,
+                    "is {size} bytes, which exceeds the limit of {limit} bytes (consider staging \
+                     the content to storage and using a URL instead)"
+                )
+            }
+            Error::DuplicateZone { field, zone } => {
+                write!(f, "`{field}` repeats the zone `{zone}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// How a kind of violation is reported.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Severity {
+    /// The violation is returned as a warning (and the task is valid).
+    #[default]
+    Warning,
+
+    /// The violation is returned as an error (and the task is invalid).
+    Error,
+}
+
+/// Options for validating a task.
+///
+/// See [`Task::validate_with()`].
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// How mount conflicts are reported.
+    mount_conflicts: Severity,
+
+    /// Whether environment variables may have names that are not portable.
+    any_env_names: bool,
+
+    /// The limit on the content of inputs (in bytes) and how content over the
+    /// limit is reported.
+    content_limit: Option<(usize, Severity)>,
+}
+
+impl Options {
+    /// Sets how volumes that conflict with the paths of inputs or outputs
+    /// ([`Error::MountConflict`]) are reported.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set severity. By default, mount
+    /// conflicts are reported as warnings.
+    pub fn mount_conflicts(mut self, value: Severity) -> Self {
+        self.mount_conflicts = value;
+        self
+    }
+
+    /// Sets whether environment variables may have names that are not
+    /// portable (see [`executor::is_portable_env_name()`]).
+    ///
+    /// This is an escape hatch for services whose backends accept such names.
+    /// Empty names are always rejected.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previous setting. By default, only portable
+    /// names are accepted.
+    pub fn allow_any_env_names(mut self, value: bool) -> Self {
+        self.any_env_names = value;
+        self
+    }
+
+    /// Sets the limit (in bytes) on the content of each input and how content
+    /// over the limit ([`Error::ContentTooLarge`]) is reported.
+    ///
+    /// Services commonly cap the size of request bodies, and large content is
+    /// the usual reason a task exceeds the cap. Reporting oversized content
+    /// as a [`Severity::Warning`] flags the inputs that would be better
+    /// staged to storage without rejecting the task. See
+    /// [`Task::inline_content_size()`] for the size of all of the content.
+    ///
+    /// # Notes
+    ///
+    /// This silently overrides any previously set limit. By default, content
+    /// is not limited.
+    pub fn content_limit(mut self, limit: usize, severity: Severity) -> Self {
+        self.content_limit = Some((limit, severity));
+        self
+    }
+}
+
+/// Returns whether a path is the same as or nested within another path.
+fn is_within(path: &str, parent: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    let parent = parent.trim_end_matches('/');
+
+    match path.strip_prefix(parent) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// The violations found within a task.
+#[derive(Default)]
+struct Violations(Vec<Error>);
+
+impl Violations {
+    /// Records a violation if `value` is empty.
+    fn empty(&mut self, field: impl FnOnce() -> String, value: bool) {
+        if value {
+            self.0.push(Error::Empty { field: field() });
+        }
+    }
+
+    /// Records a violation if `path` is not absolute.
+    fn path(&mut self, field: impl FnOnce() -> String, path: &str) {
+        if !path.starts_with('/') {
+            self.0.push(Error::RelativePath {
+                field: field(),
+                path: path.to_string(),
+            });
+        }
+    }
+
+    /// Records a violation if `value` is negative (or not a number).
+    fn negative(&mut self, field: &str, value: Option<f64>) {
+        if value.is_some_and(|value| value.is_nan() || value < 0.0) {
+            self.0.push(Error::Negative {
+                field: field.to_string(),
+            });
+        }
+    }
+}
+
+impl Task {
+    /// Checks the structure of the task against the requirements of the
+    /// specification.
+    ///
+    /// A task must have at least one executor, each with a non-empty image
+    /// and command (and portable names for its environment variables). Each
+    /// input must have either a URL or content (but not
+    /// both). The paths of the inputs, the outputs, and the volumes (and
+    /// the working directories and the streams of the executors) must be
+    /// absolute, the outputs must have a URL, and the requested resources must
+    /// not be negative.
+    ///
+    /// Every violation is returned (not just the first):
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    ///
+    /// let task = Task {
+    ///     executors: vec![Executor {
+    ///         image: String::from("ubuntu"),
+    ///         ..Default::default()
+    ///     }],
+    ///     volumes: Some(vec![String::from("data")]),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let errors = task.validate().unwrap_err();
+    /// assert_eq!(
+    ///     errors.iter().map(|e| e.field()).collect::<Vec<_>>(),
+    ///     ["executors[0].command", "volumes[0]"]
+    /// );
+    /// ```
+    ///
+    /// Violations that are only warnings by default (see [`Options`]) are not
+    /// returned.
+    pub fn validate(&self) -> Result<(), Vec<Error>> {
+        self.validate_with(&Options::default()).map(|_| ())
+    }
+
+    /// Checks the structure of the task like [`Self::validate()`], but with
+    /// configurable [`Options`].
+    ///
+    /// This additionally checks for volumes that conflict with the paths of
+    /// inputs or outputs ([`Error::MountConflict`]). Violations reported as
+    /// warnings are returned when the task is valid (and dropped otherwise):
+    ///
+    /// ```
+    /// use tes::v1::types::Task;
+    /// use tes::v1::types::task::Executor;
+    /// use tes::v1::types::task::Input;
+    /// use tes::v1::types::task::validation::Options;
+    /// use tes::v1::types::task::validation::Severity;
+    ///
+    /// let task = Task {
+    ///     executors: vec![Executor::new("ubuntu", ["true"])?],
+    ///     inputs: Some(vec![Input::from_url("s3://bucket/ref.fa", "/data/ref.fa")?]),
+    ///     volumes: Some(vec![String::from("/data")]),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let warnings = task.validate_with(&Options::default()).unwrap();
+    /// assert_eq!(warnings.len(), 1);
+    ///
+    /// let options = Options::default().mount_conflicts(Severity::Error);
+    /// assert!(task.validate_with(&options).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate_with(&self, options: &Options) -> Result<Vec<Error>, Vec<Error>> {
+        let mut violations = Violations::default();
+
+        violations.empty(|| String::from("executors"), self.executors.is_empty());
+
+        for (i, executor) in self.executors.iter().enumerate() {
+            violations.empty(
+                || format!("executors[{i}].image"),
+                executor.image.is_empty(),
+            );
+            violations.empty(
+                || format!("executors[{i}].command"),
+                executor.command.is_empty(),
+            );
+
+            for (name, path) in [
+                ("workdir", &executor.workdir),
+                ("stdin", &executor.stdin),
+                ("stdout", &executor.stdout),
+                ("stderr", &executor.stderr),
+            ] {
+                if let Some(path) = path {
+                    violations.path(|| format!("executors[{i}].{name}"), path);
+                }
+            }
+
+            // NOTE: the names are sorted so the violations are deterministic.
+            let mut names = executor
+                .env
+                .iter()
+                .flatten()
+                .map(|(name, _)| name)
+                .filter(|name| {
+                    name.is_empty()
+                        || (!options.any_env_names && !executor::is_portable_env_name(name))
+                })
+                .collect::<Vec<_>>();
+            names.sort();
+
+            for name in names {
+                violations.0.push(Error::InvalidEnvName {
+                    field: format!("executors[{i}].env"),
+                    name: name.clone(),
+                });
+            }
+        }
+
+        for (i, input) in self.inputs.iter().flatten().enumerate() {
+            violations.path(|| format!("inputs[{i}].path"), &input.path);
+
+            match (&input.url, &input.content) {
+                (Some(_), Some(_)) => violations.0.push(Error::UrlAndContent {
+                    field: format!("inputs[{i}]"),
+                }),
+                (None, None) => violations.0.push(Error::MissingSource {
+                    field: format!("inputs[{i}]"),
+                }),
+                _ => {}
+            }
+        }
+
+        for (i, output) in self.outputs.iter().flatten().enumerate() {
+            violations.path(|| format!("outputs[{i}].path"), &output.path);
+            violations.empty(|| format!("outputs[{i}].url"), output.url.is_empty());
+        }
+
+        for (i, volume) in self.volumes.iter().flatten().enumerate() {
+            violations.path(|| format!("volumes[{i}]"), volume);
+        }
+
+        // NOTE: the keys are sorted so the violations are deterministic.
+        let mut keys = self
+            .tags
+            .iter()
+            .flatten()
+            .map(|(key, _)| key)
+            .filter(|key| !tags::is_valid_key(key))
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        for key in keys {
+            violations.0.push(Error::InvalidTagKey {
+                field: String::from("tags"),
+                key: key.clone(),
+            });
+        }
+
+        if let Some(resources) = &self.resources {
+            violations.negative(
+                "resources.cpu_cores",
+                resources.cpu_cores.map(|cores| cores as f64),
+            );
+            violations.negative(
+                "resources.ram_gb",
+                resources.ram_gb.map(|ram| ram.into_inner()),
+            );
+            violations.negative(
+                "resources.disk_gb",
+                resources.disk_gb.map(|disk| disk.into_inner()),
+            );
+
+            let zones = resources.zones.as_deref().unwrap_or_default();
+            for (i, zone) in zones.iter().enumerate() {
+                violations.empty(|| format!("resources.zones[{i}]"), zone.is_empty());
+
+                // NOTE: empty zones are only reported as empty.
+                if !zone.is_empty() && zones[..i].contains(zone) {
+                    violations.0.push(Error::DuplicateZone {
+                        field: format!("resources.zones[{i}]"),
+                        zone: zone.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut warnings = Vec::new();
+
+        if let Some((limit, severity)) = options.content_limit {
+            for (i, input) in self.inputs.iter().flatten().enumerate() {
+                let size = input.content.as_ref().map(String::len).unwrap_or_default();
+                if size <= limit {
+                    continue;
+                }
+
+                let error = Error::ContentTooLarge {
+                    field: format!("inputs[{i}].content"),
+                    name: input.name.clone(),
+                    size,
+                    limit,
+                };
+
+                match severity {
+                    Severity::Warning => warnings.push(error),
+                    Severity::Error => violations.0.push(error),
+                }
+            }
+        }
+
+        let mounts = self
+            .inputs
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, input)| (format!("inputs[{i}].path"), &input.path))
+            .chain(
+                self.outputs
+                    .iter()
+                    .flatten()
+                    .enumerate()
+                    .map(|(i, output)| (format!("outputs[{i}].path"), &output.path)),
+            )
+            .collect::<Vec<_>>();
+
+        for (i, volume) in self.volumes.iter().flatten().enumerate() {
+            for (other, path) in &mounts {
+                if is_within(path, volume) || is_within(volume, path) {
+                    let conflict = Error::MountConflict {
+                        field: format!("volumes[{i}]"),
+                        volume: volume.clone(),
+                        other: other.clone(),
+                        path: path.to_string(),
+                    };
+
+                    match options.mount_conflicts {
+                        Severity::Warning => warnings.push(conflict),
+                        Severity::Error => violations.0.push(conflict),
+                    }
+                }
+            }
+        }
+
+        match violations.0 {
+            errors if errors.is_empty() => Ok(warnings),
+            errors => Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ordered_float::OrderedFloat;
+
+    use super::*;
+    use crate::v1::types::task::Executor;
+    use crate::v1::types::task::Input;
+    use crate::v1::types::task::Output;
+    use crate::v1::types::task::Resources;
+
+    /// Gets the fields of the violations within a task.
+    fn fields(task: &Task) -> Vec<String> {
+        task.validate()
+            .unwrap_err()
+            .iter()
+            .map(|error| error.field().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_tasks() {
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            inputs: Some(vec![Input::from_content("hello", "/inputs/a.txt").unwrap()]),
+            outputs: Some(vec![
+                Output::file("s3://bucket/b.txt", "/outputs/b.txt").unwrap(),
+            ]),
+            volumes: Some(vec![String::from("/scratch")]),
+            resources: Some(Resources {
+                cpu_cores: Some(0),
+                ram_gb: Some(OrderedFloat(1.5)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(task.validate(), Ok(()));
+    }
+
+    #[test]
+    fn executors() {
+        assert_eq!(fields(&Task::default()), ["executors"]);
+
+        let task = Task {
+            executors: vec![
+                Executor::new("ubuntu", ["true"]).unwrap(),
+                Executor {
+                    workdir: Some(String::from("work")),
+                    stdin: Some(String::from("/inputs/stdin.txt")),
+                    stdout: Some(String::from("stdout.txt")),
+                    stderr: Some(String::new()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            fields(&task),
+            [
+                "executors[1].image",
+                "executors[1].command",
+                "executors[1].workdir",
+                "executors[1].stdout",
+                "executors[1].stderr",
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_and_urls() {
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            inputs: Some(vec![
+                Input::from_content("hello", "/inputs/a.txt").unwrap(),
+                Input {
+                    path: String::from("inputs/b.txt"),
+                    url: Some(String::from("s3://bucket/b.txt")),
+                    ..Default::default()
+                },
+            ]),
+            outputs: Some(vec![Output {
+                path: String::from("c.txt"),
+                ..Default::default()
+            }]),
+            volumes: Some(vec![String::from("/scratch"), String::from("data")]),
+            ..Default::default()
+        };
+
+        let errors = task.validate().unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                Error::RelativePath {
+                    field: String::from("inputs[1].path"),
+                    path: String::from("inputs/b.txt"),
+                },
+                Error::RelativePath {
+                    field: String::from("outputs[0].path"),
+                    path: String::from("c.txt"),
+                },
+                Error::Empty {
+                    field: String::from("outputs[0].url"),
+                },
+                Error::RelativePath {
+                    field: String::from("volumes[1]"),
+                    path: String::from("data"),
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "`inputs[1].path` must be an absolute path, but `inputs/b.txt` is relative"
+        );
+    }
+
+    #[test]
+    fn input_sources() {
+        let input = |url: Option<&str>, content: Option<&str>| Input {
+            url: url.map(String::from),
+            path: String::from("/inputs/a.txt"),
+            content: content.map(String::from),
+            ..Default::default()
+        };
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            inputs: Some(vec![
+                input(Some("s3://bucket/a.txt"), None),
+                input(None, Some("hello")),
+                input(Some("s3://bucket/a.txt"), Some("hello")),
+                input(None, None),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            task.validate().unwrap_err(),
+            [
+                Error::UrlAndContent {
+                    field: String::from("inputs[2]"),
+                },
+                Error::MissingSource {
+                    field: String::from("inputs[3]"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn env_names() {
+        let executor = Executor {
+            env: Some(
+                [("HOME", "/root"), ("my-var", "1"), ("", "2"), ("1ST", "3")]
+                    .into_iter()
+                    .map(|(name, value)| (String::from(name), String::from(value)))
+                    .collect(),
+            ),
+            ..Executor::new("ubuntu", ["env"]).unwrap()
+        };
+
+        let task = Task {
+            executors: vec![executor],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            task.validate().unwrap_err(),
+            ["", "1ST", "my-var"]
+                .map(|name| Error::InvalidEnvName {
+                    field: String::from("executors[0].env"),
+                    name: String::from(name),
+                })
+                .to_vec()
+        );
+
+        let options = Options::default().allow_any_env_names(true);
+        assert_eq!(
+            task.validate_with(&options).unwrap_err(),
+            [Error::InvalidEnvName {
+                field: String::from("executors[0].env"),
+                name: String::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn mount_conflicts() {
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            inputs: Some(vec![
+                Input::from_url("s3://bucket/ref.fa", "/data/ref.fa").unwrap(),
+                Input::from_url("s3://bucket/reads", "/reads").unwrap(),
+                Input::from_url("s3://bucket/other", "/database/other").unwrap(),
+            ]),
+            outputs: Some(vec![
+                Output::directory("s3://bucket/out", "/scratch/out/").unwrap(),
+            ]),
+            volumes: Some(vec![
+                String::from("/data"),
+                String::from("/reads/"),
+                String::from("/scratch/out/tmp"),
+                String::from("/tmp"),
+            ]),
+            ..Default::default()
+        };
+
+        let conflicts = task.validate_with(&Options::default()).unwrap();
+        assert_eq!(
+            conflicts
+                .iter()
+                .map(|conflict| match conflict {
+                    Error::MountConflict { field, other, .. } => (field.as_str(), other.as_str()),
+                    conflict => panic!("unexpected violation: {conflict}"),
+                })
+                .collect::<Vec<_>>(),
+            [
+                ("volumes[0]", "inputs[0].path"),
+                ("volumes[1]", "inputs[1].path"),
+                ("volumes[2]", "outputs[0].path"),
+            ]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "`volumes[0]` (`/data`) conflicts with the mount path `/data/ref.fa` of \
+             `inputs[0].path`"
+        );
+        assert_eq!(task.validate(), Ok(()));
+
+        let options = Options::default().mount_conflicts(Severity::Error);
+        assert_eq!(task.validate_with(&options).unwrap_err(), conflicts);
+
+        // NOTE: paths that only share a prefix of their names do not conflict.
+        assert!(!is_within("/database/other", "/data"));
+        assert!(is_within("/data", "/data/"));
+        assert!(is_within("/data/a", "/"));
+    }
+
+    #[test]
+    fn tag_keys() {
+        let long = "k".repeat(tags::MAX_KEY_LENGTH + 1);
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            ..Default::default()
+        }
+        .tag(tags::OWNER, "me")
+        .tag("k".repeat(tags::MAX_KEY_LENGTH), "")
+        .tag(long.clone(), "")
+        .tag("", "empty");
+
+        let errors = task.validate().unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                Error::InvalidTagKey {
+                    field: String::from("tags"),
+                    key: String::new(),
+                },
+                Error::InvalidTagKey {
+                    field: String::from("tags"),
+                    key: long,
+                },
+            ]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "`tags` must not contain an empty key"
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "`tags` contains a key of 256 bytes, which is longer than the maximum of 255 bytes"
+        );
+    }
+
+    #[test]
+    fn content_limits() {
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            inputs: Some(vec![
+                Input::from_content("a".repeat(10), "/inputs/a.txt").unwrap(),
+                Input::from_content("b".repeat(11), "/inputs/b.txt")
+                    .unwrap()
+                    .name("b"),
+                Input::from_url("s3://bucket/c.txt", "/inputs/c.txt").unwrap(),
+                Input::from_content("d".repeat(12), "/inputs/d.txt").unwrap(),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(task.inline_content_size(), 33);
+        assert_eq!(task.validate(), Ok(()));
+
+        let errors = task
+            .validate_with(&Options::default().content_limit(10, Severity::Error))
+            .unwrap_err();
+        assert_eq!(
+            errors.iter().map(|error| error.field()).collect::<Vec<_>>(),
+            ["inputs[1].content", "inputs[3].content"]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "`inputs[1].content` (of input `b`) is 11 bytes, which exceeds the limit of 10 bytes \
+             (consider staging the content to storage and using a URL instead)"
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "`inputs[3].content` is 12 bytes, which exceeds the limit of 10 bytes (consider \
+             staging the content to storage and using a URL instead)"
+        );
+
+        // NOTE: as warnings, oversized content does not invalidate the task.
+        let warnings = task
+            .validate_with(&Options::default().content_limit(10, Severity::Warning))
+            .unwrap();
+        assert_eq!(warnings, errors);
+    }
+
+    #[test]
+    fn resources() {
+        let task = Task {
+            executors: vec![Executor::new("ubuntu", ["true"]).unwrap()],
+            resources: Some(Resources {
+                cpu_cores: Some(-1),
+                ram_gb: Some(OrderedFloat(-0.5)),
+                disk_gb: Some(OrderedFloat(f64::NAN)),
+                zones: Some(
+                    ["us-east1-b", "", "us-east1-c", "us-east1-b", ""]
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            fields(&task),
+            [
+                "resources.cpu_cores",
+                "resources.ram_gb",
+                "resources.disk_gb",
+                "resources.zones[1]",
+                "resources.zones[3]",
+                "resources.zones[4]",
+            ]
+        );
+        assert_eq!(
+            task.validate().unwrap_err()[4].to_string(),
+            "`resources.zones[3]` repeats the zone `us-east1-b`"
+        );
+        assert_eq!(
+            task.validate().unwrap_err()[0].to_string(),
+            "`resources.cpu_cores` must not be negative"
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Tests for the client on `wasm32` targets.
+//!
+//! These tests replace the global `fetch` function with a mock, so they run
+//! under any JavaScript runtime supported by `wasm-bindgen-test` (by default,
+//! Node.js).
+//!
+//! ```shell
+//! CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+//!     cargo test --target wasm32-unknown-unknown --features client-wasm --test wasm
+//! ```
+
+#![cfg(all(target_arch = "wasm32", feature = "client-wasm"))]
+
+use tes::v1::client::Builder;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen(inline_js = r#"
+const requests = [];
+
+export function mock_fetch(body) {
+    requests.length = 0;
+    globalThis.fetch = async (input, init) => {
+        const request = new Request(input, init);
+        requests.push(`${request.method} ${request.url}`);
+        const response = new Response(body, {
+            status: 200,
+            headers: { "content-type": "application/json" },
+        });
+        // NOTE: responses constructed by hand have an empty URL, which the
+        // client expects to be the URL of the request.
+        Object.defineProperty(response, "url", { value: request.url });
+        return response;
+    };
+}
+
+export function requests_made() {
+    return requests.join("\n");
+}
+"#)]
+extern "C" {
+    /// Replaces the global `fetch` with one responding with the given body.
+    fn mock_fetch(body: &str);
+
+    /// Gets the requests made to the mocked `fetch`, one per line.
+    fn requests_made() -> String;
+}
+
+#[wasm_bindgen_test]
+async fn getting_service_info() {
+    mock_fetch(
+        r#"{"id":"org.ga4gh.myservice","name":"My Server","type":{"group":"org.ga4gh","artifact":"tes","version":"1.0.0"},"organization":{"name":"My Organization","url":"https://example.com/"},"version":"1.5.0"}"#,
+    );
+
+    let client = Builder::default()
+        .url_from_string("https://tes.example.com/ga4gh/tes/v1/")
+        .unwrap()
+        .try_build()
+        .unwrap();
+
+    let info = client.service_info().await.unwrap();
+    assert_eq!(info.id(), "org.ga4gh.myservice");
+    assert_eq!(info.name(), "My Server");
+    assert_eq!(info.version(), "1.5.0");
+
+    assert_eq!(
+        requests_made(),
+        "GET https://tes.example.com/ga4gh/tes/v1/service-info"
+    );
+}